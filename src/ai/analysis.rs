@@ -0,0 +1,160 @@
+//! Engine evaluation of the moves of a finished game. Every move is compared with the move
+//! the engine prefers in the same position. Both moves are valued from the point of view of
+//! the player who made them, see `MinmaxPlayer::position_value`, so every seat is analysed the
+//! same way.
+
+use super::prelude::*;
+
+/// Evaluation of one played move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveAnalysis {
+    pub played: Move,
+    pub best_move: (RowType, ColType),
+    pub played_value: i32,
+    pub best_value: i32,
+}
+
+impl MoveAnalysis {
+    /// Houses the move lost compared with the move preferred by the engine.
+    pub fn loss(&self) -> i32 {
+        (self.best_value - self.played_value).max(0)
+    }
+}
+
+/// Analyses the moves of a game one at a time, so the analysis can be spread over frames.
+#[derive(Debug, Clone)]
+pub struct GameAnalyser {
+    pub mistake_threshold: i32,
+    engine: MinmaxPlayer,
+    board: Board,
+    analysis: Vec<MoveAnalysis>,
+}
+
+impl GameAnalyser {
+    /// Analyser of the moves in the history of the board.
+    pub fn new(board: &Board) -> Self {
+        let mut engine = MinmaxPlayer::new().with_evaluator(ChainControl::default());
+        engine.time_limit = ANALYSIS_TIME_LIMIT;
+        let mut board = board.clone();
+        while board.undo().is_some() {}
+
+        Self {
+            mistake_threshold: ANALYSIS_MISTAKE_THRESHOLD,
+            engine,
+            board,
+            analysis: vec![],
+        }
+    }
+
+    pub fn with_engine(mut self, engine: MinmaxPlayer) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.board.get_next_redo().is_none()
+    }
+
+    /// Analyses the next move of the game. Does nothing once all the moves are analysed.
+    pub fn analyse_next(&mut self) {
+        let Some(played) = self.board.get_next_redo().cloned() else {
+            return;
+        };
+        let best_move = self
+            .engine
+            .next_move(&self.board, played.player)
+            .expect("wall of the played move is not clicked");
+        let played_value = self.move_value(played.wall, played.player);
+        let best_value = if best_move == played.wall {
+            played_value
+        } else {
+            self.move_value(best_move, played.player)
+        };
+
+        self.analysis.push(MoveAnalysis {
+            played,
+            best_move,
+            played_value,
+            best_value,
+        });
+        self.board.redo();
+    }
+
+    /// Analyses the moves which are left and returns the analysis of the whole game.
+    pub fn analyse_all(mut self) -> Vec<MoveAnalysis> {
+        while !self.is_finished() {
+            self.analyse_next();
+        }
+        self.analysis
+    }
+
+    /// Analysis of the moves analysed so far.
+    pub fn analysis(&self) -> &[MoveAnalysis] {
+        &self.analysis
+    }
+
+    pub fn is_mistake(&self, move_analysis: &MoveAnalysis) -> bool {
+        move_analysis.loss() > self.mistake_threshold
+    }
+
+    /// Value of the position after `player` clicks the wall, for that player.
+    fn move_value(&self, (row, col): (RowType, ColType), player: Player) -> i32 {
+        let mut board = self.board.clone();
+        let additional_move = board
+            .click_wall(row, col, player)
+            .expect("analysed wall is not clicked");
+        self.engine.position_value(&board, player, additional_move)
+    }
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use super::*;
+    use crate::game::game_match::Match;
+
+    #[test]
+    fn opening_the_chain_instead_of_closing_it_is_a_mistake() {
+        let mut game_match = Match::new(Board::new(3, 1), &[Player::Player1, Player::CPU]);
+        for (row, col) in [(0, 0), (0, 1), (0, 2), (2, 0), (2, 1), (1, 0)] {
+            game_match.play(row, col).expect("wall can be clicked");
+        }
+
+        let analyser = GameAnalyser::new(game_match.board());
+        let last_move = analyser
+            .clone()
+            .analyse_all()
+            .pop()
+            .expect("moves are analysed");
+
+        assert_eq!(Player::CPU, last_move.played.player);
+        assert_eq!(3, last_move.best_value);
+        assert_eq!(-1, last_move.played_value);
+        assert!(analyser.is_mistake(&last_move));
+    }
+
+    #[test]
+    fn every_move_of_the_game_is_analysed() {
+        let mut game_match = Match::new(Board::new(2, 2), &[Player::Player1, Player::CPU]);
+        let greedy_player = GreadyAlgorithmPlayer::default();
+        game_match.play_out(&[&greedy_player, &greedy_player]);
+
+        let mut analyser = GameAnalyser::new(game_match.board());
+        analyser.analyse_next();
+        assert_eq!(1, analyser.analysis().len());
+        let analysis = analyser.analyse_all();
+
+        let played_walls = game_match
+            .board()
+            .get_history()
+            .iter()
+            .map(|played| played.wall)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            played_walls,
+            analysis
+                .iter()
+                .map(|move_analysis| move_analysis.played.wall)
+                .collect::<Vec<_>>()
+        );
+    }
+}
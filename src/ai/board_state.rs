@@ -0,0 +1,373 @@
+use std::sync::Arc;
+
+use super::prelude::*;
+use crate::game::zobrist;
+
+const BITS_PER_WORD: usize = u64::BITS as usize;
+const CELL_WALL_COUNT: usize = 4;
+
+/// Shape of a board. It does not change during a game, so all the states searched by the AI
+/// players share it.
+#[derive(Debug)]
+struct Topology {
+    width: usize,
+    height: usize,
+    mask: Vec<Vec<bool>>,
+    wall_ids: Vec<(RowType, ColType)>,
+    wall_indices: Vec<Vec<Option<usize>>>,
+    wall_cells: Vec<Vec<usize>>,
+    cell_walls: Vec<Vec<usize>>,
+    wall_keys: Vec<u64>,
+    owner_keys: Vec<[u64; PLAYER_COUNT]>,
+}
+
+impl Topology {
+    fn new(board: &Board) -> Self {
+        let mut wall_ids = vec![];
+        let mut wall_indices = vec![];
+        let mut wall_cells = vec![];
+        let mut cell_walls = vec![Vec::with_capacity(CELL_WALL_COUNT); board.width * board.height];
+
+        for row in &board.walls {
+            let mut row_indices = vec![None; row.len()];
+            for wall in row.iter().filter(|wall| wall.exists()) {
+                let idx = wall_ids.len();
+                row_indices[wall.id.1] = Some(idx);
+                let cells = wall
+                    .adjacent_cells
+                    .iter()
+                    .map(|(r, c)| r * board.width + c)
+                    .collect::<Vec<_>>();
+                for cell in &cells {
+                    cell_walls[*cell].push(idx);
+                }
+                wall_ids.push(wall.id);
+                wall_cells.push(cells);
+            }
+            wall_indices.push(row_indices);
+        }
+        let mask = board
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.exists).collect())
+            .collect();
+
+        let wall_keys = wall_ids
+            .iter()
+            .map(|(row, col)| zobrist::wall_key(*row, *col))
+            .collect();
+        let owner_keys = (0..board.width * board.height)
+            .map(|cell| {
+                Player::ALL.map(|player| {
+                    zobrist::owner_key(cell / board.width, cell % board.width, player)
+                })
+            })
+            .collect();
+
+        Self {
+            width: board.width,
+            height: board.height,
+            mask,
+            wall_ids,
+            wall_indices,
+            wall_cells,
+            cell_walls,
+            wall_keys,
+            owner_keys,
+        }
+    }
+}
+
+/// Compact copy of a `Board` used by the AI search. Walls are stored as bits and cells as flat
+/// arrays of counters and owners, so cloning is cheap and a move can be made and unmade in
+/// place with `click_wall` and `unclick_wall`. Walls are addressed by their index, see
+/// `wall_index` and `wall_id`. Walls which are not part of a masked board have no index.
+#[derive(Debug, Clone)]
+pub struct BoardState {
+    topology: Arc<Topology>,
+    walls: Vec<u64>,
+    counters: Vec<u8>,
+    owners: Vec<Option<Player>>,
+    points: [usize; PLAYER_COUNT],
+    unclicked_count: usize,
+    zobrist_key: u64,
+    walls_key: u64,
+}
+
+impl BoardState {
+    pub fn width(&self) -> usize {
+        self.topology.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.topology.height
+    }
+
+    pub fn wall_count(&self) -> usize {
+        self.topology.wall_ids.len()
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.counters.len()
+    }
+
+    pub fn wall_index(&self, row: RowType, col: ColType) -> usize {
+        self.topology.wall_indices[row][col].expect("wall is part of the board")
+    }
+
+    pub fn wall_id(&self, wall: usize) -> (RowType, ColType) {
+        self.topology.wall_ids[wall]
+    }
+
+    pub fn is_clicked(&self, wall: usize) -> bool {
+        self.walls[wall / BITS_PER_WORD] & (1 << (wall % BITS_PER_WORD)) != 0
+    }
+
+    pub fn all_is_clicked(&self) -> bool {
+        self.unclicked_count == 0
+    }
+
+    pub fn unclicked_count(&self) -> usize {
+        self.unclicked_count
+    }
+
+    pub fn unclicked_walls(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.wall_count()).filter(|wall| !self.is_clicked(*wall))
+    }
+
+    /// Cells adjacent to the wall, given as `row * width + col`.
+    pub fn wall_cells(&self, wall: usize) -> &[usize] {
+        &self.topology.wall_cells[wall]
+    }
+
+    pub fn cell_walls(&self, cell: usize) -> &[usize] {
+        &self.topology.cell_walls[cell]
+    }
+
+    /// Tells whether the cell is a part of the board rather than a hole.
+    pub fn cell_exists(&self, cell: usize) -> bool {
+        !self.topology.cell_walls[cell].is_empty()
+    }
+
+    pub fn counter(&self, cell: usize) -> usize {
+        self.counters[cell] as usize
+    }
+
+    pub fn owner(&self, cell: usize) -> Option<Player> {
+        self.owners[cell]
+    }
+
+    pub fn max_adjacent_counter(&self, wall: usize) -> CounterType {
+        self.wall_cells(wall)
+            .iter()
+            .map(|cell| self.counter(*cell))
+            .max()
+            .expect("at least one cell is adjacent to the wall")
+    }
+
+    pub fn get_points(&self, player: Player) -> usize {
+        self.points[player.index()]
+    }
+
+    /// Same key as `Board::get_zobrist_key` of the position.
+    pub fn get_zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
+
+    /// Key of the clicked walls alone. The rest of the game does not depend on who owns the
+    /// houses, so positions with the same key have the same continuations.
+    pub fn get_walls_key(&self) -> u64 {
+        self.walls_key
+    }
+
+    pub fn total_points(&self) -> usize {
+        self.points.iter().sum()
+    }
+
+    /// Clicks an unclicked wall and returns the number of houses built by `player`.
+    pub fn click_wall(&mut self, wall: usize, player: Player) -> usize {
+        debug_assert!(!self.is_clicked(wall), "wall is clicked only once");
+        self.walls[wall / BITS_PER_WORD] |= 1 << (wall % BITS_PER_WORD);
+        self.unclicked_count -= 1;
+        self.zobrist_key ^= self.topology.wall_keys[wall];
+        self.walls_key ^= self.topology.wall_keys[wall];
+
+        let mut houses = 0;
+        for cell in &self.topology.wall_cells[wall] {
+            self.counters[*cell] += 1;
+            if self.counters[*cell] as usize == CELL_WALL_COUNT {
+                self.owners[*cell] = Some(player);
+                self.zobrist_key ^= self.topology.owner_keys[*cell][player.index()];
+                self.points[player.index()] += 1;
+                houses += 1;
+            }
+        }
+        houses
+    }
+
+    /// Reverts `click_wall`, taking the houses next to the wall from their owners.
+    pub fn unclick_wall(&mut self, wall: usize) {
+        debug_assert!(self.is_clicked(wall), "only clicked walls are unclicked");
+        self.walls[wall / BITS_PER_WORD] &= !(1 << (wall % BITS_PER_WORD));
+        self.unclicked_count += 1;
+        self.zobrist_key ^= self.topology.wall_keys[wall];
+        self.walls_key ^= self.topology.wall_keys[wall];
+
+        for cell in &self.topology.wall_cells[wall] {
+            if let Some(owner) = self.owners[*cell].take() {
+                self.zobrist_key ^= self.topology.owner_keys[*cell][owner.index()];
+                self.points[owner.index()] -= 1;
+            }
+            self.counters[*cell] -= 1;
+        }
+    }
+
+    /// Builds a `Board` in the same position. The move history is not restored.
+    pub fn to_board(&self) -> Board {
+        let mut board = Board::with_mask(self.width(), self.height(), &self.topology.mask);
+        for wall in 0..self.wall_count() {
+            if self.is_clicked(wall) {
+                let (row, col) = self.wall_id(wall);
+                board
+                    .set_wall_clicked(row, col)
+                    .expect("wall ids come from the board");
+            }
+        }
+        for (cell, owner) in self.owners.iter().enumerate() {
+            if let Some(player) = owner {
+                board.set_owner(cell / self.width(), cell % self.width(), *player);
+            }
+        }
+        board
+    }
+}
+
+impl From<&Board> for BoardState {
+    fn from(board: &Board) -> Self {
+        let topology = Topology::new(board);
+        let mut walls = vec![0; topology.wall_ids.len().div_ceil(BITS_PER_WORD)];
+        let mut unclicked_count = 0;
+        let mut walls_key = 0;
+        for (idx, (row, col)) in topology.wall_ids.iter().enumerate() {
+            if board.walls[*row][*col].is_clicked {
+                walls[idx / BITS_PER_WORD] |= 1 << (idx % BITS_PER_WORD);
+                walls_key ^= topology.wall_keys[idx];
+            } else {
+                unclicked_count += 1;
+            }
+        }
+        let cells = board.cells.iter().flatten();
+        let counters = cells.clone().map(|cell| cell.counter as u8).collect();
+        let owners = cells.map(|cell| cell.owner).collect();
+
+        Self {
+            topology: Arc::new(topology),
+            walls,
+            counters,
+            owners,
+            points: board.statistics.points,
+            unclicked_count,
+            zobrist_key: board.get_zobrist_key(),
+            walls_key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod board_state_tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn click_and_unclick_restore_the_state() {
+        let board = Board::new(2, 1);
+        let mut state = BoardState::from(&board);
+        let walls = [(0, 0), (1, 0), (2, 0), (0, 1), (1, 2), (2, 1)];
+        for (row, col) in walls {
+            assert_eq!(0, state.click_wall(state.wall_index(row, col), Player::Player1));
+        }
+
+        let middle = state.wall_index(1, 1);
+        assert_eq!(2, state.click_wall(middle, Player::CPU));
+        assert_eq!(2, state.get_points(Player::CPU));
+        assert!(state.all_is_clicked());
+
+        state.unclick_wall(middle);
+
+        assert_eq!(0, state.get_points(Player::CPU));
+        assert_eq!(None, state.owner(0));
+        assert_eq!(3, state.counter(1));
+        assert_eq!(vec![middle], state.unclicked_walls().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn key_is_the_same_as_the_key_of_the_board() {
+        let mut board = Board::new(2, 1);
+        let mut state = BoardState::from(&board);
+        for (row, col) in [(0, 0), (1, 0), (2, 0), (0, 1), (1, 2), (2, 1), (1, 1)] {
+            let _ = board.click_wall(row, col, Player::Player2);
+            state.click_wall(state.wall_index(row, col), Player::Player2);
+            assert_eq!(board.get_zobrist_key(), state.get_zobrist_key());
+        }
+
+        let last = state.wall_index(1, 1);
+        state.unclick_wall(last);
+        let _ = board.undo();
+
+        assert_eq!(board.get_zobrist_key(), state.get_zobrist_key());
+        assert_eq!(board.get_zobrist_key(), state.to_board().get_zobrist_key());
+    }
+
+    #[test]
+    fn state_converts_back_to_the_same_board() {
+        let s = " XXXXX -----
+X     |     X
+X     |     X
+X     |     X
+X     |     X
+X     |     X
+ XXXXX XXXXX
+XCCCCCX     |
+XCCCCCX     |
+XCCCCCX     |
+XCCCCCX     |
+XCCCCCX     |
+ XXXXX -----";
+        let board = Board::from_str(s).expect("board can be parsed");
+
+        let state = BoardState::from(&board);
+        let restored = state.to_board();
+
+        assert_eq!(format!("{board}"), format!("{restored}"));
+        assert_eq!(1, restored.statistics.get_points(Player::CPU));
+        assert_eq!(board.cells[0][1].counter, restored.cells[0][1].counter);
+        assert_eq!(4, state.unclicked_count());
+    }
+
+    #[test]
+    fn walls_around_holes_are_left_out() {
+        let board = Board::with_mask(2, 2, &[vec![true, false], vec![true, true]]);
+        let state = BoardState::from(&board);
+
+        assert_eq!(10, state.wall_count());
+        assert_eq!(10, state.unclicked_count());
+        assert_eq!((0, 0), state.wall_id(state.wall_index(0, 0)));
+        assert_eq!((2, 1), state.wall_id(state.wall_index(2, 1)));
+        assert!(!state.to_board().cells[0][1].exists);
+    }
+
+    #[test]
+    fn walls_beyond_one_word_are_stored() {
+        let board = Board::new(9, 9);
+        let mut state = BoardState::from(&board);
+        let last = state.wall_count() - 1;
+        assert!(last >= BITS_PER_WORD);
+
+        state.click_wall(last, Player::Player2);
+
+        assert!(state.is_clicked(last));
+        assert_eq!(state.wall_id(last), (18, 8));
+        assert_eq!(state.wall_count() - 1, state.unclicked_count());
+    }
+}
@@ -0,0 +1,468 @@
+//! Dots and boxes endgame analysis. Cells with two clicked walls are linked through their
+//! unclicked walls into chains and loops. Clicking any wall of a chain or a loop gives all its
+//! houses away, so once no safe wall is left the players hand the components over one by one.
+//! The player who takes a long component may decline its last two houses (four for a loop)
+//! and keep control, i.e. force the opponent to open the next component.
+
+use super::prelude::*;
+
+const CAPTURABLE_COUNTER: usize = 3;
+const CHAIN_COUNTER: usize = 2;
+const LONG_CHAIN_LENGTH: usize = 3;
+const DECLINED_CHAIN_HOUSES: usize = 2;
+const DECLINED_LOOP_HOUSES: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ComponentKind {
+    Chain,
+    Loop,
+}
+
+/// Chain or loop of cells with two clicked walls. Cells are listed in their order along the
+/// component, chains start at one of their ends.
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub kind: ComponentKind,
+    pub cells: Vec<usize>,
+    pub walls: Vec<usize>,
+}
+
+impl Component {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Long components can be declined by the player who takes them. Every loop is long.
+    pub fn is_long(&self) -> bool {
+        self.kind == ComponentKind::Loop || self.len() >= LONG_CHAIN_LENGTH
+    }
+
+    /// Wall giving the component away. Two cell chains are split in the middle, so the
+    /// opponent cannot decline them, other chains are opened at their end.
+    pub fn sacrifice_wall(&self, state: &BoardState) -> usize {
+        if self.kind == ComponentKind::Chain && self.len() == 2 {
+            return shared_wall(state, self.cells[0], self.cells[1])
+                .expect("cells of a chain are linked");
+        }
+        self.walls[0]
+    }
+}
+
+/// Houses which can be built one after another starting from a cell with three clicked
+/// walls. `walls[i]` builds `cells[i]`. A run is closed when its last wall builds two houses,
+/// which happens when both ends of a chain or an opened loop can be taken.
+#[derive(Debug, Clone)]
+pub struct CaptureRun {
+    pub cells: Vec<usize>,
+    pub walls: Vec<usize>,
+}
+
+impl CaptureRun {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.walls.len() < self.cells.len()
+    }
+
+    /// Wall which leaves the last two houses of the run (four of a closed run) to the
+    /// opponent, or `None` when the run is too short to be declined.
+    pub fn declining_wall(&self) -> Option<usize> {
+        match (self.is_closed(), self.len()) {
+            (false, 2) => Some(self.walls[1]),
+            (true, 4) => Some(self.walls[1]),
+            _ => None,
+        }
+    }
+
+    /// Tells whether the player taking the run may decline its last houses, i.e. the run is a
+    /// loony offer in nimstring terms.
+    pub fn can_be_declined(&self) -> bool {
+        self.len() >= self.declined_houses()
+    }
+
+    pub fn declined_houses(&self) -> usize {
+        if self.is_closed() {
+            DECLINED_LOOP_HOUSES
+        } else {
+            DECLINED_CHAIN_HOUSES
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainAnalysis {
+    pub components: Vec<Component>,
+    pub runs: Vec<CaptureRun>,
+    pub safe_walls: Vec<usize>,
+    unclicked_count: usize,
+    free_cells: usize,
+}
+
+impl ChainAnalysis {
+    pub fn new(state: &BoardState) -> Self {
+        let mut visited = vec![false; state.cell_count()];
+
+        let mut runs = vec![];
+        for cell in 0..state.cell_count() {
+            if state.counter(cell) == CAPTURABLE_COUNTER && !visited[cell] {
+                let run = find_capture_run(state, cell);
+                if run.is_closed() {
+                    // the run is found again from its other end
+                    let last = *run.cells.last().expect("run is not empty");
+                    visited[last] = true;
+                }
+                for cell in &run.cells {
+                    visited[*cell] = true;
+                }
+                runs.push(run);
+            }
+        }
+
+        let mut components = vec![];
+        for cell in 0..state.cell_count() {
+            if state.counter(cell) == CHAIN_COUNTER && !visited[cell] {
+                components.push(find_component(state, cell, &mut visited));
+            }
+        }
+
+        let safe_walls = state
+            .unclicked_walls()
+            .filter(|wall| state.max_adjacent_counter(*wall) < CHAIN_COUNTER)
+            .collect();
+        let free_cells = (0..state.cell_count())
+            .filter(|cell| state.cell_exists(*cell) && state.owner(*cell).is_none())
+            .count();
+
+        Self {
+            components,
+            runs,
+            safe_walls,
+            unclicked_count: state.unclicked_count(),
+            free_cells,
+        }
+    }
+
+    pub fn long_chain_count(&self) -> usize {
+        self.components
+            .iter()
+            .filter(|component| component.kind == ComponentKind::Chain && component.is_long())
+            .count()
+    }
+
+    /// Long chain rule for two players. Every turn but the last ends with a wall which builds
+    /// no house and the player in control declines every long chain but the last one, so the
+    /// number of the remaining turns is known from the long chains. The player to move gets
+    /// control when the number is odd. Loops are declined twice and do not change it.
+    pub fn player_to_move_has_control(&self) -> bool {
+        let declined = self.long_chain_count().saturating_sub(1);
+        // the free cells are subtracted, adding them gives the same parity without underflow
+        let remaining_turns = self.unclicked_count + declined + 1 + self.free_cells;
+        !remaining_turns.is_multiple_of(2)
+    }
+
+    /// Houses the player in control wins over the opponent when the long components are
+    /// handed over, loops first, and all but the last one are declined.
+    pub fn controlled_value(&self) -> isize {
+        let long = self
+            .components
+            .iter()
+            .filter(|component| component.is_long())
+            .collect::<Vec<_>>();
+        if long.is_empty() {
+            return 0;
+        }
+        let houses = long.iter().map(|component| component.len()).sum::<usize>() as isize;
+        let loops = long
+            .iter()
+            .filter(|component| component.kind == ComponentKind::Loop)
+            .count() as isize;
+        let chains = long.len() as isize - loops;
+        let last_declined = if chains > 0 {
+            2 * DECLINED_CHAIN_HOUSES
+        } else {
+            2 * DECLINED_LOOP_HOUSES
+        } as isize;
+        houses
+            - 2 * DECLINED_CHAIN_HOUSES as isize * chains
+            - 2 * DECLINED_LOOP_HOUSES as isize * loops
+            + last_declined
+    }
+
+    pub fn short_chain_count(&self) -> usize {
+        self.components
+            .iter()
+            .filter(|component| !component.is_long())
+            .count()
+    }
+
+    /// Tells whether declining the houses of the last run keeps enough of the remaining
+    /// components to pay off. The short chains are given away first and each of them passes
+    /// the move, so control is only kept when there is an even number of them.
+    pub fn should_decline(&self, run: &CaptureRun) -> bool {
+        run.declining_wall().is_some()
+            && self.short_chain_count().is_multiple_of(2)
+            && self.controlled_value() > run.declined_houses() as isize
+    }
+
+    /// Component the player forced to move gives away: short chains first, then loops and
+    /// long chains, the shortest first.
+    pub fn cheapest_component(&self) -> Option<&Component> {
+        self.components.iter().min_by_key(|component| {
+            (
+                component.is_long(),
+                component.kind == ComponentKind::Chain,
+                component.len(),
+            )
+        })
+    }
+}
+
+fn other_cell(state: &BoardState, wall: usize, cell: usize) -> Option<usize> {
+    state
+        .wall_cells(wall)
+        .iter()
+        .copied()
+        .find(|other| *other != cell)
+}
+
+fn shared_wall(state: &BoardState, cell: usize, other: usize) -> Option<usize> {
+    state
+        .cell_walls(cell)
+        .iter()
+        .copied()
+        .find(|wall| state.wall_cells(*wall).contains(&other))
+}
+
+fn unclicked_cell_walls(state: &BoardState, cell: usize) -> impl Iterator<Item = usize> + '_ {
+    state
+        .cell_walls(cell)
+        .iter()
+        .copied()
+        .filter(|wall| !state.is_clicked(*wall))
+}
+
+pub(super) fn find_capture_run(state: &BoardState, start: usize) -> CaptureRun {
+    let mut run = CaptureRun {
+        cells: vec![start],
+        walls: vec![],
+    };
+    let mut cell = start;
+    let mut previous_wall = None;
+    while let Some(wall) = unclicked_cell_walls(state, cell).find(|w| Some(*w) != previous_wall) {
+        run.walls.push(wall);
+        match other_cell(state, wall, cell) {
+            Some(next) if run.cells.contains(&next) => break,
+            Some(next) if state.counter(next) == CHAIN_COUNTER => {
+                run.cells.push(next);
+                cell = next;
+                previous_wall = Some(wall);
+            }
+            Some(next) if state.counter(next) == CAPTURABLE_COUNTER => {
+                run.cells.push(next);
+                break;
+            }
+            _ => break,
+        }
+    }
+    run
+}
+
+fn is_linked(state: &BoardState, wall: usize, cell: usize, visited: &[bool]) -> bool {
+    other_cell(state, wall, cell)
+        .is_some_and(|other| state.counter(other) == CHAIN_COUNTER && !visited[other])
+}
+
+fn find_component(state: &BoardState, start: usize, visited: &mut [bool]) -> Component {
+    // walk to one end of the chain, or around the loop back to the start
+    let mut end = start;
+    let mut previous_wall = None;
+    let mut kind = ComponentKind::Chain;
+    while let Some((wall, next)) = unclicked_cell_walls(state, end)
+        .filter(|wall| Some(*wall) != previous_wall && is_linked(state, *wall, end, visited))
+        .find_map(|wall| other_cell(state, wall, end).map(|next| (wall, next)))
+    {
+        if next == start {
+            kind = ComponentKind::Loop;
+            break;
+        }
+        end = next;
+        previous_wall = Some(wall);
+    }
+
+    let mut cells = vec![end];
+    let mut walls = vec![];
+    visited[end] = true;
+    let mut cell = end;
+    loop {
+        let mut next_cell = None;
+        for wall in unclicked_cell_walls(state, cell) {
+            if walls.contains(&wall) {
+                continue;
+            }
+            walls.push(wall);
+            if next_cell.is_none() && is_linked(state, wall, cell, visited) {
+                next_cell = other_cell(state, wall, cell);
+            }
+        }
+        match next_cell {
+            Some(next) => {
+                visited[next] = true;
+                cells.push(next);
+                cell = next;
+            }
+            None => break,
+        }
+    }
+    if kind == ComponentKind::Chain {
+        // put the end wall of the first cell first
+        if let Some(pos) = walls.iter().position(|wall| {
+            state
+                .wall_cells(*wall)
+                .iter()
+                .all(|other| *other == end || !cells.contains(other))
+        }) {
+            walls.swap(0, pos);
+        }
+    }
+
+    Component { kind, cells, walls }
+}
+
+#[cfg(test)]
+mod chain_analysis_tests {
+    use super::*;
+
+    fn click_walls(board: &mut Board, walls: &[(usize, usize)]) {
+        for (row, col) in walls {
+            board
+                .click_wall(*row, *col, Player::Player1)
+                .expect("wall can be clicked");
+        }
+    }
+
+    fn horizontal_walls(board: &Board) -> Vec<(usize, usize)> {
+        (0..=board.height)
+            .flat_map(|row| (0..board.width).map(move |col| (2 * row, col)))
+            .collect()
+    }
+
+    #[test]
+    fn chains_are_found() {
+        let mut board = Board::new(3, 2);
+        let walls = horizontal_walls(&board);
+        click_walls(&mut board, &walls);
+        let state = BoardState::from(&board);
+
+        let analysis = ChainAnalysis::new(&state);
+
+        assert_eq!(2, analysis.components.len());
+        for component in &analysis.components {
+            assert_eq!(ComponentKind::Chain, component.kind);
+            assert_eq!(3, component.len());
+            assert_eq!(4, component.walls.len());
+            assert!(component.is_long());
+            let first_wall = state.wall_id(component.walls[0]);
+            assert!(first_wall.1 == 0 || first_wall.1 == 3);
+        }
+        assert!(analysis.safe_walls.is_empty());
+        assert!(analysis.runs.is_empty());
+        assert_eq!(2, analysis.long_chain_count());
+    }
+
+    #[test]
+    fn loops_are_found() {
+        let mut board = Board::new(2, 2);
+        click_walls(
+            &mut board,
+            &[
+                (0, 0),
+                (0, 1),
+                (4, 0),
+                (4, 1),
+                (1, 0),
+                (3, 0),
+                (1, 2),
+                (3, 2),
+            ],
+        );
+
+        let analysis = ChainAnalysis::new(&BoardState::from(&board));
+
+        assert_eq!(1, analysis.components.len());
+        assert_eq!(ComponentKind::Loop, analysis.components[0].kind);
+        assert_eq!(4, analysis.components[0].len());
+        assert_eq!(4, analysis.components[0].walls.len());
+        assert_eq!(0, analysis.long_chain_count());
+    }
+
+    #[test]
+    fn capture_runs_follow_opened_chains() {
+        let mut board = Board::new(3, 1);
+        let walls = horizontal_walls(&board);
+        click_walls(&mut board, &walls);
+        click_walls(&mut board, &[(1, 0)]);
+        let state = BoardState::from(&board);
+
+        let analysis = ChainAnalysis::new(&state);
+
+        assert!(analysis.components.is_empty());
+        assert_eq!(1, analysis.runs.len());
+        let run = &analysis.runs[0];
+        assert_eq!(vec![0, 1, 2], run.cells);
+        assert!(!run.is_closed());
+        assert_eq!(None, run.declining_wall());
+    }
+
+    #[test]
+    fn opened_loop_is_a_closed_run() {
+        let mut board = Board::new(2, 2);
+        click_walls(
+            &mut board,
+            &[
+                (0, 0),
+                (0, 1),
+                (4, 0),
+                (4, 1),
+                (1, 0),
+                (3, 0),
+                (1, 2),
+                (3, 2),
+                (2, 0),
+            ],
+        );
+        let state = BoardState::from(&board);
+
+        let analysis = ChainAnalysis::new(&state);
+
+        assert_eq!(1, analysis.runs.len());
+        let run = &analysis.runs[0];
+        assert!(run.is_closed());
+        assert_eq!(4, run.len());
+        assert_eq!(
+            (2, 1),
+            state.wall_id(run.declining_wall().expect("loop can be declined"))
+        );
+    }
+
+    #[test]
+    fn player_to_move_into_a_single_chain_loses_control() {
+        let mut board = Board::new(3, 1);
+        let walls = horizontal_walls(&board);
+        click_walls(&mut board, &walls);
+
+        let analysis = ChainAnalysis::new(&BoardState::from(&board));
+
+        assert!(!analysis.player_to_move_has_control());
+        assert_eq!(3, analysis.controlled_value());
+    }
+}
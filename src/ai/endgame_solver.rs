@@ -0,0 +1,170 @@
+//! Exact search of small positions. Once few walls are left, every continuation is searched to
+//! the end of the game. The value of a position is the margin of the remaining houses for the
+//! player to move, which does not depend on the houses built so far, so positions are
+//! memoised by their clicked walls alone.
+
+use super::prelude::*;
+
+/// Result of a position when both sides play perfectly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameOutcome {
+    Won,
+    Drawn,
+    Lost,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct EndgameSolver {
+    cache: HashMap<u64, i32>,
+}
+
+impl EndgameSolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tells whether the position is small enough to be solved in time.
+    pub fn can_solve(state: &BoardState) -> bool {
+        state.unclicked_count() <= ENDGAME_SOLVER_WALLS
+    }
+
+    /// Houses the player to move builds from now on minus the houses built by the opponent.
+    pub fn solve(&mut self, state: &mut BoardState) -> i32 {
+        if state.all_is_clicked() {
+            return 0;
+        }
+        let key = state.get_walls_key();
+        if let Some(margin) = self.cache.get(&key) {
+            return *margin;
+        }
+
+        let walls = state.unclicked_walls().collect::<Vec<_>>();
+        let margin = walls
+            .into_iter()
+            .map(|wall| self.solve_move(state, wall))
+            .max()
+            .expect("some wall is not clicked");
+
+        self.cache.insert(key, margin);
+        margin
+    }
+
+    /// Walls giving the best margin of the remaining houses together with the margin.
+    pub fn best_moves(&mut self, state: &mut BoardState) -> (i32, Vec<usize>) {
+        let mut best_margin = i32::MIN;
+        let mut best_moves = vec![];
+        let walls = state.unclicked_walls().collect::<Vec<_>>();
+        for wall in walls {
+            let margin = self.solve_move(state, wall);
+            if margin > best_margin {
+                best_margin = margin;
+                best_moves.clear();
+            }
+            if margin == best_margin {
+                best_moves.push(wall);
+            }
+        }
+        (best_margin, best_moves)
+    }
+
+    /// Margin after the player to move clicks the wall. Who owns the houses does not matter
+    /// to the rest of the game, so they are built by the CPU.
+    fn solve_move(&mut self, state: &mut BoardState, wall: usize) -> i32 {
+        let houses = state.click_wall(wall, Player::CPU) as i32;
+        let margin = if houses > 0 {
+            houses + self.solve(state)
+        } else {
+            -self.solve(state)
+        };
+        state.unclick_wall(wall);
+        margin
+    }
+}
+
+/// Outcome of the game for `player` who is to move, counting the houses built so far and
+/// taking everyone else as the opponent. `None` when too many walls are left to solve the
+/// position.
+pub fn solve_outcome(board: &Board, player: Player) -> Option<GameOutcome> {
+    let mut state = BoardState::from(board);
+    if !EndgameSolver::can_solve(&state) {
+        return None;
+    }
+    let own_points = state.get_points(player) as i32;
+    let other_points = state.total_points() as i32 - own_points;
+    let margin = own_points - other_points + EndgameSolver::new().solve(&mut state);
+
+    Some(match margin.cmp(&0) {
+        std::cmp::Ordering::Greater => GameOutcome::Won,
+        std::cmp::Ordering::Equal => GameOutcome::Drawn,
+        std::cmp::Ordering::Less => GameOutcome::Lost,
+    })
+}
+
+#[cfg(test)]
+mod endgame_solver_tests {
+    use super::*;
+
+    fn board_with_horizontal_walls(width: usize, height: usize) -> Board {
+        let mut board = Board::new(width, height);
+        for row in 0..=height {
+            for col in 0..width {
+                let _ = board.click_wall(2 * row, col, Player::Player1);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn player_opening_the_last_chain_loses_it() {
+        let board = board_with_horizontal_walls(3, 1);
+
+        assert_eq!(
+            -3,
+            EndgameSolver::new().solve(&mut BoardState::from(&board))
+        );
+        assert_eq!(Some(GameOutcome::Lost), solve_outcome(&board, Player::CPU));
+    }
+
+    #[test]
+    fn opponent_keeps_control_of_two_long_chains() {
+        let board = board_with_horizontal_walls(4, 2);
+
+        assert_eq!(
+            -4,
+            EndgameSolver::new().solve(&mut BoardState::from(&board))
+        );
+    }
+
+    #[test]
+    fn houses_built_so_far_are_counted() {
+        let mut board = Board::new(2, 1);
+        for (row, col) in [(0, 0), (2, 0), (1, 0), (0, 1), (2, 1), (1, 1)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+
+        assert_eq!(Some(GameOutcome::Drawn), solve_outcome(&board, Player::CPU));
+        assert_eq!(
+            Some(GameOutcome::Won),
+            solve_outcome(&board, Player::Player1)
+        );
+    }
+
+    #[test]
+    fn house_is_built_when_offered() {
+        let mut board = Board::new(2, 1);
+        for (row, col) in [(0, 0), (1, 0), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        let mut state = BoardState::from(&board);
+
+        let (margin, moves) = EndgameSolver::new().best_moves(&mut state);
+
+        assert_eq!(2, margin);
+        assert_eq!(vec![state.wall_index(1, 1)], moves);
+    }
+
+    #[test]
+    fn big_positions_are_not_solved() {
+        assert_eq!(None, solve_outcome(&Board::new(5, 5), Player::CPU));
+    }
+}
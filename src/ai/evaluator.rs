@@ -0,0 +1,133 @@
+use dyn_clone::DynClone;
+
+use super::prelude::*;
+
+const PARITY_BONUS: i32 = 2;
+
+/// Scores the positions in which the search stops, see `MinmaxPlayer::with_evaluator`.
+pub trait Evaluator: Debug + DynClone + Send {
+    /// Returns how good the position is for `player`, in houses. `is_players_turn` tells
+    /// whether `player` moves next.
+    fn evaluate(&self, state: &BoardState, player: Player, is_players_turn: bool) -> i32;
+}
+
+dyn_clone::clone_trait_object!(Evaluator);
+
+/// Points of the player minus the points of everyone else.
+pub fn score_difference(state: &BoardState, player: Player) -> i32 {
+    2 * state.get_points(player) as i32 - state.total_points() as i32
+}
+
+/// Houses built so far.
+#[derive(Default, Debug, Clone)]
+pub struct ScoreDifference {}
+
+impl Evaluator for ScoreDifference {
+    fn evaluate(&self, state: &BoardState, player: Player, _is_players_turn: bool) -> i32 {
+        score_difference(state, player)
+    }
+}
+
+/// Houses built so far and, once no safe wall is left, the houses won by the player in
+/// control of the endgame, see `ChainAnalysis::controlled_value`. The player to move has to
+/// open a component, so the other player is in control.
+#[derive(Default, Debug, Clone)]
+pub struct ChainControl {}
+
+impl Evaluator for ChainControl {
+    fn evaluate(&self, state: &BoardState, player: Player, is_players_turn: bool) -> i32 {
+        let score = score_difference(state, player);
+        let analysis = ChainAnalysis::new(state);
+        if !analysis.safe_walls.is_empty() || !analysis.runs.is_empty() {
+            return score;
+        }
+        let controlled_value = analysis.controlled_value() as i32;
+        if is_players_turn {
+            score - controlled_value
+        } else {
+            score + controlled_value
+        }
+    }
+}
+
+/// Houses built so far and a bonus for the player who gets control of the endgame by the long
+/// chain rule while safe walls are left.
+#[derive(Default, Debug, Clone)]
+pub struct SafeMoveParity {}
+
+impl Evaluator for SafeMoveParity {
+    fn evaluate(&self, state: &BoardState, player: Player, is_players_turn: bool) -> i32 {
+        let score = score_difference(state, player);
+        let analysis = ChainAnalysis::new(state);
+        if analysis.safe_walls.is_empty() {
+            return score;
+        }
+        if analysis.player_to_move_has_control() == is_players_turn {
+            score + PARITY_BONUS
+        } else {
+            score - PARITY_BONUS
+        }
+    }
+}
+
+#[cfg(test)]
+mod evaluator_tests {
+    use super::*;
+
+    fn board_with_horizontal_walls(width: usize, height: usize) -> Board {
+        let mut board = Board::new(width, height);
+        for row in 0..=height {
+            for col in 0..width {
+                let _ = board.click_wall(2 * row, col, Player::Player1);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn score_difference_counts_everyone_else_as_the_opponent() {
+        let mut board = Board::new(3, 1);
+        for (row, col) in [(0, 0), (1, 0), (2, 0), (1, 1)] {
+            let _ = board.click_wall(row, col, Player::Player2);
+        }
+        let state = BoardState::from(&board);
+
+        assert_eq!(
+            1,
+            ScoreDifference::default().evaluate(&state, Player::Player2, true)
+        );
+        assert_eq!(
+            -1,
+            ScoreDifference::default().evaluate(&state, Player::CPU, false)
+        );
+    }
+
+    #[test]
+    fn player_to_move_into_long_chains_loses_them() {
+        let state = BoardState::from(&board_with_horizontal_walls(3, 2));
+
+        assert_eq!(
+            -2,
+            ChainControl::default().evaluate(&state, Player::Player3, true)
+        );
+        assert_eq!(
+            2,
+            ChainControl::default().evaluate(&state, Player::Player3, false)
+        );
+    }
+
+    #[test]
+    fn parity_is_only_scored_while_safe_walls_are_left() {
+        let chains = BoardState::from(&board_with_horizontal_walls(3, 2));
+        let empty = BoardState::from(&Board::new(1, 1));
+
+        assert_eq!(
+            0,
+            SafeMoveParity::default().evaluate(&chains, Player::Player1, true)
+        );
+        assert_eq!(
+            -SafeMoveParity::default().evaluate(&empty, Player::Player1, true),
+            SafeMoveParity::default().evaluate(&empty, Player::Player1, false)
+        );
+    }
+}
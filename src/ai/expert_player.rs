@@ -0,0 +1,155 @@
+use super::prelude::*;
+
+/// Plays by the dots and boxes strategy, see `chain_analysis`. It takes the houses it is
+/// offered unless declining the last of them keeps control of the endgame, plays safe walls
+/// by the long chain rule and, when it has to give houses away, gives the cheapest chain. The
+/// first moves are taken from the opening book.
+#[derive(Debug, Clone)]
+pub struct ExpertPlayer {
+    /// Takes the first moves from the opening book instead of the strategy.
+    pub opening_book: bool,
+    rng: SeededRng,
+}
+
+impl Default for ExpertPlayer {
+    fn default() -> Self {
+        Self {
+            opening_book: true,
+            rng: SeededRng::default(),
+        }
+    }
+}
+
+impl MoveGenerator for ExpertPlayer {
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        let state = BoardState::from(board);
+        self.rng.start_move(board, player);
+        self.opening_book
+            .then(|| opening_move(&self.rng, board))
+            .flatten()
+            .or_else(|| choose_expert_wall_index(&self.rng, &state).map(|wall| state.wall_id(wall)))
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+}
+
+/// Index of the wall the expert player would click, or `None` when all walls are clicked.
+pub(super) fn choose_expert_wall_index(rng: &SeededRng, state: &BoardState) -> Option<usize> {
+    if state.all_is_clicked() {
+        return None;
+    }
+    let analysis = ChainAnalysis::new(state);
+
+    choose_capture(&analysis)
+        .or_else(|| choose_safe_wall(rng, state, &analysis))
+        .or_else(|| {
+            analysis
+                .cheapest_component()
+                .map(|component| component.sacrifice_wall(state))
+        })
+        .or_else(|| choose_greedy_wall_index(rng, state))
+}
+
+fn choose_capture(analysis: &ChainAnalysis) -> Option<usize> {
+    // the longest run is taken last, so it is the one which may be declined
+    let last_run = analysis.runs.iter().max_by_key(|run| run.len())?;
+    if analysis.runs.len() > 1 {
+        let shortest_run = analysis.runs.iter().min_by_key(|run| run.len())?;
+        return Some(shortest_run.walls[0]);
+    }
+    if analysis.safe_walls.is_empty() && analysis.should_decline(last_run) {
+        return last_run.declining_wall();
+    }
+    Some(last_run.walls[0])
+}
+
+/// Prefers the safe walls after which the opponent does not get control by the long chain
+/// rule.
+fn choose_safe_wall(
+    rng: &SeededRng,
+    state: &BoardState,
+    analysis: &ChainAnalysis,
+) -> Option<usize> {
+    let mut state = state.clone();
+    let winning_walls = analysis
+        .safe_walls
+        .iter()
+        .copied()
+        .filter(|wall| {
+            state.click_wall(*wall, Player::CPU);
+            let opponent_has_control = ChainAnalysis::new(&state).player_to_move_has_control();
+            state.unclick_wall(*wall);
+            !opponent_has_control
+        })
+        .collect::<Vec<_>>();
+
+    rng.choose(&winning_walls)
+        .or_else(|| rng.choose(&analysis.safe_walls))
+        .copied()
+}
+
+#[cfg(test)]
+mod expert_player_tests {
+    use super::*;
+    use crate::game::game_match::Match;
+
+    fn board_with_horizontal_walls(width: usize, height: usize) -> Board {
+        let mut board = Board::new(width, height);
+        for row in 0..=height {
+            for col in 0..width {
+                let _ = board.click_wall(2 * row, col, Player::Player1);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn all_but_two_houses_are_taken_to_keep_control() {
+        let mut board = board_with_horizontal_walls(4, 2);
+        let _ = board.click_wall(1, 0, Player::Player1);
+        let move_generator = ExpertPlayer::default();
+
+        assert_eq!(Some((1, 1)), move_generator.next_move(&board, Player::CPU));
+
+        let _ = board.click_wall(1, 1, Player::CPU);
+        let _ = board.click_wall(1, 2, Player::CPU);
+
+        assert_eq!(Some((1, 4)), move_generator.next_move(&board, Player::CPU));
+    }
+
+    #[test]
+    fn last_chain_is_taken_completely() {
+        let mut board = board_with_horizontal_walls(4, 1);
+        for col in 0..3 {
+            let _ = board.click_wall(1, col, Player::CPU);
+        }
+
+        let next_move = ExpertPlayer::default().next_move(&board, Player::CPU);
+
+        assert_eq!(Some((1, 3)), next_move);
+    }
+
+    #[test]
+    fn two_house_chain_is_given_away_in_the_middle() {
+        let board = board_with_horizontal_walls(2, 2);
+
+        let next_move = ExpertPlayer::default().next_move(&board, Player::CPU);
+
+        assert!(matches!(next_move, Some((1, 1)) | Some((3, 1))));
+    }
+
+    #[test]
+    fn expert_wins_against_gready_player() {
+        let mut points = 0;
+        for _ in 0..3 {
+            let mut game_match = Match::new(Board::new(5, 5), &[Player::Player1, Player::CPU]);
+            let stats =
+                game_match.play_out(&[&GreadyAlgorithmPlayer::default(), &ExpertPlayer::default()]);
+            points += stats.get_points(Player::CPU);
+        }
+
+        assert!(points > 3 * 25 / 2, "{points}");
+    }
+}
@@ -1,39 +1,38 @@
 use super::prelude::*;
 
 #[derive(Default, Debug, Clone)]
-pub struct GreadyAlgorithmPlayer {}
+pub struct GreadyAlgorithmPlayer {
+    rng: SeededRng,
+}
 
 impl MoveGenerator for GreadyAlgorithmPlayer {
-    fn next_move(&self, board: &Board) -> Option<(RowType, ColType)> {
-        if board.all_is_clicked() {
-            return None;
-        }
-        let walls = collect_wall_statistics(board);
-
-        // look for wall adjacent to cell with max counter = 3
-        // if none found look for wall adjacent to cell with max counter = 0 or 1
-        // if none found take random with max counter = 2
-        if let Some(wall_next_to_almost_closed_cell) =
-            choose_wall_index(&walls, |&ws| ws.max_adjacent_counter == 3)
-        {
-            return Some((
-                wall_next_to_almost_closed_cell.row,
-                wall_next_to_almost_closed_cell.col,
-            ));
-        }
-
-        if let Some(wall_next_to_empty_cell) =
-            choose_wall_index(&walls, |&ws| ws.max_adjacent_counter <= 1)
-        {
-            return Some((wall_next_to_empty_cell.row, wall_next_to_empty_cell.col));
-        }
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        let state = BoardState::from(board);
+        self.rng.start_move(board, player);
+        choose_greedy_wall_index(&self.rng, &state).map(|wall| state.wall_id(wall))
+    }
 
-        if let Some(wall) = choose_wall_index(&walls, |&ws| ws.max_adjacent_counter == 2) {
-            return Some((wall.row, wall.col));
-        }
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+}
 
-        unreachable!("We should fill all the cases by now!!!")
+/// Index of the wall the greedy player would click, or `None` when all walls are clicked.
+pub(super) fn choose_greedy_wall_index(rng: &SeededRng, state: &BoardState) -> Option<usize> {
+    if state.all_is_clicked() {
+        return None;
     }
+    let walls = collect_wall_statistics(state);
+
+    // look for wall adjacent to cell with max counter = 3
+    // if none found look for wall adjacent to cell with max counter = 0 or 1
+    // if none found take random with max counter = 2
+    let wall = choose_wall_index(rng, &walls, |&ws| ws.max_adjacent_counter == 3)
+        .or_else(|| choose_wall_index(rng, &walls, |&ws| ws.max_adjacent_counter <= 1))
+        .or_else(|| choose_wall_index(rng, &walls, |&ws| ws.max_adjacent_counter == 2))
+        .expect("We should fill all the cases by now!!!");
+
+    Some(state.wall_index(wall.row, wall.col))
 }
 
 #[cfg(test)]
@@ -56,7 +55,7 @@ mod gready_algorithm_player_tests {
         let gready_algorithm_player = GreadyAlgorithmPlayer::default();
 
         // act
-        let next_move = gready_algorithm_player.next_move(&board);
+        let next_move = gready_algorithm_player.next_move(&board, Player::CPU);
 
         // assert
         assert!(next_move.is_none());
@@ -74,7 +73,7 @@ mod gready_algorithm_player_tests {
         let gready_algorithm_player = GreadyAlgorithmPlayer::default();
 
         // act
-        let next_move = gready_algorithm_player.next_move(&board);
+        let next_move = gready_algorithm_player.next_move(&board, Player::CPU);
 
         // assert
         assert_eq!(next_move, Some((2, 0)));
@@ -101,7 +100,7 @@ mod gready_algorithm_player_tests {
         ];
 
         // act
-        let mut next_move = gready_algorithm_player.next_move(&board);
+        let mut next_move = gready_algorithm_player.next_move(&board, Player::CPU);
 
         // assert
         assert!(valid_moves.contains(&next_move.expect("the move is possible"))); // wall with counter 0
@@ -112,7 +111,7 @@ mod gready_algorithm_player_tests {
             let _ = board.click_wall(row, col, player);
 
             // act
-            next_move = gready_algorithm_player.next_move(&board);
+            next_move = gready_algorithm_player.next_move(&board, Player::CPU);
 
             // assert
             assert!(valid_moves.contains(&next_move.expect("the move is possible")));
@@ -135,7 +134,7 @@ mod gready_algorithm_player_tests {
         let gready_algorithm_player = GreadyAlgorithmPlayer::default();
 
         // act
-        let next_move = gready_algorithm_player.next_move(&board);
+        let next_move = gready_algorithm_player.next_move(&board, Player::CPU);
 
         // assert
         assert!(valid_moves.contains(&next_move.expect("the move is possible")));
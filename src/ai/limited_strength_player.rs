@@ -0,0 +1,314 @@
+//! CPU players with a strength between 0 and 100. Below full strength every move is drawn by
+//! a softmax over shallow evaluations of the walls, so a worse move is less likely the more
+//! houses it loses and the higher the strength is. On big boards only
+//! `STRENGTH_EVALUATED_WALLS` walls are evaluated, the ones which build houses first. A weak player still takes the houses it is
+//! offered most of the time and rather gives away one house than a chain.
+
+use super::prelude::*;
+
+/// Plays the moves of the wrapped move generator, unless the draw picks a move which the
+/// shallow evaluation rates worse than the best one. At `MAX_CPU_STRENGTH` it never does.
+#[derive(Debug, Clone)]
+pub struct LimitedStrengthPlayer {
+    pub strength: usize,
+    move_generator: Box<dyn MoveGenerator>,
+    engine: MinmaxPlayer,
+    rng: SeededRng,
+}
+
+impl LimitedStrengthPlayer {
+    pub fn new(move_generator: Box<dyn MoveGenerator>, strength: usize) -> Self {
+        let mut engine = MinmaxPlayer::new().with_table_memory(STRENGTH_TABLE_MEMORY);
+        engine.max_depth = STRENGTH_EVALUATION_DEPTH;
+        engine.endgame_walls = 0;
+
+        Self {
+            strength: strength.min(MAX_CPU_STRENGTH),
+            move_generator,
+            engine,
+            rng: SeededRng::default(),
+        }
+    }
+
+    /// Temperature of the softmax in houses, zero at full strength.
+    fn temperature(&self) -> f64 {
+        STRENGTH_MAX_TEMPERATURE * (MAX_CPU_STRENGTH - self.strength.min(MAX_CPU_STRENGTH)) as f64
+            / MAX_CPU_STRENGTH as f64
+    }
+
+    /// Draws a move by the values of the walls. Returns `None` when the drawn move is as good as
+    /// the best one, then the wrapped move generator decides.
+    fn mistake(
+        &self,
+        board: &Board,
+        player: Player,
+        values: &[((RowType, ColType), i32)],
+    ) -> Option<(RowType, ColType)> {
+        let best_value = values.iter().map(|(_, value)| *value).max()?;
+        let weights = softmax_weights(
+            &values.iter().map(|(_, value)| *value).collect::<Vec<_>>(),
+            self.temperature(),
+        );
+
+        self.rng.start_move(board, player);
+        let mut draw = self.rng.gen_fraction() * weights.iter().sum::<f64>();
+        for ((wall, value), weight) in values.iter().zip(&weights) {
+            draw -= weight;
+            if draw < 0.0 {
+                return (*value < best_value).then_some(*wall);
+            }
+        }
+        None
+    }
+
+    /// Value of the wall for `player`, after a shallow search of the replies.
+    fn move_value(&self, board: &Board, player: Player, (row, col): (RowType, ColType)) -> i32 {
+        let mut board = board.clone();
+        let additional_move = board
+            .click_wall(row, col, player)
+            .expect("wall is not clicked");
+        self.engine.position_value(&board, player, additional_move)
+    }
+
+    /// Walls to evaluate, at most `STRENGTH_EVALUATED_WALLS`. The walls which build houses come
+    /// first, then the ones which do not give houses away, each group in a random order.
+    fn evaluated_walls(&self, board: &Board, player: Player) -> Vec<(RowType, ColType)> {
+        let state = BoardState::from(board);
+        let mut walls = state.unclicked_walls().collect::<Vec<_>>();
+        if walls.len() <= STRENGTH_EVALUATED_WALLS {
+            return walls.iter().map(|wall| state.wall_id(*wall)).collect();
+        }
+
+        self.rng.start_move(board, player);
+        for idx in (1..walls.len()).rev() {
+            walls.swap(idx, self.rng.gen_range(0, idx + 1));
+        }
+        walls.sort_by_key(|wall| match state.max_adjacent_counter(*wall) {
+            3 => 0,
+            2 => 2,
+            _ => 1,
+        });
+        walls
+            .iter()
+            .take(STRENGTH_EVALUATED_WALLS)
+            .map(|wall| state.wall_id(*wall))
+            .collect()
+    }
+}
+
+/// Weights of the values by the softmax with the given temperature. The best value weighs 1.
+pub fn softmax_weights(values: &[i32], temperature: f64) -> Vec<f64> {
+    let Some(best_value) = values.iter().max() else {
+        return vec![];
+    };
+    values
+        .iter()
+        .map(|value| ((value - best_value) as f64 / temperature).exp())
+        .collect()
+}
+
+impl MoveGenerator for LimitedStrengthPlayer {
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        if self.temperature() <= 0.0 {
+            return self.move_generator.next_move(board, player);
+        }
+        let values = self
+            .evaluated_walls(board, player)
+            .into_iter()
+            .map(|wall| (wall, self.move_value(board, player, wall)))
+            .collect::<Vec<_>>();
+        self.mistake(board, player, &values)
+            .or_else(|| self.move_generator.next_move(board, player))
+    }
+
+    fn start_search(&self, board: &Board, player: Player) -> Box<dyn MoveSearch> {
+        if self.temperature() <= 0.0 {
+            return self.move_generator.start_search(board, player);
+        }
+        Box::new(LimitedStrengthSearch::new(self.clone(), board, player))
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+        self.move_generator.reseed(seed);
+    }
+
+    fn new_game(&mut self) {
+        self.move_generator.new_game();
+        self.engine.new_game();
+    }
+}
+
+/// Search of `LimitedStrengthPlayer` done in steps. Every step evaluates as many walls as fit in
+/// its budget, then the draw either finishes the search with a worse move or hands it over to
+/// the search of the wrapped move generator.
+#[derive(Debug)]
+pub struct LimitedStrengthSearch {
+    strength_player: LimitedStrengthPlayer,
+    board: Board,
+    player: Player,
+    walls: Vec<(RowType, ColType)>,
+    values: Vec<((RowType, ColType), i32)>,
+    mistake: Option<(RowType, ColType)>,
+    search: Option<Box<dyn MoveSearch>>,
+}
+
+impl LimitedStrengthSearch {
+    fn new(strength_player: LimitedStrengthPlayer, board: &Board, player: Player) -> Self {
+        Self {
+            walls: strength_player.evaluated_walls(board, player),
+            strength_player,
+            board: board.clone(),
+            player,
+            values: vec![],
+            mistake: None,
+            search: None,
+        }
+    }
+}
+
+impl MoveSearch for LimitedStrengthSearch {
+    fn step(&mut self, budget: f64) {
+        if let Some(search) = &mut self.search {
+            search.step(budget);
+            return;
+        }
+        if self.mistake.is_some() {
+            return;
+        }
+
+        let step_deadline = ggez::timer::time() + budget;
+        while let Some(wall) = self.walls.get(self.values.len()).copied() {
+            let value = self
+                .strength_player
+                .move_value(&self.board, self.player, wall);
+            self.values.push((wall, value));
+            if ggez::timer::time() >= step_deadline {
+                return;
+            }
+        }
+
+        self.mistake = self
+            .strength_player
+            .mistake(&self.board, self.player, &self.values);
+        if self.mistake.is_none() {
+            self.search = Some(
+                self.strength_player
+                    .move_generator
+                    .start_search(&self.board, self.player),
+            );
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.mistake.is_some()
+            || self
+                .search
+                .as_ref()
+                .is_some_and(|search| search.is_finished())
+    }
+
+    fn best_so_far(&self) -> Option<(RowType, ColType)> {
+        self.mistake.or_else(|| self.search.as_ref()?.best_so_far())
+    }
+}
+
+#[cfg(test)]
+mod limited_strength_player_tests {
+    use super::*;
+    use crate::game::game_match::Match;
+
+    /// Board 3x1 with the middle house offered to the player who moves.
+    fn board_with_offered_house() -> Board {
+        let mut board = Board::new(3, 1);
+        for (row, col) in [(0, 1), (2, 1), (1, 1)] {
+            board
+                .click_wall(row, col, Player::Player1)
+                .expect("wall can be clicked");
+        }
+        board
+    }
+
+    fn mistakes(strength: usize) -> usize {
+        let board = board_with_offered_house();
+        let mut player = LimitedStrengthPlayer::new(Box::new(ExpertPlayer::default()), strength);
+        (0..50)
+            .filter(|seed| {
+                player.reseed(*seed);
+                player.next_move(&board, Player::CPU) != Some((1, 2))
+            })
+            .count()
+    }
+
+    #[test]
+    fn worse_moves_weigh_less() {
+        let weights = softmax_weights(&[0, -1, -3, 0], 1.0);
+
+        assert_eq!(1.0, weights[0]);
+        assert_eq!(1.0, weights[3]);
+        assert!(weights[1] > weights[2]);
+        assert!(weights[2] > 0.0);
+    }
+
+    #[test]
+    fn full_strength_plays_like_the_wrapped_player() {
+        let board = board_with_offered_house();
+        let player = LimitedStrengthPlayer::new(Box::new(ExpertPlayer::default()), 100);
+
+        assert_eq!(Some((1, 2)), player.next_move(&board, Player::CPU));
+        assert_eq!(0, mistakes(MAX_CPU_STRENGTH));
+    }
+
+    #[test]
+    fn weaker_players_make_more_mistakes() {
+        let weak_mistakes = mistakes(0);
+
+        assert!(weak_mistakes > 0);
+        assert!(weak_mistakes > mistakes(70));
+        assert!(weak_mistakes < 50);
+    }
+
+    #[test]
+    fn search_evaluates_the_walls_in_steps() {
+        let board = Board::new(4, 4);
+        for seed in 0..5 {
+            let mut player = LimitedStrengthPlayer::new(Box::new(ExpertPlayer::default()), 0);
+            player.reseed(seed);
+            let mut search = player.start_search(&board, Player::CPU);
+
+            search.step(0.0);
+            assert!(!search.is_finished());
+            while !search.is_finished() {
+                search.step(0.0);
+            }
+
+            assert_eq!(player.next_move(&board, Player::CPU), search.best_so_far());
+        }
+    }
+
+    #[test]
+    fn big_board_evaluates_the_walls_which_build_houses() {
+        let mut board = Board::new(15, 15);
+        for (row, col) in [(0, 7), (2, 7), (1, 7)] {
+            board
+                .click_wall(row, col, Player::Player1)
+                .expect("wall can be clicked");
+        }
+        let player = LimitedStrengthPlayer::new(Box::new(ExpertPlayer::default()), 0);
+
+        let walls = player.evaluated_walls(&board, Player::CPU);
+
+        assert_eq!(STRENGTH_EVALUATED_WALLS, walls.len());
+        assert_eq!((1, 8), walls[0]);
+        assert!(!walls.contains(&(3, 7)));
+    }
+
+    #[test]
+    fn weakest_player_finishes_the_game() {
+        let mut game_match = Match::new(Board::new(4, 4), &[Player::Player1, Player::CPU]);
+        let player = LimitedStrengthPlayer::new(Box::new(ExpertPlayer::default()), 0);
+        game_match.play_out(&[&player, &player]);
+
+        assert!(game_match.board().all_is_clicked());
+    }
+}
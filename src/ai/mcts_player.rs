@@ -0,0 +1,365 @@
+use super::prelude::*;
+
+const WIN_REWARD: f64 = 1.0;
+const TIE_REWARD: f64 = 0.5;
+
+/// How the moves of a playout are chosen after the tree is left.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RolloutPolicy {
+    Random,
+    Greedy,
+}
+
+/// When the search of a move stops: after a number of playouts or after a number of seconds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SearchBudget {
+    Playouts(usize),
+    Time(f64),
+}
+
+#[derive(Debug)]
+struct Node {
+    wall: Option<usize>,
+    player: Player,
+    children: Vec<usize>,
+    untried_walls: Vec<usize>,
+    visits: u32,
+    reward: f64,
+}
+
+impl Node {
+    /// Node reached by `player` clicking `wall`. Its reward is counted for that player.
+    fn new(wall: Option<usize>, player: Player, state: &BoardState) -> Self {
+        Self {
+            wall,
+            player,
+            children: vec![],
+            untried_walls: state.unclicked_walls().collect(),
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+
+    fn upper_confidence_bound(&self, parent_visits: u32, exploration: f64) -> f64 {
+        let visits = f64::from(self.visits);
+        self.reward / visits + exploration * (f64::from(parent_visits).ln() / visits).sqrt()
+    }
+}
+
+/// Monte Carlo tree search with the UCT selection. Every playout walks down the tree, adds one
+/// node and plays the game to the end with the rollout policy. A wall which builds a house
+/// gives the same player another move, like in `MinmaxPlayer`. The most visited move is
+/// played.
+#[derive(Debug, Clone)]
+pub struct MctsPlayer {
+    pub budget: SearchBudget,
+    pub rollout: RolloutPolicy,
+    pub exploration: f64,
+    /// Takes the first moves from the opening book instead of searching.
+    pub opening_book: bool,
+    rng: SeededRng,
+}
+
+impl MctsPlayer {
+    pub fn new(budget: SearchBudget, rollout: RolloutPolicy) -> Self {
+        Self {
+            budget,
+            rollout,
+            exploration: MCTS_EXPLORATION,
+            opening_book: true,
+            rng: SeededRng::default(),
+        }
+    }
+
+    /// Plays a game from the state, where `seats[0]` moves. The turn passes between the two
+    /// seats.
+    fn playout(&self, tree: &mut Vec<Node>, mut state: BoardState, seats: [Player; 2]) {
+        let mut player = seats[0];
+        let mut path = vec![0];
+        let mut node = 0;
+
+        // selection
+        while tree[node].untried_walls.is_empty() && !tree[node].children.is_empty() {
+            let parent_visits = tree[node].visits;
+            node = *tree[node]
+                .children
+                .iter()
+                .max_by(|a, b| {
+                    let a = tree[**a].upper_confidence_bound(parent_visits, self.exploration);
+                    let b = tree[**b].upper_confidence_bound(parent_visits, self.exploration);
+                    a.total_cmp(&b)
+                })
+                .expect("node has children");
+            let wall = tree[node].wall.expect("only the root has no wall");
+            player = next_player(&mut state, wall, player, seats);
+            path.push(node);
+        }
+
+        // expansion
+        if !tree[node].untried_walls.is_empty() {
+            let idx = self.rng.gen_range(0, tree[node].untried_walls.len());
+            let wall = tree[node].untried_walls.swap_remove(idx);
+            let mover = player;
+            player = next_player(&mut state, wall, player, seats);
+            tree.push(Node::new(Some(wall), mover, &state));
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            path.push(child);
+        }
+
+        // simulation
+        while !state.all_is_clicked() {
+            let wall = match self.rollout {
+                RolloutPolicy::Random => {
+                    let walls = state.unclicked_walls().collect::<Vec<_>>();
+                    walls[self.rng.gen_range(0, walls.len())]
+                }
+                RolloutPolicy::Greedy => {
+                    choose_greedy_wall_index(&self.rng, &state).expect("some wall is not clicked")
+                }
+            };
+            player = next_player(&mut state, wall, player, seats);
+        }
+
+        // backpropagation
+        for node in path {
+            tree[node].visits += 1;
+            tree[node].reward += reward(&state, tree[node].player);
+        }
+    }
+}
+
+impl Default for MctsPlayer {
+    fn default() -> Self {
+        Self::new(SearchBudget::Time(MCTS_TIME_LIMIT), RolloutPolicy::Greedy)
+    }
+}
+
+impl MoveGenerator for MctsPlayer {
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        let mut search = self.start_search(board, player);
+        search.step(f64::MAX);
+        search.best_so_far()
+    }
+
+    fn start_search(&self, board: &Board, player: Player) -> Box<dyn MoveSearch> {
+        if self.opening_book {
+            self.rng.start_move(board, player);
+            if let Some(wall) = opening_move(&self.rng, board) {
+                return Box::new(FinishedSearch::new(Some(wall)));
+            }
+        }
+        Box::new(MctsSearch::new(self.clone(), board, player))
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+}
+
+/// Playouts of `MctsPlayer` done in steps. A time budget counts from the start of the search.
+/// The seat which asks for a move plays against everyone else as a single opponent, like in
+/// `MinmaxPlayer`.
+#[derive(Debug)]
+pub struct MctsSearch {
+    mcts_player: MctsPlayer,
+    state: BoardState,
+    seats: [Player; 2],
+    tree: Vec<Node>,
+    playouts: usize,
+    deadline: f64,
+}
+
+impl MctsSearch {
+    fn new(mcts_player: MctsPlayer, board: &Board, player: Player) -> Self {
+        let state = BoardState::from(board);
+        mcts_player.rng.start_move(board, player);
+        let deadline = ggez::timer::time()
+            + match mcts_player.budget {
+                SearchBudget::Time(seconds) => seconds,
+                SearchBudget::Playouts(_) => f64::MAX,
+            };
+        let opponent = Player::ALL
+            .into_iter()
+            .find(|other| *other != player)
+            .expect("there are other players");
+        Self {
+            mcts_player,
+            // the root is reached by the opponent, so its children are the moves of `player`
+            tree: vec![Node::new(None, opponent, &state)],
+            state,
+            seats: [player, opponent],
+            playouts: 0,
+            deadline,
+        }
+    }
+}
+
+impl MoveSearch for MctsSearch {
+    /// Makes at least one playout unless the search is finished.
+    fn step(&mut self, budget: f64) {
+        let step_deadline = ggez::timer::time() + budget;
+        while !self.is_finished() {
+            self.mcts_player
+                .playout(&mut self.tree, self.state.clone(), self.seats);
+            self.playouts += 1;
+            if ggez::timer::time() >= step_deadline {
+                break;
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        if self.state.all_is_clicked() {
+            return true;
+        }
+        match self.mcts_player.budget {
+            SearchBudget::Playouts(count) => self.playouts >= count,
+            SearchBudget::Time(_) => self.playouts > 0 && ggez::timer::time() > self.deadline,
+        }
+    }
+
+    /// The most visited move.
+    fn best_so_far(&self) -> Option<(RowType, ColType)> {
+        if self.state.all_is_clicked() {
+            return None;
+        }
+        self.tree[0]
+            .children
+            .iter()
+            .max_by_key(|child| self.tree[**child].visits)
+            .and_then(|child| self.tree[*child].wall)
+            .or_else(|| available_moves(&self.state).first().copied())
+            .map(|wall| self.state.wall_id(wall))
+    }
+}
+
+/// Clicks the wall and returns the seat to move next.
+fn next_player(state: &mut BoardState, wall: usize, player: Player, seats: [Player; 2]) -> Player {
+    if state.click_wall(wall, player) > 0 {
+        player
+    } else if player == seats[0] {
+        seats[1]
+    } else {
+        seats[0]
+    }
+}
+
+/// Reward of a finished game for the player, everyone else counts as the opponent.
+fn reward(state: &BoardState, player: Player) -> f64 {
+    let own_points = state.get_points(player);
+    let other_points = state.total_points() - own_points;
+    match own_points.cmp(&other_points) {
+        std::cmp::Ordering::Greater => WIN_REWARD,
+        std::cmp::Ordering::Equal => TIE_REWARD,
+        std::cmp::Ordering::Less => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod mcts_player_tests {
+    use super::*;
+    use crate::game::game_match::Match;
+
+    #[test]
+    fn house_is_built_when_offered() {
+        let mut board = Board::new(2, 1);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(1, 0, Player::Player1);
+        let _ = board.click_wall(2, 0, Player::Player1);
+
+        for rollout in [RolloutPolicy::Random, RolloutPolicy::Greedy] {
+            let move_generator = MctsPlayer::new(SearchBudget::Playouts(500), rollout);
+
+            assert_eq!(Some((1, 1)), move_generator.next_move(&board, Player::CPU));
+        }
+    }
+
+    #[test]
+    fn when_all_is_clicked_there_is_no_other_move() {
+        let mut board = Board::new(1, 1);
+        for (row, col) in [(0, 0), (1, 0), (1, 1), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+
+        assert!(MctsPlayer::default()
+            .next_move(&board, Player::CPU)
+            .is_none());
+    }
+
+    #[test]
+    fn gready_vs_mcts() {
+        for seat in [Player::CPU, Player::Player2] {
+            let mut points = 0;
+            for seed in 0..6 {
+                let mut game_match = Match::new(Board::new(3, 3), &[Player::Player1, seat]);
+                let mut gready_player = GreadyAlgorithmPlayer::default();
+                let mut mcts_player =
+                    MctsPlayer::new(SearchBudget::Playouts(2000), RolloutPolicy::Greedy);
+                mcts_player.opening_book = false;
+                gready_player.reseed(seed);
+                mcts_player.reseed(seed);
+                let stats = game_match.play_out(&[&gready_player, &mcts_player]);
+                points += stats.get_points(seat);
+            }
+
+            // MCTS moving second takes more than a third of the 54 houses from either seat
+            assert!(points > 18, "{seat:?}: {points}");
+        }
+    }
+
+    #[test]
+    fn move_is_found_in_time_on_big_board() {
+        let move_generator = MctsPlayer::new(SearchBudget::Time(0.2), RolloutPolicy::Random);
+        let board = Board::new(15, 15);
+
+        let start = ggez::timer::time();
+        let mut search = MctsSearch::new(move_generator, &board, Player::CPU);
+        search.step(f64::MAX);
+
+        // the deadline stopped the search, the bound only catches a search which ignores it
+        assert!(search.best_so_far().is_some());
+        assert!(search.playouts > 0);
+        assert!(ggez::timer::time() > search.deadline);
+        assert!(ggez::timer::time() - start < 30.0);
+    }
+
+    #[test]
+    fn turn_passes_between_the_seat_and_its_opponent() {
+        let board = Board::new(3, 3);
+        let mcts_player = MctsPlayer::new(SearchBudget::Playouts(2000), RolloutPolicy::Greedy);
+
+        let mut search = MctsSearch::new(mcts_player, &board, Player::Player2);
+        search.step(f64::MAX);
+
+        // no house can be built by the first three moves, so the seats take turns
+        let mut nodes = vec![0];
+        for player in [Player::Player2, Player::Player1, Player::Player2] {
+            nodes = nodes
+                .iter()
+                .flat_map(|node| search.tree[*node].children.clone())
+                .collect();
+            assert!(!nodes.is_empty());
+            assert!(nodes.iter().all(|node| search.tree[*node].player == player));
+        }
+    }
+
+    #[test]
+    fn playouts_are_spread_over_the_steps() {
+        let mut board = Board::new(2, 1);
+        for (row, col) in [(0, 0), (1, 0), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        let move_generator = MctsPlayer::new(SearchBudget::Playouts(500), RolloutPolicy::Random);
+
+        let mut search = move_generator.start_search(&board, Player::CPU);
+        let mut steps = 0;
+        while !search.is_finished() {
+            search.step(0.0);
+            steps += 1;
+        }
+
+        assert_eq!(500, steps);
+        assert_eq!(Some((1, 1)), search.best_so_far());
+    }
+}
@@ -1,11 +1,8 @@
-use std::cell::RefCell;
-use std::hash::DefaultHasher;
-use std::hash::Hash;
-use std::hash::Hasher;
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
 
 use super::prelude::*;
-
-type MinmaxCache = std::collections::HashMap<u64, MinmaxBestMoves>;
+use crate::game::zobrist;
 
 #[derive(Default, Debug, Clone)]
 struct MinmaxBestMoves {
@@ -23,62 +20,289 @@ impl MinmaxBestMoves {
 }
 
 #[derive(Debug, Copy, Clone)]
-struct MinmaxParamters<'a> {
-    board: &'a Board,
+struct MinmaxParamters {
     depth: usize,
     is_maximizing_player: bool,
     player: Player,
+    maximizing_player: Player,
+    opponent: Player,
 }
 
-impl<'a> MinmaxParamters<'a> {
-    fn new(board: &'a Board, depth: usize, is_maximizing_player: bool, player: Player) -> Self {
+impl MinmaxParamters {
+    /// Parameters of the root, where `maximizing_player` moves. Everyone else is searched as
+    /// a single `opponent`.
+    fn new(depth: usize, maximizing_player: Player, opponent: Player) -> Self {
         Self {
-            board,
             depth,
-            is_maximizing_player,
-            player,
+            is_maximizing_player: true,
+            player: maximizing_player,
+            maximizing_player,
+            opponent,
         }
     }
+
+    fn next_turn(&self) -> Self {
+        Self {
+            depth: self.depth - 1,
+            is_maximizing_player: !self.is_maximizing_player,
+            player: if self.is_maximizing_player {
+                self.opponent
+            } else {
+                self.maximizing_player
+            },
+            ..*self
+        }
+    }
+}
+
+/// Progress of the iterative deepening of one move, kept between the steps of a search.
+#[derive(Debug, Clone)]
+struct Deepening {
+    state: BoardState,
+    player: Player,
+    opponent: Player,
+    players_turn: bool,
+    next_depth: usize,
+    best: MinmaxBestMoves,
+    finished: bool,
 }
 
-impl<'a> Hash for MinmaxParamters<'a> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        for wall in self.board.walls.iter().flatten() {
-            wall.is_clicked.hash(state);
+impl Deepening {
+    /// Deepening of the position scored for `player`, `players_turn` tells whether `player`
+    /// moves first. Everyone else is searched as a single opponent.
+    fn new(state: BoardState, player: Player, players_turn: bool) -> Self {
+        let opponent = Player::ALL
+            .into_iter()
+            .find(|other| *other != player)
+            .expect("there are other players");
+        Self {
+            state,
+            player,
+            opponent,
+            players_turn,
+            next_depth: 1,
+            best: MinmaxBestMoves::default(),
+            finished: false,
         }
-        for cell in self.board.cells.iter().flatten() {
-            cell.owner.hash(state);
+    }
+
+    fn root_params(&self, depth: usize) -> MinmaxParamters {
+        let params = MinmaxParamters::new(depth, self.player, self.opponent);
+        if self.players_turn {
+            params
+        } else {
+            MinmaxParamters {
+                is_maximizing_player: false,
+                player: self.opponent,
+                ..params
+            }
         }
-        self.depth.hash(state);
-        self.is_maximizing_player.hash(state);
-        self.player.hash(state);
+    }
+
+    /// One of the best moves, picked the same way however often it is asked for.
+    fn best_move(&self, rng: &SeededRng) -> Option<(RowType, ColType)> {
+        rng.start_position(self.state.get_zobrist_key() ^ self.player.index() as u64);
+        choose_wall_index(rng, &self.best.moves, |_| true)
+            .copied()
+            .or_else(|| {
+                available_moves(&self.state)
+                    .first()
+                    .map(|wall| self.state.wall_id(*wall))
+            })
     }
 }
 
+/// Alpha-beta search with iterative deepening. The search goes one turn deeper at a time
+/// until `node_limit` positions are searched, `time_limit` seconds run out, the end of the game
+/// is reached or `max_depth` turns are searched. Only the node limit gives the same moves on
+/// every machine, so by default the time is not limited. The best move of the last completed depth is played, the best moves of the
+/// previous depth are tried first. Positions with at most `endgame_walls` unclicked walls are
+/// solved exactly by `EndgameSolver` instead, the first moves are taken from the opening book
+/// when `opening_book` is set. The seat which asks for a move is searched against everyone
+/// else and the leaves are scored by the `evaluator`.
 #[derive(Debug, Clone)]
 pub struct MinmaxPlayer {
     pub max_depth: usize,
-    cache: RefCell<MinmaxCache>,
+    pub node_limit: usize,
+    pub time_limit: f64,
+    pub endgame_walls: usize,
+    pub opening_book: bool,
+    evaluator: Box<dyn Evaluator>,
+    rng: SeededRng,
+    table: Arc<Mutex<TranspositionTable>>,
+    deadline: Cell<f64>,
+    out_of_time: Cell<bool>,
+    nodes: Cell<usize>,
+    completed_depth: Cell<usize>,
 }
 
 impl MinmaxPlayer {
     pub fn new() -> Self {
         Self {
             max_depth: MAX_MINMAX_DEPTH,
-            cache: RefCell::new(HashMap::new()),
+            node_limit: MINMAX_NODE_LIMIT,
+            time_limit: f64::INFINITY,
+            endgame_walls: ENDGAME_SOLVER_WALLS,
+            opening_book: true,
+            evaluator: Box::new(ScoreDifference::default()),
+            rng: SeededRng::default(),
+            table: new_table(TRANSPOSITION_TABLE_MEMORY),
+            deadline: Cell::new(f64::MAX),
+            out_of_time: Cell::new(false),
+            nodes: Cell::new(0),
+            completed_depth: Cell::new(0),
+        }
+    }
+
+    pub fn with_evaluator(mut self, evaluator: impl Evaluator + 'static) -> Self {
+        self.evaluator = Box::new(evaluator);
+        self.new_game();
+        self
+    }
+
+    /// Limits the transposition table to `memory` bytes, see `TranspositionTable`.
+    pub fn with_table_memory(mut self, memory: usize) -> Self {
+        self.table = new_table(memory);
+        self
+    }
+
+    /// Number of the positions kept in the transposition table.
+    pub fn table_len(&self) -> usize {
+        self.table.lock().map_or(0, |table| table.len())
+    }
+
+    /// Moves of the opening book or of the search which are equally good for `player`.
+    pub fn best_moves(&self, board: &Board, player: Player) -> Vec<(RowType, ColType)> {
+        let deadline = ggez::timer::time() + self.time_limit;
+        let mut deepening = self.start_deepening(board, player);
+        self.deepen(&mut deepening, deadline, deadline);
+        deepening.best.moves
+    }
+
+    /// Looks the position up in the opening book, solves small positions or prepares the
+    /// iterative deepening of the move of `player`.
+    fn start_deepening(&self, board: &Board, player: Player) -> Deepening {
+        let mut deepening = Deepening::new(BoardState::from(board), player, true);
+        self.completed_depth.set(0);
+        self.nodes.set(0);
+        if let Ok(mut table) = self.table.lock() {
+            table.new_search();
+        }
+
+        if let Some(moves) = self
+            .opening_book
+            .then(|| opening_book().lookup(board))
+            .flatten()
+        {
+            deepening.best.moves = moves;
+            deepening.finished = true;
+            return deepening;
         }
+
+        let state = &mut deepening.state;
+        if state.unclicked_count() <= self.endgame_walls {
+            let (_, moves) = EndgameSolver::new().best_moves(state);
+            self.completed_depth.set(state.unclicked_count());
+            deepening.best.moves = moves.iter().map(|wall| state.wall_id(*wall)).collect();
+            deepening.finished = true;
+        }
+        deepening
     }
 
-    fn minmax(&self, params: MinmaxParamters, mut alpha: i32, mut beta: i32) -> MinmaxBestMoves {
-        let hash = hash_state(params);
+    /// Value of the position for `player`, who moves next when `players_turn` is set. Small
+    /// positions get the final margin of `player` over everyone else, the others the score of
+    /// the search within `time_limit`.
+    pub fn position_value(&self, board: &Board, player: Player, players_turn: bool) -> i32 {
+        let mut state = BoardState::from(board);
+        if state.unclicked_count() <= self.endgame_walls {
+            let margin = EndgameSolver::new().solve(&mut state);
+            let remaining_margin = if players_turn { margin } else { -margin };
+            return score_difference(&state, player) + remaining_margin;
+        }
+
+        let mut deepening = Deepening::new(state, player, players_turn);
+        let deadline = ggez::timer::time() + self.time_limit;
+        self.nodes.set(0);
+        self.deepen(&mut deepening, deadline, deadline);
+        deepening.best.score
+    }
 
-        if let Some(value) = self.check_in_cache(hash) {
+    /// Searches one turn deeper at a time until `step_deadline`. A depth interrupted before
+    /// `deadline` is searched again by the next call, the subtrees it completed are taken from
+    /// the transposition table. Only the stored results count towards the node limit, so the
+    /// steps hardly change how far the node limit lets the search go. Once `deadline` passes or
+    /// the node limit is reached the deepening is finished.
+    fn deepen(&self, deepening: &mut Deepening, step_deadline: f64, deadline: f64) {
+        self.deadline.set(step_deadline.min(deadline));
+        self.out_of_time.set(false);
+        let max_depth = self.max_depth.min(deepening.state.unclicked_count());
+
+        while !deepening.finished {
+            if deepening.next_depth > max_depth {
+                deepening.finished = true;
+                break;
+            }
+            let depth = deepening.next_depth;
+            let params = deepening.root_params(depth);
+            let result = self.minmax(&mut deepening.state, params, i32::MIN, i32::MAX);
+            if self.out_of_time.get() {
+                if step_deadline >= deadline || self.nodes.get() >= self.node_limit {
+                    // moves of an interrupted search are only used when nothing else was found
+                    if deepening.best.moves.is_empty() {
+                        deepening.best = result;
+                    }
+                    deepening.finished = true;
+                }
+                break;
+            }
+            #[cfg(feature = "print_debug")]
+            println!("Depth {depth}: score = {}, moves = {:?}", result.score, &result.moves);
+            deepening.best = result;
+            deepening.next_depth += 1;
+            self.completed_depth.set(depth);
+        }
+    }
+
+    /// Depth of the last completed search of `next_move`.
+    pub fn completed_depth(&self) -> usize {
+        self.completed_depth.get()
+    }
+
+    /// Tells whether the search ran out of time or of nodes. Once it does, nothing more is stored
+    /// in the transposition table since the scores are not exact any more.
+    fn is_out_of_time(&self) -> bool {
+        if !self.out_of_time.get()
+            && (self.nodes.get() >= self.node_limit || ggez::timer::time() > self.deadline.get())
+        {
+            self.out_of_time.set(true);
+        }
+        self.out_of_time.get()
+    }
+
+    /// Searches the moves from the given state. Moves are made and unmade in place, so the state
+    /// is the same when the search returns.
+    fn minmax(
+        &self,
+        state: &mut BoardState,
+        params: MinmaxParamters,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> MinmaxBestMoves {
+        let key = table_key(state, params);
+        let window = (alpha, beta);
+
+        if let Some(value) = self.probe(key, params.depth, alpha, beta) {
             return value;
         }
 
-        if params.depth == 0 || params.board.all_is_clicked() {
-            let result = MinmaxBestMoves::new(self.evaluate(params.board), &[]);
-            self.update_cache(hash, &result);
+        if params.depth == 0 || state.all_is_clicked() || self.is_out_of_time() {
+            let score = self.evaluator.evaluate(
+                state,
+                params.maximizing_player,
+                params.is_maximizing_player,
+            );
+            let result = MinmaxBestMoves::new(score, &[]);
+            self.store(key, params.depth, Bound::Exact, &result);
             return result;
         }
 
@@ -89,39 +313,16 @@ impl MinmaxPlayer {
         };
         let mut best_moves = vec![];
 
-        for (row, col) in available_moves(params.board) {
-            let mut new_board = params.board.clone();
-            let additional_move = new_board
-                .click_wall(row, col, params.player)
-                .expect("the wall was not clicked twice");
+        for wall in self.ordered_moves(state, params) {
+            let additional_move = state.click_wall(wall, params.player) > 0;
 
             let cur_best = if additional_move {
-                self.minmax(
-                    MinmaxParamters::new(
-                        &new_board,
-                        params.depth,
-                        params.is_maximizing_player,
-                        params.player,
-                    ),
-                    alpha,
-                    beta,
-                )
+                self.minmax(state, params, alpha, beta)
             } else {
-                self.minmax(
-                    MinmaxParamters::new(
-                        &new_board,
-                        params.depth - 1,
-                        !params.is_maximizing_player,
-                        params.player.opponent(),
-                    ),
-                    alpha,
-                    beta,
-                )
+                self.minmax(state, params.next_turn(), alpha, beta)
             };
-            #[cfg(feature = "print_debug")]
-            if depth == self.max_depth {
-                println!("Evaluating move ({row}, {col}): score = {score}");
-            }
+            state.unclick_wall(wall);
+            let (row, col) = state.wall_id(wall);
 
             if cur_best.score == best_score {
                 best_moves.push((row, col));
@@ -147,28 +348,52 @@ impl MinmaxPlayer {
         }
 
         let result = MinmaxBestMoves::new(best_score, &best_moves);
-        self.update_cache(hash, &result);
+        let bound = Bound::of(best_score, window.0, window.1);
+        self.store(key, params.depth, bound, &result);
         result
     }
 
-    fn check_in_cache(&self, hash: u64) -> Option<MinmaxBestMoves> {
-        if let Ok(cache) = self.cache.try_borrow() {
-            if let Some(result) = cache.get(&hash) {
-                return Some(result.clone());
+    /// Available moves with the best moves found by an earlier search of the position first.
+    fn ordered_moves(&self, state: &BoardState, params: MinmaxParamters) -> Vec<usize> {
+        let mut moves = available_moves(state);
+        if params.depth <= 1 {
+            return moves;
+        }
+        let previous = self.table.lock().ok().and_then(|table| {
+            table
+                .probe(table_key(state, params))
+                .map(|entry| entry.moves.clone())
+        });
+        if let Some(previous) = previous {
+            for (row, col) in previous.iter().rev() {
+                let wall = state.wall_index(*row, *col);
+                if let Some(pos) = moves.iter().position(|m| *m == wall) {
+                    moves[..=pos].rotate_right(1);
+                }
             }
         }
-        None
+        moves
     }
 
-    fn update_cache(&self, hash: u64, value: &MinmaxBestMoves) {
-        if let Ok(mut cache) = self.cache.try_borrow_mut() {
-            cache.insert(hash, value.clone());
-        }
+    /// Result stored in the transposition table when it decides the search of the node.
+    fn probe(&self, key: u64, depth: usize, alpha: i32, beta: i32) -> Option<MinmaxBestMoves> {
+        let table = self.table.lock().ok()?;
+        table
+            .probe(key)
+            .filter(|entry| entry.decides(depth, alpha, beta))
+            .map(|entry| MinmaxBestMoves::new(entry.score, &entry.moves))
     }
 
-    fn evaluate(&self, board: &Board) -> i32 {
-        let stats = board.get_statistics();
-        stats.cpu_points as i32 - stats.player1_points as i32
+    /// Keeps the result of a completed node, which counts towards `node_limit`. Results of a
+    /// search which ran out are neither kept nor counted.
+    fn store(&self, key: u64, depth: usize, bound: Bound, value: &MinmaxBestMoves) {
+        if self.out_of_time.get() {
+            return;
+        }
+        self.nodes.set(self.nodes.get() + 1);
+        if let Ok(mut table) = self.table.lock() {
+            table.store(key, depth, value.score, bound, &value.moves);
+        }
     }
 }
 
@@ -179,22 +404,77 @@ impl Default for MinmaxPlayer {
 }
 
 impl MoveGenerator for MinmaxPlayer {
-    fn next_move(&self, board: &Board) -> Option<(RowType, ColType)> {
-        let best = self.minmax(
-            MinmaxParamters::new(board, self.max_depth, true, Player::CPU),
-            i32::MIN,
-            i32::MAX,
-        );
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        let deadline = ggez::timer::time() + self.time_limit;
+        let mut deepening = self.start_deepening(board, player);
+        self.deepen(&mut deepening, deadline, deadline);
         #[cfg(feature = "print_debug")]
-        println!("Best move: {:?}", &best.moves);
-        choose_wall_index(&best.moves, |_| true).copied()
+        println!("Best move: {:?}", &deepening.best.moves);
+        deepening.best_move(&self.rng)
+    }
+
+    fn start_search(&self, board: &Board, player: Player) -> Box<dyn MoveSearch> {
+        let minmax_player = self.clone();
+        let deepening = minmax_player.start_deepening(board, player);
+        Box::new(MinmaxSearch {
+            deadline: ggez::timer::time() + self.time_limit,
+            minmax_player,
+            deepening,
+        })
     }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+
+    /// Copies of the player share the transposition table, the new game gets a table of its own.
+    fn new_game(&mut self) {
+        let memory = self
+            .table
+            .lock()
+            .map_or(TRANSPOSITION_TABLE_MEMORY, |table| table.memory());
+        self.table = new_table(memory);
+    }
+}
+
+/// Iterative deepening of `MinmaxPlayer` done in steps. The time limit counts from the start of
+/// the search, the steps only decide how much of it is spent in one go.
+#[derive(Debug)]
+pub struct MinmaxSearch {
+    minmax_player: MinmaxPlayer,
+    deepening: Deepening,
+    deadline: f64,
+}
+
+impl MoveSearch for MinmaxSearch {
+    fn step(&mut self, budget: f64) {
+        if !self.deepening.finished {
+            let step_deadline = ggez::timer::time() + budget;
+            self.minmax_player
+                .deepen(&mut self.deepening, step_deadline, self.deadline);
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.deepening.finished
+    }
+
+    fn best_so_far(&self) -> Option<(RowType, ColType)> {
+        self.deepening.best_move(&self.minmax_player.rng)
+    }
+}
+
+fn new_table(memory: usize) -> Arc<Mutex<TranspositionTable>> {
+    Arc::new(Mutex::new(TranspositionTable::new(memory)))
 }
 
-fn hash_state(params: MinmaxParamters<'_>) -> u64 {
-    let hasher = &mut DefaultHasher::new();
-    params.hash(hasher);
-    hasher.finish()
+/// Transposition table key of the node: the Zobrist key of the position mixed with the seats of
+/// the search. The depth is kept in the entry, so deeper results serve shallower searches.
+fn table_key(state: &BoardState, params: MinmaxParamters) -> u64 {
+    let params_key = ((params.is_maximizing_player as u64) << 12)
+        | ((params.player.index() as u64) << 4)
+        | params.maximizing_player.index() as u64;
+    state.get_zobrist_key() ^ zobrist::mix(params_key)
 }
 
 #[cfg(test)]
@@ -202,11 +482,13 @@ mod minmax_player_tests {
     use std::str::FromStr;
 
     use super::*;
+    use crate::game::game_match::Match;
 
     #[test]
     fn gready_vs_minmax() {
         let sample_move_generator = GreadyAlgorithmPlayer::default();
-        let tested_move_generator = MinmaxPlayer::new();
+        let mut tested_move_generator = MinmaxPlayer::new();
+        tested_move_generator.node_limit = 100_000;
 
         let stats = play_game(sample_move_generator, tested_move_generator);
         assert_eq!(Some(Player::CPU), stats.winner);
@@ -215,12 +497,289 @@ mod minmax_player_tests {
     #[test]
     fn region_vs_minmax() {
         let sample_move_generator = RegionCountingPlayer::default();
-        let tested_move_generator = MinmaxPlayer::new();
+        let mut tested_move_generator = MinmaxPlayer::new();
+        tested_move_generator.node_limit = 100_000;
 
         let stats = play_game(sample_move_generator, tested_move_generator);
         assert_eq!(Some(Player::CPU), stats.winner);
     }
 
+    #[test]
+    fn minmax_plays_for_any_seat() {
+        let mut minmax_player = MinmaxPlayer::new();
+        minmax_player.max_depth = 3;
+        let mut game_match = Match::new(Board::new(4, 4), &[Player::Player2, Player::Player3]);
+
+        let stats = game_match.play_out(&[&GreadyAlgorithmPlayer::default(), &minmax_player]);
+
+        assert_eq!(Some(Player::Player3), stats.winner);
+    }
+
+    #[test]
+    fn evaluator_can_be_chosen() {
+        let minmax_players = [
+            MinmaxPlayer::new().with_evaluator(ChainControl::default()),
+            MinmaxPlayer::new().with_evaluator(SafeMoveParity::default()),
+        ];
+        for mut minmax_player in minmax_players {
+            minmax_player.max_depth = 3;
+
+            let stats = play_game(GreadyAlgorithmPlayer::default(), minmax_player);
+
+            assert_eq!(Some(Player::CPU), stats.winner);
+        }
+    }
+
+    #[test]
+    fn position_is_valued_for_either_side_to_move() {
+        let mut board = Board::new(3, 1);
+        for col in 0..3 {
+            let _ = board.click_wall(0, col, Player::Player1);
+            let _ = board.click_wall(2, col, Player::Player1);
+        }
+        let mut minmax_player = MinmaxPlayer::new();
+
+        assert_eq!(-3, minmax_player.position_value(&board, Player::CPU, true));
+        assert_eq!(3, minmax_player.position_value(&board, Player::CPU, false));
+
+        minmax_player.endgame_walls = 0;
+        minmax_player.max_depth = 1;
+        let _ = board.click_wall(1, 0, Player::Player1);
+
+        assert_eq!(3, minmax_player.position_value(&board, Player::CPU, true));
+        assert_eq!(-3, minmax_player.position_value(&board, Player::CPU, false));
+    }
+
+    #[test]
+    fn move_is_found_in_time_on_big_board() {
+        let mut move_generator = MinmaxPlayer::new();
+        move_generator.node_limit = 50_000;
+        let board = Board::new(15, 15);
+
+        let start = ggez::timer::time();
+        let next_move = move_generator.next_move(&board, Player::CPU);
+
+        // the node limit stopped the search, the bound only catches a search which ignores it
+        assert!(next_move.is_some());
+        assert!(move_generator.out_of_time.get());
+        assert!(move_generator.completed_depth() < move_generator.max_depth);
+        assert!(ggez::timer::time() - start < 30.0);
+    }
+
+    #[test]
+    fn small_board_is_searched_to_the_end() {
+        let move_generator = MinmaxPlayer::new();
+        let board = Board::new(2, 2);
+
+        let next_move = move_generator.next_move(&board, Player::CPU);
+
+        assert!(next_move.is_some());
+        assert_eq!(12, move_generator.completed_depth());
+    }
+
+    #[test]
+    fn endgame_is_solved_to_keep_control() {
+        let mut board = Board::new(4, 2);
+        for row in [0, 2, 4] {
+            for col in 0..4 {
+                let _ = board.click_wall(row, col, Player::Player1);
+            }
+        }
+        for (row, col) in [(1, 0), (1, 1), (1, 2)] {
+            let _ = board.click_wall(row, col, Player::CPU);
+        }
+
+        let next_move = MinmaxPlayer::new().next_move(&board, Player::CPU);
+
+        assert_eq!(Some((1, 4)), next_move);
+    }
+
+    #[test]
+    fn interrupted_search_keeps_the_last_completed_depth() {
+        let mut move_generator = MinmaxPlayer::new();
+        move_generator.node_limit = 100_000;
+        move_generator.opening_book = false;
+        let board = Board::new(6, 6);
+
+        let next_move = move_generator.next_move(&board, Player::CPU);
+        let remaining_walls = BoardState::from(&board).unclicked_count();
+
+        assert!(next_move.is_some());
+        assert!(move_generator.out_of_time.get());
+        assert!(move_generator.completed_depth() >= 1);
+        assert!(move_generator.completed_depth() < move_generator.max_depth);
+        assert!(move_generator.completed_depth() < remaining_walls);
+    }
+
+    #[test]
+    fn first_move_is_taken_from_the_opening_book() {
+        let mut board = Board::new(3, 3);
+        let _ = board.click_wall(4, 1, Player::Player1);
+        let book_moves = opening_book()
+            .lookup(&board)
+            .expect("position is in the book");
+
+        let next_move = MinmaxPlayer::new().next_move(&board, Player::CPU);
+
+        assert!(book_moves.contains(&next_move.expect("move is found")));
+    }
+
+    #[test]
+    fn scores_do_not_depend_on_the_table() {
+        let mut board = Board::new(3, 3);
+        for (row, col) in [(0, 0), (1, 1), (2, 2), (3, 0), (5, 3), (6, 1)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        let mut minmax_player = MinmaxPlayer::new();
+        minmax_player.max_depth = 5;
+        minmax_player.endgame_walls = 0;
+        let small_table = minmax_player.clone().with_table_memory(0);
+        let expected = minmax_player.position_value(&board, Player::CPU, true);
+
+        // searches of the following positions leave bounds in the table
+        minmax_player.max_depth = 4;
+        for (row, col) in [(0, 1), (4, 1), (6, 2), (2, 0), (4, 3)] {
+            let mut next = board.clone();
+            let _ = next.click_wall(row, col, Player::CPU);
+            minmax_player.position_value(&next, Player::CPU, false);
+        }
+        minmax_player.max_depth = 5;
+
+        assert_eq!(
+            expected,
+            minmax_player.position_value(&board, Player::CPU, true)
+        );
+        assert_eq!(
+            expected,
+            small_table.position_value(&board, Player::CPU, true)
+        );
+    }
+
+    #[test]
+    fn cut_off_search_is_not_taken_as_exact() {
+        let mut board = Board::new(3, 3);
+        for (row, col) in [(6, 2), (5, 1), (6, 1), (2, 0), (1, 1), (4, 2)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        let mut state = BoardState::from(&board);
+        let params = Deepening::new(state.clone(), Player::CPU, true).root_params(4);
+        let mut minmax_player = MinmaxPlayer::new();
+        minmax_player.endgame_walls = 0;
+        let fresh = minmax_player.clone().with_table_memory(1 << 20);
+        let mut expected = fresh.minmax(&mut state, params, i32::MIN, i32::MAX);
+        let tested = minmax_player.with_table_memory(1 << 20);
+
+        // the window is below the value, so the search stops at the first good move
+        let bound = expected.score - 1;
+        let cut_off = tested.minmax(&mut state, params, bound, bound);
+        let mut result = tested.minmax(&mut state, params, i32::MIN, i32::MAX);
+        expected.moves.sort();
+        result.moves.sort();
+
+        assert_eq!(1, cut_off.moves.len());
+        assert_eq!(expected.score, result.score);
+        assert_eq!(expected.moves, result.moves);
+    }
+
+    #[test]
+    fn table_is_shared_by_copies_until_new_game() {
+        let mut minmax_player = MinmaxPlayer::new();
+        minmax_player.max_depth = 3;
+        minmax_player.opening_book = false;
+        let copy = minmax_player.clone();
+
+        copy.next_move(&Board::new(4, 4), Player::CPU);
+
+        assert!(minmax_player.table_len() > 0);
+        minmax_player.new_game();
+        assert_eq!(0, minmax_player.table_len());
+        assert!(copy.table_len() > 0);
+    }
+
+    #[test]
+    fn hard_game_is_replayed_from_the_seed() {
+        let mut minmax_player = MinmaxPlayer::default();
+        minmax_player.reseed(5);
+        let mut play = || {
+            minmax_player.new_game();
+            let mut game_match = Match::new(Board::new(3, 3), &[Player::Player1, Player::CPU]);
+            game_match.play_out(&[&RegionCountingPlayer::default(), &minmax_player]);
+            game_match.board().get_history().to_vec()
+        };
+
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn steps_do_not_change_the_move() {
+        let mut board = Board::new(4, 4);
+        for (row, col) in [(0, 0), (3, 2), (8, 1)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        let mut move_generator = MinmaxPlayer::new();
+        move_generator.node_limit = 200_000;
+        move_generator.opening_book = false;
+        move_generator.reseed(7);
+
+        // a copy with a table of its own searches the same as the player does
+        let stepped_player = move_generator
+            .clone()
+            .with_table_memory(TRANSPOSITION_TABLE_MEMORY);
+        let mut search = MinmaxSearch {
+            deepening: stepped_player.start_deepening(&board, Player::CPU),
+            minmax_player: stepped_player,
+            deadline: f64::INFINITY,
+        };
+        let mut steps = 0;
+        while !search.is_finished() {
+            search.step(0.001);
+            steps += 1;
+        }
+        let next_move = move_generator.next_move(&board, Player::CPU);
+
+        assert!(steps > 1, "{steps}");
+        assert_eq!(next_move, search.best_so_far());
+        assert_eq!(
+            move_generator.completed_depth(),
+            search.minmax_player.completed_depth()
+        );
+    }
+
+    #[test]
+    fn search_in_steps_spans_the_node_limit() {
+        let mut move_generator = MinmaxPlayer::new();
+        move_generator.node_limit = 200_000;
+        move_generator.opening_book = false;
+        let board = Board::new(6, 6);
+
+        let mut search = move_generator.start_search(&board, Player::CPU);
+        let mut steps = 0;
+        while !search.is_finished() {
+            search.step(0.001);
+            steps += 1;
+        }
+
+        assert!(steps > 1, "{steps}");
+        assert!(search.best_so_far().is_some());
+    }
+
+    #[test]
+    fn search_in_steps_builds_the_offered_house() {
+        let mut board = Board::new(3, 1);
+        for (row, col) in [(0, 0), (1, 0), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        let mut move_generator = MinmaxPlayer::new();
+        move_generator.endgame_walls = 0;
+
+        let mut search = move_generator.start_search(&board, Player::CPU);
+        while !search.is_finished() {
+            search.step(0.001);
+        }
+
+        assert_eq!(Some((1, 1)), search.best_so_far());
+    }
+
     #[test]
     fn load_board() {
         let s = " XXXXX XXXXX XXXXX
@@ -326,28 +885,21 @@ X     |     X     X
         let width = 5;
         let height = 5;
 
-        let mut board = Board::new(width, height);
-
-        while !board.all_is_clicked() {
-            perform_all_moves(&first_player_move_generator, &mut board, Player::Player1);
-            if board.all_is_clicked() {
-                break;
-            }
-            perform_all_moves(&second_player_move_generator, &mut board, Player::CPU);
-        }
-        let stats = board.get_statistics();
-        println!("{board}");
+        let mut game_match = Match::new(Board::new(width, height), &[Player::Player1, Player::CPU]);
+        let stats =
+            game_match.play_out(&[&first_player_move_generator, &second_player_move_generator]);
+        println!("{}", game_match.board());
         println!("{stats:?}");
         stats
     }
 
     fn perform_all_moves(move_generator: &impl MoveGenerator, board: &mut Board, player: Player) {
         let (mut row, mut col) = move_generator
-            .next_move(board)
+            .next_move(board, player)
             .expect("there is a move available");
 
         while let Ok(true) = board.click_wall(row, col, player) {
-            if let Some((r, c)) = move_generator.next_move(board) {
+            if let Some((r, c)) = move_generator.next_move(board, player) {
                 row = r;
                 col = c;
             } else {
@@ -2,7 +2,6 @@ use dyn_clone::DynClone;
 
 use prelude::*;
 
-use quad_rand::ChooseRandom;
 use std::collections::HashMap;
 use std::fmt::Debug;
 
@@ -12,13 +11,41 @@ pub type RowType = usize;
 pub type ColType = usize;
 pub type CounterType = usize;
 
+pub mod analysis;
+pub mod board_state;
+pub mod chain_analysis;
+pub mod endgame_solver;
+pub mod evaluator;
+pub mod expert_player;
 pub mod greedy_algorithm_player;
+pub mod limited_strength_player;
+pub mod mcts_player;
 pub mod minmax_player;
+pub mod move_search;
+pub mod nimstring;
+pub mod nimstring_player;
+pub mod opening_book;
 pub mod prelude;
 pub mod region_counting_player;
+pub mod rng;
+pub mod tournament;
+pub mod transposition_table;
+
+pub trait MoveGenerator: Debug + DynClone + Send {
+    /// Returns the wall to click on behalf of `player`, or `None` when all walls are clicked.
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)>;
+
+    /// Starts a search of the same move which is done in steps, see `MoveSearch`. Move
+    /// generators which search for long split their search, the others find the move at once.
+    fn start_search(&self, board: &Board, player: Player) -> Box<dyn MoveSearch> {
+        Box::new(FinishedSearch::new(self.next_move(board, player)))
+    }
+
+    /// Seeds the random number generator which picks between equally good moves, see `rng`.
+    fn reseed(&mut self, seed: u64);
 
-pub trait MoveGenerator: Debug + DynClone {
-    fn next_move(&self, board: &Board) -> Option<(RowType, ColType)>;
+    /// Forgets what was learnt during the previous game, e.g. the positions kept by a search.
+    fn new_game(&mut self) {}
 }
 
 dyn_clone::clone_trait_object!(MoveGenerator);
@@ -44,44 +71,46 @@ fn filter_walls<T>(walls: &[T], filter_condition: impl FnMut(&&T) -> bool) -> Ve
     walls.iter().filter(filter_condition).collect::<Vec<_>>()
 }
 
-fn choose_wall_index<T>(walls: &[T], filter_condition: impl FnMut(&&T) -> bool) -> Option<&T> {
-    filter_walls(walls, filter_condition)
-        .as_slice()
-        .choose()
+fn choose_wall_index<'a, T>(
+    rng: &SeededRng,
+    walls: &'a [T],
+    filter_condition: impl FnMut(&&T) -> bool,
+) -> Option<&'a T> {
+    rng.choose(filter_walls(walls, filter_condition).as_slice())
         .copied()
 }
 
-fn find_region_size(board: &Board, wall: &WallStatistics) -> usize {
-    let mut board = board.clone();
-    let mut row = wall.row;
-    let mut col = wall.col;
-    let player = crate::game::Player::CPU;
-    let move_generator = GreadyAlgorithmPlayer::default();
-
-    let starting_points = board.statistics.cpu_points;
-    if wall.max_adjacent_counter == 2 && board.click_wall(row, col, player).is_ok() {
-        if let Some((r, c)) = move_generator.next_move(&board) {
-            row = r;
-            col = c;
+/// Plays greedily as the CPU starting from the given wall and returns the number of houses
+/// built on the way.
+fn find_region_size(rng: &SeededRng, state: &BoardState, wall: &WallStatistics) -> usize {
+    let mut state = state.clone();
+    let mut wall_idx = state.wall_index(wall.row, wall.col);
+    let player = Player::CPU;
+
+    let starting_points = state.get_points(player);
+    if wall.max_adjacent_counter == 2 && !state.is_clicked(wall_idx) {
+        state.click_wall(wall_idx, player);
+        if let Some(next_wall) = choose_greedy_wall_index(rng, &state) {
+            wall_idx = next_wall;
         }
     }
-    while let Ok(true) = board.click_wall(row, col, player) {
-        if let Some((r, c)) = move_generator.next_move(&board) {
-            row = r;
-            col = c;
+    while !state.is_clicked(wall_idx) && state.click_wall(wall_idx, player) > 0 {
+        if let Some(next_wall) = choose_greedy_wall_index(rng, &state) {
+            wall_idx = next_wall;
         }
     }
-    board.statistics.cpu_points - starting_points
+    state.get_points(player) - starting_points
 }
 
 fn build_region_size_map<'a>(
-    board: &Board,
+    rng: &SeededRng,
+    state: &BoardState,
     walls: &'a [WallStatistics],
     filter_condition: impl FnMut(&&WallStatistics) -> bool,
 ) -> HashMap<usize, Vec<&'a WallStatistics>> {
     filter_walls(walls, filter_condition)
         .iter()
-        .map(|&ws| (find_region_size(board, ws), ws))
+        .map(|&ws| (find_region_size(rng, state, ws), ws))
         .fold(
             HashMap::<usize, Vec<_>>::new(),
             |mut hashmap, (region_size, ws)| {
@@ -92,23 +121,12 @@ fn build_region_size_map<'a>(
         )
 }
 
-fn collect_wall_statistics(board: &Board) -> Vec<WallStatistics> {
-    board
-        .walls
-        .iter()
-        .flatten()
-        .filter_map(|wall| {
-            if wall.is_clicked {
-                None
-            } else {
-                let max_counter = wall
-                    .adjacent_cells
-                    .iter()
-                    .map(|(r, c)| board.cells[*r][*c].counter)
-                    .max()
-                    .expect("at least one cell is adjacent to the wall");
-                Some(WallStatistics::new(wall.id.0, wall.id.1, max_counter))
-            }
+fn collect_wall_statistics(state: &BoardState) -> Vec<WallStatistics> {
+    state
+        .unclicked_walls()
+        .map(|wall| {
+            let (row, col) = state.wall_id(wall);
+            WallStatistics::new(row, col, state.max_adjacent_counter(wall))
         })
         .collect()
 }
@@ -122,10 +140,14 @@ fn wall_priority(wall: &WallStatistics) -> usize {
     }
 }
 
-fn available_moves(board: &Board) -> impl Iterator<Item = (usize, usize)> + use<'_> {
-    let mut unclicked_walls = collect_wall_statistics(board);
+/// Indices of the unclicked walls, the most promising first.
+fn available_moves(state: &BoardState) -> Vec<usize> {
+    let mut unclicked_walls = collect_wall_statistics(state);
     unclicked_walls.sort_by_key(wall_priority);
-    unclicked_walls.into_iter().map(|ws| (ws.row, ws.col))
+    unclicked_walls
+        .into_iter()
+        .map(|ws| state.wall_index(ws.row, ws.col))
+        .collect()
 }
 
 #[cfg(test)]
@@ -170,7 +192,7 @@ mod ai_functions_tests {
             //dbg!(&wall, &expected_region_sizes[idx]);
             assert_eq!(
                 expected_region_sizes[idx],
-                find_region_size(&board, wall)
+                find_region_size(&SeededRng::default(), &BoardState::from(&board), wall)
             );
         }
     }
@@ -199,7 +221,7 @@ mod ai_functions_tests {
 
         assert_eq!(
             expected_region_size,
-            find_region_size(&board, &wall)
+            find_region_size(&SeededRng::default(), &BoardState::from(&board), &wall)
         );
     }
 
@@ -222,7 +244,7 @@ mod ai_functions_tests {
 
         assert_eq!(
             expected_region_size,
-            find_region_size(&board, &wall)
+            find_region_size(&SeededRng::default(), &BoardState::from(&board), &wall)
         );
 }
 }
\ No newline at end of file
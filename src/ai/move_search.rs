@@ -0,0 +1,159 @@
+//! Searches which span several frames. The game loop gives every search a slice of time in each
+//! frame, so the board is drawn and handles input while the CPU thinks.
+
+use super::prelude::*;
+
+/// Search of a move started by `MoveGenerator::start_search`.
+pub trait MoveSearch: Debug {
+    /// Searches for about `budget` seconds, or does nothing when the search is finished.
+    fn step(&mut self, budget: f64);
+
+    fn is_finished(&self) -> bool;
+
+    /// Best move found so far. Once the search is finished it is the move to play.
+    fn best_so_far(&self) -> Option<(RowType, ColType)>;
+}
+
+/// Search of a move generator which finds its move at once.
+#[derive(Debug)]
+pub struct FinishedSearch {
+    best: Option<(RowType, ColType)>,
+}
+
+impl FinishedSearch {
+    pub fn new(best: Option<(RowType, ColType)>) -> Self {
+        Self { best }
+    }
+}
+
+impl MoveSearch for FinishedSearch {
+    fn step(&mut self, _budget: f64) {}
+
+    fn is_finished(&self) -> bool {
+        true
+    }
+
+    fn best_so_far(&self) -> Option<(RowType, ColType)> {
+        self.best
+    }
+}
+
+/// Search run by `next_move` of a copy of the move generator on a worker thread. The steps
+/// only check whether the worker is done.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct WorkerSearch {
+    receiver: std::sync::mpsc::Receiver<Option<(RowType, ColType)>>,
+    best: Option<(RowType, ColType)>,
+    finished: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WorkerSearch {
+    pub fn new(
+        move_generator: &(dyn MoveGenerator + 'static),
+        board: &Board,
+        player: Player,
+    ) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let move_generator = dyn_clone::clone_box(move_generator);
+        let board = board.clone();
+        std::thread::spawn(move || {
+            // the search is not needed any more when the receiver is gone
+            let _ = sender.send(move_generator.next_move(&board, player));
+        });
+        Self {
+            receiver,
+            best: None,
+            finished: false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl MoveSearch for WorkerSearch {
+    fn step(&mut self, _budget: f64) {
+        use std::sync::mpsc::TryRecvError;
+
+        if self.finished {
+            return;
+        }
+        match self.receiver.try_recv() {
+            Ok(best) => {
+                self.best = best;
+                self.finished = true;
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => {
+                eprintln!("Worker thread stopped without a move");
+                self.finished = true;
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn best_so_far(&self) -> Option<(RowType, ColType)> {
+        self.best
+    }
+}
+
+/// Starts the search of the move of `player`. Native builds search on a worker thread, the
+/// browser has no threads, so there the search is done in steps.
+pub fn start_thinking(
+    move_generator: &(dyn MoveGenerator + 'static),
+    board: &Board,
+    player: Player,
+) -> Box<dyn MoveSearch> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Box::new(WorkerSearch::new(move_generator, board, player))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        move_generator.start_search(board, player)
+    }
+}
+
+#[cfg(test)]
+mod move_search_tests {
+    use super::*;
+
+    fn finish_search(mut search: Box<dyn MoveSearch>) -> Option<(RowType, ColType)> {
+        while !search.is_finished() {
+            std::thread::yield_now();
+            search.step(0.01);
+        }
+        search.best_so_far()
+    }
+
+    fn board_with_house_offered() -> Board {
+        let mut board = Board::new(2, 1);
+        for (row, col) in [(0, 0), (1, 0), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+        board
+    }
+
+    #[test]
+    fn move_generators_find_their_move_at_once_by_default() {
+        let search =
+            GreadyAlgorithmPlayer::default().start_search(&board_with_house_offered(), Player::CPU);
+
+        assert!(search.is_finished());
+        assert_eq!(Some((1, 1)), search.best_so_far());
+    }
+
+    #[test]
+    fn worker_finds_the_move_of_the_move_generator() {
+        let search = start_thinking(
+            &ExpertPlayer::default(),
+            &board_with_house_offered(),
+            Player::CPU,
+        );
+
+        assert_eq!(Some((1, 1)), finish_search(search));
+    }
+}
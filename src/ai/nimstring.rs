@@ -0,0 +1,260 @@
+//! Nimstring values of positions. Nimstring is dots and boxes in which the player who builds
+//! the last house loses, because a player who builds a house has to move again. Whoever wins
+//! the nimstring game of a position gets control of its endgame.
+//!
+//! Houses which cannot be declined are taken for free, they do not change whose turn it is.
+//! A position in which the player to move may take or decline houses is loony, it is won by
+//! that player and moving into it is never counted as an option. What is left splits into
+//! components which do not share cells, and the value of the position is the nim-sum of their
+//! values.
+
+use super::chain_analysis::find_capture_run;
+use super::prelude::*;
+use crate::game::zobrist;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NimValue {
+    Nimber(u32),
+    Loony,
+}
+
+impl NimValue {
+    /// Tells whether the player to move loses the nimstring game.
+    pub fn is_lost(&self) -> bool {
+        *self == NimValue::Nimber(0)
+    }
+}
+
+/// Evaluates positions of one board. Components are searched exactly and remembered by their
+/// unclicked walls, so the evaluator should not be shared between boards of different shapes.
+#[derive(Debug, Clone)]
+pub struct NimstringEvaluator {
+    pub max_component_walls: usize,
+    cache: HashMap<u64, u32>,
+}
+
+impl NimstringEvaluator {
+    pub fn new() -> Self {
+        Self {
+            max_component_walls: NIMSTRING_COMPONENT_WALLS,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Value of the position for the player to move, or `None` when some component has more
+    /// than `max_component_walls` unclicked walls.
+    pub fn evaluate(&mut self, state: &BoardState) -> Option<NimValue> {
+        let mut state = state.clone();
+        let walls = state.unclicked_walls().collect::<Vec<_>>();
+        if take_free_houses(&mut state, &walls, &mut vec![]) {
+            return Some(NimValue::Loony);
+        }
+        let walls = state.unclicked_walls().collect::<Vec<_>>();
+        let components = split_components(&state, &walls);
+        if components
+            .iter()
+            .any(|component| component.len() > self.max_component_walls)
+        {
+            return None;
+        }
+
+        Some(NimValue::Nimber(
+            components
+                .iter()
+                .map(|component| self.component_nimber(&mut state, component))
+                .fold(0, |value, nimber| value ^ nimber),
+        ))
+    }
+
+    /// Value of the part of the position made of the given unclicked walls.
+    fn nim_value(&mut self, state: &mut BoardState, walls: &[usize]) -> NimValue {
+        let mut taken = vec![];
+        let value = if take_free_houses(state, walls, &mut taken) {
+            NimValue::Loony
+        } else {
+            let walls = walls
+                .iter()
+                .copied()
+                .filter(|wall| !state.is_clicked(*wall))
+                .collect::<Vec<_>>();
+            NimValue::Nimber(
+                split_components(state, &walls)
+                    .iter()
+                    .map(|component| self.component_nimber(state, component))
+                    .fold(0, |value, nimber| value ^ nimber),
+            )
+        };
+        for wall in taken.iter().rev() {
+            state.unclick_wall(*wall);
+        }
+        value
+    }
+
+    /// Minimal excluded value of the options of a component, moves into loony positions are
+    /// left out.
+    fn component_nimber(&mut self, state: &mut BoardState, walls: &[usize]) -> u32 {
+        let key = walls
+            .iter()
+            .fold(0, |key, wall| key ^ zobrist::mix(*wall as u64));
+        if let Some(nimber) = self.cache.get(&key) {
+            return *nimber;
+        }
+
+        let mut options = vec![];
+        for (idx, wall) in walls.iter().enumerate() {
+            let rest = walls
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != idx)
+                .map(|(_, wall)| *wall)
+                .collect::<Vec<_>>();
+            state.click_wall(*wall, Player::CPU);
+            if let NimValue::Nimber(nimber) = self.nim_value(state, &rest) {
+                options.push(nimber);
+            }
+            state.unclick_wall(*wall);
+        }
+        let nimber = (0..)
+            .find(|nimber| !options.contains(nimber))
+            .expect("some value is not an option");
+
+        self.cache.insert(key, nimber);
+        nimber
+    }
+}
+
+impl Default for NimstringEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Takes the houses among the given walls which cannot be declined and records the clicked
+/// walls. Returns `true` when the position is loony.
+fn take_free_houses(state: &mut BoardState, walls: &[usize], taken: &mut Vec<usize>) -> bool {
+    loop {
+        let capturable = walls
+            .iter()
+            .filter(|wall| !state.is_clicked(**wall))
+            .flat_map(|wall| state.wall_cells(*wall).to_vec())
+            .find(|cell| state.counter(*cell) == 3);
+        let Some(cell) = capturable else {
+            return false;
+        };
+        let run = find_capture_run(state, cell);
+        if run.can_be_declined() {
+            return true;
+        }
+        for wall in run.walls {
+            state.click_wall(wall, Player::CPU);
+            taken.push(wall);
+        }
+    }
+}
+
+/// Groups the walls by the cells they connect.
+fn split_components(state: &BoardState, walls: &[usize]) -> Vec<Vec<usize>> {
+    let mut roots = (0..state.cell_count()).collect::<Vec<_>>();
+    fn find(roots: &mut [usize], cell: usize) -> usize {
+        let mut root = cell;
+        while roots[root] != root {
+            roots[root] = roots[roots[root]];
+            root = roots[root];
+        }
+        root
+    }
+    for wall in walls {
+        if let [cell, other] = state.wall_cells(*wall) {
+            let (root, other_root) = (find(&mut roots, *cell), find(&mut roots, *other));
+            roots[root] = other_root;
+        }
+    }
+
+    let mut components = HashMap::<usize, Vec<usize>>::new();
+    for wall in walls {
+        let root = find(&mut roots, state.wall_cells(*wall)[0]);
+        components.entry(root).or_default().push(*wall);
+    }
+    components.into_values().collect()
+}
+
+#[cfg(test)]
+mod nimstring_tests {
+    use super::*;
+
+    fn board_with_horizontal_walls(width: usize, height: usize) -> Board {
+        let mut board = Board::new(width, height);
+        for row in 0..=height {
+            for col in 0..width {
+                let _ = board.click_wall(2 * row, col, Player::Player1);
+            }
+        }
+        board
+    }
+
+    fn evaluate(board: &Board) -> Option<NimValue> {
+        NimstringEvaluator::new().evaluate(&BoardState::from(board))
+    }
+
+    #[test]
+    fn single_box_is_won_by_the_first_player() {
+        assert_eq!(Some(NimValue::Nimber(1)), evaluate(&Board::new(1, 1)));
+    }
+
+    #[test]
+    fn short_chains_are_worth_one_and_long_chains_nothing() {
+        assert_eq!(
+            Some(NimValue::Nimber(1)),
+            evaluate(&board_with_horizontal_walls(1, 1))
+        );
+        assert_eq!(
+            Some(NimValue::Nimber(1)),
+            evaluate(&board_with_horizontal_walls(2, 1))
+        );
+        assert_eq!(
+            Some(NimValue::Nimber(0)),
+            evaluate(&board_with_horizontal_walls(3, 1))
+        );
+        assert_eq!(
+            Some(NimValue::Nimber(0)),
+            evaluate(&board_with_horizontal_walls(5, 1))
+        );
+    }
+
+    #[test]
+    fn values_of_components_are_added() {
+        assert_eq!(
+            Some(NimValue::Nimber(0)),
+            evaluate(&board_with_horizontal_walls(2, 2))
+        );
+        assert_eq!(
+            Some(NimValue::Nimber(0)),
+            evaluate(&board_with_horizontal_walls(2, 4))
+        );
+        assert_eq!(
+            Some(NimValue::Nimber(1)),
+            evaluate(&board_with_horizontal_walls(2, 3))
+        );
+    }
+
+    #[test]
+    fn half_hearted_handout_is_loony() {
+        let mut board = board_with_horizontal_walls(2, 1);
+        let _ = board.click_wall(1, 0, Player::Player1);
+
+        assert_eq!(Some(NimValue::Loony), evaluate(&board));
+    }
+
+    #[test]
+    fn free_houses_are_taken() {
+        let mut board = board_with_horizontal_walls(2, 1);
+        let _ = board.click_wall(1, 1, Player::Player1);
+
+        assert_eq!(Some(NimValue::Nimber(0)), evaluate(&board));
+    }
+
+    #[test]
+    fn big_components_are_not_evaluated() {
+        assert_eq!(None, evaluate(&Board::new(3, 3)));
+    }
+}
@@ -0,0 +1,126 @@
+use super::prelude::*;
+
+/// Plays the moves which leave the opponent a lost nimstring position, see `nimstring`. While
+/// the components of the board are too big to be evaluated it plays like `ExpertPlayer`. The
+/// first moves are taken from the opening book.
+#[derive(Default, Debug, Clone)]
+pub struct NimstringPlayer {
+    rng: SeededRng,
+}
+
+impl MoveGenerator for NimstringPlayer {
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        let state = BoardState::from(board);
+        if state.all_is_clicked() {
+            return None;
+        }
+        self.rng.start_move(board, player);
+        if let Some(wall) = opening_move(&self.rng, board) {
+            return Some(wall);
+        }
+        choose_nimstring_wall_index(&self.rng, &state)
+            .or_else(|| choose_expert_wall_index(&self.rng, &state))
+            .map(|wall| state.wall_id(wall))
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+}
+
+fn choose_nimstring_wall_index(rng: &SeededRng, state: &BoardState) -> Option<usize> {
+    let analysis = ChainAnalysis::new(state);
+    let mut evaluator = NimstringEvaluator::new();
+
+    if let Some(run) = analysis
+        .runs
+        .iter()
+        .find(|run| !run.can_be_declined())
+        .or_else(|| analysis.runs.first())
+    {
+        if analysis.runs.len() > 1 || run.declining_wall().is_none() {
+            return Some(run.walls[0]);
+        }
+        // decline the houses when the position left after taking them is lost for the taker
+        let mut taken = state.clone();
+        for wall in &run.walls {
+            taken.click_wall(*wall, Player::CPU);
+        }
+        return if evaluator.evaluate(&taken)?.is_lost() {
+            run.declining_wall()
+        } else {
+            Some(run.walls[0])
+        };
+    }
+
+    evaluator.evaluate(state)?;
+    let mut state = state.clone();
+    let walls = state.unclicked_walls().collect::<Vec<_>>();
+    let winning_walls = walls
+        .into_iter()
+        .filter(|wall| {
+            state.click_wall(*wall, Player::CPU);
+            let value = evaluator.evaluate(&state);
+            state.unclick_wall(*wall);
+            value.is_some_and(|value| value.is_lost())
+        })
+        .collect::<Vec<_>>();
+    rng.choose(&winning_walls).copied()
+}
+
+#[cfg(test)]
+mod nimstring_player_tests {
+    use super::*;
+
+    #[test]
+    fn short_chain_is_given_away_to_win_the_nimstring_game() {
+        let mut board = Board::with_mask(3, 2, &[vec![true, true, true], vec![true, true, false]]);
+        for (row, col) in [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (4, 0),
+            (4, 1),
+        ] {
+            board
+                .click_wall(row, col, Player::Player1)
+                .expect("wall can be clicked");
+        }
+
+        let next_move = NimstringPlayer::default().next_move(&board, Player::CPU);
+
+        assert_eq!(Some((3, 1)), next_move);
+    }
+
+    #[test]
+    fn houses_are_declined_when_taking_them_loses() {
+        let mut board = Board::new(4, 2);
+        for row in [0, 2, 4] {
+            for col in 0..4 {
+                let _ = board.click_wall(row, col, Player::Player1);
+            }
+        }
+        for (row, col) in [(1, 0), (1, 1), (1, 2)] {
+            let _ = board.click_wall(row, col, Player::CPU);
+        }
+
+        let next_move = NimstringPlayer::default().next_move(&board, Player::CPU);
+
+        assert_eq!(Some((1, 4)), next_move);
+    }
+
+    #[test]
+    fn when_all_is_clicked_there_is_no_other_move() {
+        let mut board = Board::new(1, 1);
+        for (row, col) in [(0, 0), (1, 0), (1, 1), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::Player1);
+        }
+
+        assert!(NimstringPlayer::default()
+            .next_move(&board, Player::CPU)
+            .is_none());
+    }
+}
@@ -0,0 +1,440 @@
+//! Recommended moves for the first walls of a game. The book is built offline by the
+//! `build_opening_book` binary and embedded for boards from 3x3, whose openings are solved
+//! exactly, to 6x6, whose openings are played out by `ExpertPlayer`s. It only keeps the positions where some safe walls are worse than the others, the CPU
+//! players search the rest. Positions which are mirror images or rotations of each other
+//! share an entry: the book stores every position in its canonical orientation, the one with
+//! the smallest key, and lookups map the moves back to the orientation of the board.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use super::prelude::*;
+use crate::game::notation::{wall_from_notation, wall_to_notation};
+use crate::game::settings::parse_board_size;
+use crate::game::zobrist::{mix, wall_key};
+
+static EMBEDDED_OPENING_BOOK: &str = include_str!("opening_book.txt");
+static OPENING_BOOK: OnceLock<OpeningBook> = OnceLock::new();
+
+/// Book embedded in the binary.
+pub fn opening_book() -> &'static OpeningBook {
+    OPENING_BOOK.get_or_init(|| {
+        EMBEDDED_OPENING_BOOK
+            .parse()
+            .expect("embedded opening book is valid")
+    })
+}
+
+/// One of the moves the embedded book recommends for the position, picked by `rng`.
+pub fn opening_move(rng: &SeededRng, board: &Board) -> Option<(RowType, ColType)> {
+    let moves = opening_book().lookup(board)?;
+    rng.choose(&moves).copied()
+}
+
+/// Mirror images and rotations of the board. Rectangles have the first four, squares all.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Symmetry {
+    Identity,
+    FlipRows,
+    FlipCols,
+    Rotate180,
+    Transpose,
+    AntiTranspose,
+    Rotate90,
+    Rotate270,
+}
+
+impl Symmetry {
+    const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::FlipRows,
+        Symmetry::FlipCols,
+        Symmetry::Rotate180,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+        Symmetry::Rotate90,
+        Symmetry::Rotate270,
+    ];
+
+    fn of_board(width: usize, height: usize) -> &'static [Symmetry] {
+        if width == height {
+            &Self::ALL
+        } else {
+            &Self::ALL[..4]
+        }
+    }
+
+    fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            symmetry => symmetry,
+        }
+    }
+
+    /// Position of the joint in the row and column after the transformation. Boards with
+    /// `width` x `height` houses have joints from `(0, 0)` to `(height, width)`.
+    fn joint(self, (row, col): (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::FlipRows => (height - row, col),
+            Symmetry::FlipCols => (row, width - col),
+            Symmetry::Rotate180 => (height - row, width - col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (width - col, height - row),
+            Symmetry::Rotate90 => (col, height - row),
+            Symmetry::Rotate270 => (width - col, row),
+        }
+    }
+
+    /// Wall between the transformed joints of the wall.
+    fn wall(
+        self,
+        (row, col): (RowType, ColType),
+        width: usize,
+        height: usize,
+    ) -> (RowType, ColType) {
+        let (start, end) = if row % 2 == 0 {
+            ((row / 2, col), (row / 2, col + 1))
+        } else {
+            ((row / 2, col), (row / 2 + 1, col))
+        };
+        let (start_row, start_col) = self.joint(start, width, height);
+        let (end_row, end_col) = self.joint(end, width, height);
+        if start_row == end_row {
+            (2 * start_row, start_col.min(end_col))
+        } else {
+            (2 * start_row.min(end_row) + 1, start_col)
+        }
+    }
+
+    fn walls(
+        self,
+        walls: &[(RowType, ColType)],
+        width: usize,
+        height: usize,
+    ) -> Vec<(RowType, ColType)> {
+        walls
+            .iter()
+            .map(|wall| self.wall(*wall, width, height))
+            .collect()
+    }
+}
+
+/// Key of the position with the clicked walls on a board of the given size.
+fn position_key(walls: &[(RowType, ColType)], width: usize, height: usize) -> u64 {
+    walls.iter().fold(
+        mix(((width as u64) << 32) | height as u64),
+        |key, (row, col)| key ^ wall_key(*row, *col),
+    )
+}
+
+/// Smallest key of the position over the symmetries of the board, together with the symmetry
+/// which gives it.
+fn canonical_key(walls: &[(RowType, ColType)], width: usize, height: usize) -> (u64, Symmetry) {
+    Symmetry::of_board(width, height)
+        .iter()
+        .map(|symmetry| {
+            let key = position_key(&symmetry.walls(walls, width, height), width, height);
+            (key, *symmetry)
+        })
+        .min_by_key(|(key, _)| *key)
+        .expect("there is the identity")
+}
+
+/// Key shared by the position with the clicked walls and its mirror images and rotations.
+pub fn symmetric_position_key(walls: &[(RowType, ColType)], width: usize, height: usize) -> u64 {
+    canonical_key(walls, width, height).0
+}
+
+/// Positions in their canonical orientation, see `canonical_key`, of a board of the given
+/// size with up to `max_walls` clicked walls and no houses built.
+pub fn opening_positions(
+    width: usize,
+    height: usize,
+    max_walls: usize,
+) -> Vec<Vec<(RowType, ColType)>> {
+    let state = BoardState::from(&Board::new(width, height));
+    let all_walls = state
+        .unclicked_walls()
+        .map(|wall| state.wall_id(wall))
+        .collect::<Vec<_>>();
+    let builds_house = |walls: &[(RowType, ColType)]| {
+        let mut board = Board::new(width, height);
+        walls
+            .iter()
+            .any(|(row, col)| board.click_wall(*row, *col, Player::Player1) == Ok(true))
+    };
+
+    let mut positions: Vec<Vec<(RowType, ColType)>> = vec![vec![]];
+    let mut level = positions.clone();
+    for _ in 0..max_walls {
+        let mut seen = HashSet::new();
+        let mut next_level = vec![];
+        for walls in &level {
+            for wall in all_walls.iter().filter(|wall| !walls.contains(wall)) {
+                let mut child = walls.clone();
+                child.push(*wall);
+                let (key, symmetry) = canonical_key(&child, width, height);
+                if seen.insert(key) && !builds_house(&child) {
+                    next_level.push(symmetry.walls(&child, width, height));
+                }
+            }
+        }
+        positions.extend(next_level.iter().cloned());
+        level = next_level;
+    }
+    positions
+}
+
+#[derive(Debug, Clone)]
+struct BookEntry {
+    width: usize,
+    height: usize,
+    walls: Vec<(RowType, ColType)>,
+    moves: Vec<(RowType, ColType)>,
+}
+
+/// Recommended moves of positions given by their size and clicked walls.
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    entries: HashMap<u64, BookEntry>,
+    max_walls: usize,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Recommends the moves in the position with the clicked walls, replacing the moves
+    /// recommended before in the same or a symmetric position.
+    pub fn insert(
+        &mut self,
+        width: usize,
+        height: usize,
+        walls: &[(RowType, ColType)],
+        moves: &[(RowType, ColType)],
+    ) {
+        let (key, symmetry) = canonical_key(walls, width, height);
+        self.max_walls = self.max_walls.max(walls.len());
+        self.entries.insert(
+            key,
+            BookEntry {
+                width,
+                height,
+                walls: symmetry.walls(walls, width, height),
+                moves: symmetry.walls(moves, width, height),
+            },
+        );
+    }
+
+    /// Moves recommended in the position of the board, `None` when the position is not in
+    /// the book. Only rectangular boards without houses built are looked up.
+    pub fn lookup(&self, board: &Board) -> Option<Vec<(RowType, ColType)>> {
+        let walls = board
+            .walls
+            .iter()
+            .enumerate()
+            .flat_map(|(row, walls)| {
+                walls
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, wall)| wall.is_clicked)
+                    .map(move |(col, _)| (row, col))
+            })
+            .collect::<Vec<_>>();
+        if walls.len() > self.max_walls
+            || board.cells.iter().flatten().any(|cell| !cell.exists)
+            || board.statistics.points.iter().any(|points| *points > 0)
+        {
+            return None;
+        }
+
+        let (key, symmetry) = canonical_key(&walls, board.width, board.height);
+        let entry = self.entries.get(&key)?;
+        Some(
+            symmetry
+                .inverse()
+                .walls(&entry.moves, board.width, board.height),
+        )
+    }
+}
+
+/// One line per position: `<width>x<height>: <clicked walls> -> <recommended moves>`, the
+/// walls written in the notation of the game records.
+impl std::fmt::Display for OpeningBook {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let notation = |walls: &[(RowType, ColType)]| {
+            walls
+                .iter()
+                .map(|(row, col)| format!(" {}", wall_to_notation(*row, *col)))
+                .collect::<String>()
+        };
+        let mut lines = self
+            .entries
+            .values()
+            .map(|entry| {
+                let mut walls = entry.walls.clone();
+                walls.sort();
+                (
+                    (entry.width, entry.height, walls.len(), walls.clone()),
+                    format!(
+                        "{}x{}:{} ->{}",
+                        entry.width,
+                        entry.height,
+                        notation(&walls),
+                        notation(&entry.moves)
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, line) in lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for OpeningBook {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_walls = |walls: &str| {
+            walls
+                .split_whitespace()
+                .map(wall_from_notation)
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let mut book = Self::new();
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (size, position) = line
+                .split_once(':')
+                .ok_or(format!("Wrong opening book line '{line}'"))?;
+            let (walls, moves) = position
+                .split_once("->")
+                .ok_or(format!("Wrong opening book line '{line}'"))?;
+            let (width, height) = parse_board_size(size)?;
+            book.insert(width, height, &parse_walls(walls)?, &parse_walls(moves)?);
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod opening_book_tests {
+    use super::*;
+
+    fn click(board: &mut Board, walls: &[(RowType, ColType)]) {
+        for (row, col) in walls {
+            board
+                .click_wall(*row, *col, Player::Player1)
+                .expect("wall can be clicked");
+        }
+    }
+
+    #[test]
+    fn every_symmetry_keeps_the_walls_on_the_board() {
+        for (width, height) in [(3, 3), (4, 2)] {
+            let state = BoardState::from(&Board::new(width, height));
+            let walls = state
+                .unclicked_walls()
+                .map(|wall| state.wall_id(wall))
+                .collect::<Vec<_>>();
+            for symmetry in Symmetry::of_board(width, height) {
+                let mut transformed = symmetry.walls(&walls, width, height);
+                transformed.sort();
+                let mut sorted = walls.clone();
+                sorted.sort();
+                assert_eq!(sorted, transformed, "{symmetry:?}");
+                assert_eq!(
+                    walls,
+                    symmetry
+                        .inverse()
+                        .walls(&symmetry.walls(&walls, width, height), width, height)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn moves_are_found_in_symmetric_positions() {
+        let mut book = OpeningBook::new();
+        book.insert(3, 3, &[(0, 0)], &[(6, 2), (1, 1)]);
+
+        let mut board = Board::new(3, 3);
+        click(&mut board, &[(6, 2)]);
+        let mut moves = book.lookup(&board).expect("position is in the book");
+        moves.sort();
+
+        assert_eq!(vec![(0, 0), (5, 2)], moves);
+        assert!(book.lookup(&Board::new(3, 3)).is_none());
+    }
+
+    #[test]
+    fn book_is_written_and_read_back() {
+        let mut book = OpeningBook::new();
+        book.insert(4, 3, &[], &[(2, 1)]);
+        book.insert(4, 3, &[(1, 0), (0, 0)], &[(3, 4)]);
+
+        let text = book.to_string();
+        let loaded = text
+            .parse::<OpeningBook>()
+            .expect("opening book can be parsed");
+
+        assert_eq!(2, loaded.len());
+        assert!(text.starts_with("4x3: -> "));
+        assert_eq!(
+            book.lookup(&Board::new(4, 3)),
+            loaded.lookup(&Board::new(4, 3))
+        );
+    }
+
+    #[test]
+    fn symmetric_positions_are_listed_once() {
+        // the 7 walls of 2x1 are a long side, a short side or the middle wall
+        let positions = opening_positions(2, 1, 1);
+
+        assert_eq!(1 + 3, positions.len());
+    }
+
+    #[test]
+    fn embedded_book_answers_the_first_move_of_larger_boards() {
+        // every first move of 3x3 is as good as the others
+        for size in 4..=6 {
+            let board = Board::new(size, size);
+            assert!(opening_book().lookup(&board).is_some(), "{size}");
+        }
+    }
+
+    #[test]
+    fn embedded_book_leaves_out_some_safe_walls() {
+        assert!(!opening_book().is_empty());
+        for entry in opening_book().entries.values() {
+            let mut board = Board::new(entry.width, entry.height);
+            click(&mut board, &entry.walls);
+            let state = BoardState::from(&board);
+            let safe_walls = ChainAnalysis::new(&state)
+                .safe_walls
+                .iter()
+                .map(|wall| state.wall_id(*wall))
+                .collect::<Vec<_>>();
+            let safe_moves = entry
+                .moves
+                .iter()
+                .filter(|wall| safe_walls.contains(wall))
+                .count();
+
+            assert!(safe_moves < safe_walls.len(), "{:?}", entry.walls);
+        }
+    }
+}
@@ -1,15 +1,41 @@
+pub use super::analysis::*;
+pub use super::board_state::*;
+pub use super::chain_analysis::*;
+pub use super::endgame_solver::*;
+pub use super::evaluator::*;
+pub use super::expert_player::*;
 pub use super::greedy_algorithm_player::*;
+pub use super::limited_strength_player::*;
+pub use super::mcts_player::*;
 pub use super::minmax_player::*;
+pub use super::move_search::*;
+pub use super::nimstring::*;
+pub use super::nimstring_player::*;
+pub use super::opening_book::*;
 pub use super::region_counting_player::*;
+pub use super::rng::*;
+pub use super::transposition_table::*;
 pub use super::*;
 
 pub use crate::game::*;
 pub use crate::rendering::constants::*;
 
-pub fn get_cpu_player(difficulty: &Difficulty) -> Box<dyn MoveGenerator> {
-    match difficulty {
+/// CPU player of the given difficulty. Below `MAX_CPU_STRENGTH` it makes mistakes, see
+/// `LimitedStrengthPlayer`.
+pub fn get_cpu_player(
+    difficulty: &Difficulty,
+    strength: usize,
+    seed: u64,
+) -> Box<dyn MoveGenerator> {
+    let mut move_generator: Box<dyn MoveGenerator> = match difficulty {
         Difficulty::Easy => Box::new(GreadyAlgorithmPlayer::default()),
         Difficulty::Medium => Box::new(RegionCountingPlayer::default()),
         Difficulty::Hard => Box::new(MinmaxPlayer::default()),
+        Difficulty::Expert => Box::new(ExpertPlayer::default()),
+    };
+    if strength < MAX_CPU_STRENGTH {
+        move_generator = Box::new(LimitedStrengthPlayer::new(move_generator, strength));
     }
+    move_generator.reseed(seed);
+    move_generator
 }
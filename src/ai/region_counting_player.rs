@@ -1,22 +1,27 @@
 use super::prelude::*;
 
 #[derive(Default, Debug, Clone)]
-pub struct RegionCountingPlayer {}
+pub struct RegionCountingPlayer {
+    rng: SeededRng,
+}
 
 impl MoveGenerator for RegionCountingPlayer {
-    fn next_move(&self, board: &Board) -> Option<(RowType, ColType)> {
-        if board.all_is_clicked() {
+    fn next_move(&self, board: &Board, player: Player) -> Option<(RowType, ColType)> {
+        let state = BoardState::from(board);
+        if state.all_is_clicked() {
             return None;
         }
-        let walls = collect_wall_statistics(board);
+        let rng = &self.rng;
+        rng.start_move(board, player);
+        let walls = collect_wall_statistics(&state);
 
         let wall_region_size_map =
-            build_region_size_map(board, &walls, |&ws| ws.max_adjacent_counter == 3);
+            build_region_size_map(rng, &state, &walls, |&ws| ws.max_adjacent_counter == 3);
 
         // look for wall adjacent to cell with max counter = 3 and choose the one which creates the biggest region
         if let Some(max_region) = wall_region_size_map.keys().max() {
             if let Some(wall_next_to_almost_closed_cell) =
-                choose_wall_index(&wall_region_size_map[max_region], |&ws| {
+                choose_wall_index(rng, &wall_region_size_map[max_region], |&ws| {
                     ws.max_adjacent_counter == 3
                 })
             {
@@ -29,17 +34,17 @@ impl MoveGenerator for RegionCountingPlayer {
 
         // if none found look for wall adjacent to cell with max counter = 0 or 1
         if let Some(wall_next_to_empty_cell) =
-            choose_wall_index(&walls, |&ws| ws.max_adjacent_counter <= 1)
+            choose_wall_index(rng, &walls, |&ws| ws.max_adjacent_counter <= 1)
         {
             return Some((wall_next_to_empty_cell.row, wall_next_to_empty_cell.col));
         }
 
         // if none found take random with max counter = 2 which will create the smallest region
         let wall_region_size_map =
-            build_region_size_map(board, &walls, |&ws| ws.max_adjacent_counter == 2);
+            build_region_size_map(rng, &state, &walls, |&ws| ws.max_adjacent_counter == 2);
 
         if let Some(min_region) = wall_region_size_map.keys().min() {
-            if let Some(wall) = choose_wall_index(&wall_region_size_map[min_region], |&ws| {
+            if let Some(wall) = choose_wall_index(rng, &wall_region_size_map[min_region], |&ws| {
                 ws.max_adjacent_counter == 2
             }) {
                 return Some((wall.row, wall.col));
@@ -48,6 +53,10 @@ impl MoveGenerator for RegionCountingPlayer {
 
         unreachable!("We should fill all the cases by now!!!")
     }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
 }
 
 #[cfg(test)]
@@ -76,7 +85,7 @@ mod region_counting_player_tests {
 
         let move_generator = RegionCountingPlayer::default();
 
-        let next_move = move_generator.next_move(&board);
+        let next_move = move_generator.next_move(&board, Player::CPU);
 
         assert!(next_move.is_some() && expected_moves.contains(&next_move.unwrap()));
     }
@@ -105,7 +114,7 @@ mod region_counting_player_tests {
 
         let move_generator = RegionCountingPlayer::default();
 
-        let next_move = move_generator.next_move(&board);
+        let next_move = move_generator.next_move(&board, Player::CPU);
         dbg!(&next_move);
 
         assert!(next_move.is_some() && expected_moves.contains(&next_move.unwrap()));
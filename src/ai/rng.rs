@@ -0,0 +1,146 @@
+//! Random numbers of the move generators. Every move generator owns a `SeededRng` and starts
+//! each move by reseeding it from its seed and the position, so the move it picks depends on
+//! the seed and the position only, not on the moves asked for before or on copies made by the
+//! game. Games against the CPU can be replayed from the seed, since the searches of the CPU
+//! players are limited by the number of positions rather than by time. Searches given a time
+//! limit, like those of the tournament, are not replayed.
+
+use quad_rand::{ChooseRandom, RandGenerator};
+
+use super::prelude::*;
+use crate::game::zobrist::mix;
+
+pub struct SeededRng {
+    seed: u64,
+    generator: RandGenerator,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        let generator = RandGenerator::new();
+        generator.srand(seed);
+        Self { seed, generator }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// Restarts the sequence for the move of `player` in the position of the board.
+    pub fn start_move(&self, board: &Board, player: Player) {
+        self.start_position(board.get_zobrist_key() ^ player.index() as u64);
+    }
+
+    /// Restarts the sequence for the position with the given key.
+    pub fn start_position(&self, key: u64) {
+        self.generator.srand(mix(self.seed ^ mix(key)));
+    }
+
+    /// Random number in `low..high`.
+    pub fn gen_range(&self, low: usize, high: usize) -> usize {
+        self.generator.gen_range(low, high)
+    }
+
+    /// Random number in `0.0..1.0`.
+    pub fn gen_fraction(&self) -> f64 {
+        self.generator.rand() as f64 / (u32::MAX as f64 + 1.0)
+    }
+
+    pub fn choose<'a, T>(&self, items: &'a [T]) -> Option<&'a T> {
+        items.choose_with_state(&self.generator)
+    }
+}
+
+impl Default for SeededRng {
+    fn default() -> Self {
+        Self::new(DEFAULT_RNG_SEED)
+    }
+}
+
+/// Copies start from the seed, every move restarts the sequence anyway.
+impl Clone for SeededRng {
+    fn clone(&self) -> Self {
+        Self::new(self.seed)
+    }
+}
+
+impl std::fmt::Debug for SeededRng {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SeededRng")
+            .field("seed", &self.seed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod rng_tests {
+    use super::*;
+    use crate::game::game_match::Match;
+
+    fn draws(rng: &SeededRng) -> Vec<usize> {
+        (0..8).map(|_| rng.gen_range(0, 1000)).collect()
+    }
+
+    #[test]
+    fn same_seed_and_position_give_the_same_numbers() {
+        let board = Board::new(3, 3);
+        let rng = SeededRng::new(7);
+        rng.start_move(&board, Player::CPU);
+        let first = draws(&rng);
+        draws(&rng);
+
+        let copy = rng.clone();
+        copy.start_move(&board, Player::CPU);
+
+        assert_eq!(first, draws(&copy));
+    }
+
+    #[test]
+    fn other_seeds_and_positions_give_other_numbers() {
+        let mut board = Board::new(3, 3);
+        let rng = SeededRng::new(7);
+        rng.start_move(&board, Player::CPU);
+        let first = draws(&rng);
+
+        let other_seed = SeededRng::new(8);
+        other_seed.start_move(&board, Player::CPU);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        rng.start_move(&board, Player::CPU);
+
+        assert_ne!(first, draws(&other_seed));
+        assert_ne!(first, draws(&rng));
+    }
+
+    #[test]
+    fn game_is_replayed_from_the_seed() {
+        let play = |seed| {
+            let mut greedy_player = GreadyAlgorithmPlayer::default();
+            let mut region_counting_player = RegionCountingPlayer::default();
+            greedy_player.reseed(seed);
+            region_counting_player.reseed(seed);
+            let mut game_match = Match::new(Board::new(4, 4), &[Player::Player1, Player::CPU]);
+            game_match.play_out(&[&greedy_player, &region_counting_player]);
+            game_match.board().get_history().to_vec()
+        };
+
+        assert_eq!(play(3), play(3));
+        assert_ne!(play(3), play(4));
+    }
+
+    #[test]
+    fn hard_game_is_replayed_from_the_seed() {
+        let play = |seed| {
+            let hard_player = get_cpu_player(&Difficulty::Hard, MAX_CPU_STRENGTH, seed);
+            let medium_player = get_cpu_player(&Difficulty::Medium, MAX_CPU_STRENGTH, seed);
+            let mut game_match = Match::new(Board::new(3, 3), &[Player::Player1, Player::CPU]);
+            game_match.play_out(&[medium_player.as_ref(), hard_player.as_ref()]);
+            game_match.board().get_history().to_vec()
+        };
+
+        assert_eq!(play(3), play(3));
+    }
+}
@@ -0,0 +1,497 @@
+//! Round robin between move generators, see the `tournament` binary. Every pair of entrants
+//! plays a game on every board size with every seed, once with each of them moving first.
+//! The results are summed up into standings with Elo estimates.
+
+use std::fmt::Write;
+
+use super::prelude::*;
+use crate::game::game_match::Match;
+
+/// Names accepted by `registered_entrant`.
+pub const REGISTERED_ENTRANTS: [&str; 7] = [
+    "Easy",
+    "Medium",
+    "Hard",
+    "Expert",
+    "ChainMinmax",
+    "Mcts",
+    "Nimstring",
+];
+
+/// Move generator taking part in a tournament.
+#[derive(Debug, Clone)]
+pub struct Entrant {
+    pub name: String,
+    move_generator: Box<dyn MoveGenerator>,
+}
+
+impl Entrant {
+    pub fn new(name: &str, move_generator: Box<dyn MoveGenerator>) -> Self {
+        Self {
+            name: name.to_string(),
+            move_generator,
+        }
+    }
+}
+
+/// Entrant of the registered move generator with the given name. The difficulties play like in
+/// the game, the searching move generators think for `time_limit` seconds per move. A name may
+/// end with `@<strength>`, e.g. `Hard@60`, for the move generator limited to that strength, see
+/// `LimitedStrengthPlayer`.
+pub fn registered_entrant(name: &str, time_limit: f64) -> Result<Entrant, String> {
+    if let Some((registered_name, strength)) = name.split_once('@') {
+        let strength = strength
+            .parse()
+            .map_err(|_| format!("Wrong strength '{strength}'"))?;
+        let entrant = registered_entrant(registered_name, time_limit)?;
+        let move_generator = LimitedStrengthPlayer::new(entrant.move_generator, strength);
+        return Ok(Entrant::new(name, Box::new(move_generator)));
+    }
+
+    let mut minmax_player = MinmaxPlayer::new();
+    minmax_player.time_limit = time_limit;
+
+    let move_generator: Box<dyn MoveGenerator> = match name {
+        "Hard" => Box::new(minmax_player),
+        "ChainMinmax" => Box::new(minmax_player.with_evaluator(ChainControl::default())),
+        "Mcts" => Box::new(MctsPlayer::new(
+            SearchBudget::Time(time_limit),
+            RolloutPolicy::Greedy,
+        )),
+        "Nimstring" => Box::new(NimstringPlayer::default()),
+        _ => get_cpu_player(
+            &name
+                .parse::<Difficulty>()
+                .map_err(|_| format!("Unknown entrant '{name}'"))?,
+            MAX_CPU_STRENGTH,
+            DEFAULT_RNG_SEED,
+        ),
+    };
+    Ok(Entrant::new(name, move_generator))
+}
+
+/// Result of one game between two entrants, given by their indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentGame {
+    pub first: usize,
+    pub second: usize,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub points: [usize; 2],
+    /// Seconds the entrants spent on their moves.
+    pub think_time: [f64; 2],
+    pub moves: [usize; 2],
+}
+
+impl TournamentGame {
+    /// Points the entrant scored minus the points of its opponent, or `None` when the entrant
+    /// did not play the game.
+    pub fn margin(&self, entrant: usize) -> Option<i32> {
+        let margin = self.points[0] as i32 - self.points[1] as i32;
+        if entrant == self.first {
+            Some(margin)
+        } else if entrant == self.second {
+            Some(-margin)
+        } else {
+            None
+        }
+    }
+
+    /// 1 for a win, 0.5 for a draw and 0 for a loss of the entrant.
+    fn score(&self, entrant: usize) -> Option<f64> {
+        self.margin(entrant)
+            .map(|margin| (f64::from(margin.signum()) + 1.0) / 2.0)
+    }
+
+    fn seat(&self, entrant: usize) -> usize {
+        if entrant == self.first {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+/// Results of an entrant against all of its opponents.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Standing {
+    pub games: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub margin: i32,
+    pub think_time: f64,
+    pub moves: usize,
+}
+
+impl Standing {
+    fn add(&mut self, game: &TournamentGame, entrant: usize) {
+        let Some(margin) = game.margin(entrant) else {
+            return;
+        };
+        self.games += 1;
+        match margin.signum() {
+            1 => self.wins += 1,
+            0 => self.draws += 1,
+            _ => self.losses += 1,
+        }
+        self.margin += margin;
+        self.think_time += game.think_time[game.seat(entrant)];
+        self.moves += game.moves[game.seat(entrant)];
+    }
+
+    pub fn average_margin(&self) -> f64 {
+        f64::from(self.margin) / self.games.max(1) as f64
+    }
+
+    /// Average time of a move in milliseconds.
+    pub fn average_think_ms(&self) -> f64 {
+        1000.0 * self.think_time / self.moves.max(1) as f64
+    }
+
+    pub fn wdl(&self) -> String {
+        format!("{}/{}/{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// Round robin played one game at a time, like `GameAnalyser` analyses one move at a time.
+#[derive(Debug)]
+pub struct Tournament {
+    entrants: Vec<Entrant>,
+    schedule: Vec<(usize, usize, (usize, usize), u64)>,
+    games: Vec<TournamentGame>,
+}
+
+impl Tournament {
+    pub fn new(entrants: Vec<Entrant>, sizes: &[(usize, usize)], seeds: &[u64]) -> Self {
+        let mut schedule = vec![];
+        for first in 0..entrants.len() {
+            for second in first + 1..entrants.len() {
+                for size in sizes {
+                    for seed in seeds {
+                        schedule.push((first, second, *size, *seed));
+                        schedule.push((second, first, *size, *seed));
+                    }
+                }
+            }
+        }
+        schedule.reverse();
+
+        Self {
+            entrants,
+            schedule,
+            games: vec![],
+        }
+    }
+
+    pub fn entrants(&self) -> &[Entrant] {
+        &self.entrants
+    }
+
+    pub fn games(&self) -> &[TournamentGame] {
+        &self.games
+    }
+
+    pub fn games_left(&self) -> usize {
+        self.schedule.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.schedule.is_empty()
+    }
+
+    /// Plays the next game of the schedule. Returns `None` once all the games are played.
+    pub fn play_next(&mut self) -> Option<&TournamentGame> {
+        let (first, second, (width, height), seed) = self.schedule.pop()?;
+        let move_generators = [first, second].map(|entrant| {
+            let mut move_generator = self.entrants[entrant].move_generator.clone();
+            move_generator.reseed(seed);
+            move_generator.new_game();
+            move_generator
+        });
+        let players = [Player::Player1, Player::Player2];
+        let mut game_match = Match::new(Board::new(width, height), &players);
+        let mut think_time = [0.0; 2];
+        let mut moves = [0; 2];
+        while !game_match.is_over() {
+            let seat = if game_match.current_player() == players[0] {
+                0
+            } else {
+                1
+            };
+            let start = ggez::timer::time();
+            game_match
+                .play_turn(move_generators[seat].as_ref())
+                .expect("move generators return valid moves");
+            think_time[seat] += ggez::timer::time() - start;
+            moves[seat] += 1;
+        }
+
+        let statistics = game_match.get_statistics();
+        self.games.push(TournamentGame {
+            first,
+            second,
+            width,
+            height,
+            seed,
+            points: players.map(|player| statistics.get_points(player)),
+            think_time,
+            moves,
+        });
+        self.games.last()
+    }
+
+    pub fn play_all(&mut self) {
+        while self.play_next().is_some() {}
+    }
+
+    /// Results of the entrant in the games played so far, against everyone or one opponent.
+    pub fn standing(&self, entrant: usize, opponent: Option<usize>) -> Standing {
+        let mut standing = Standing::default();
+        for game in self
+            .games
+            .iter()
+            .filter(|game| opponent.is_none_or(|opponent| game.margin(opponent).is_some()))
+        {
+            standing.add(game, entrant);
+        }
+        standing
+    }
+
+    /// Elo ratings which best explain the results, averaging `TOURNAMENT_ELO_BASE`. Every
+    /// entrant is given a draw against an average opponent, so the ratings stay finite when an
+    /// entrant wins or loses all its games.
+    pub fn elo_ratings(&self) -> Vec<f64> {
+        let count = self.entrants.len();
+        let expected =
+            |rating: f64, opponent: f64| 1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0));
+        let mut ratings = vec![TOURNAMENT_ELO_BASE; count];
+
+        for _ in 0..TOURNAMENT_ELO_ITERATIONS {
+            let mut scores = vec![0.5; count];
+            let mut expected_scores = vec![0.0; count];
+            let mut variances = vec![0.0; count];
+            for (entrant, rating) in ratings.iter().enumerate() {
+                let average = expected(*rating, TOURNAMENT_ELO_BASE);
+                expected_scores[entrant] += average;
+                variances[entrant] += average * (1.0 - average);
+            }
+            for game in &self.games {
+                for (entrant, opponent) in [(game.first, game.second), (game.second, game.first)] {
+                    let expected_score = expected(ratings[entrant], ratings[opponent]);
+                    scores[entrant] += game.score(entrant).expect("entrant played the game");
+                    expected_scores[entrant] += expected_score;
+                    variances[entrant] += expected_score * (1.0 - expected_score);
+                }
+            }
+            // Newton step of the likelihood of the results
+            for entrant in 0..count {
+                ratings[entrant] += 400.0 / std::f64::consts::LN_10
+                    * (scores[entrant] - expected_scores[entrant])
+                    / variances[entrant];
+            }
+        }
+
+        let shift = TOURNAMENT_ELO_BASE - ratings.iter().sum::<f64>() / count.max(1) as f64;
+        ratings.iter().map(|rating| rating + shift).collect()
+    }
+
+    /// Standings and the wins/draws/losses of every pair of entrants as text tables.
+    pub fn to_text(&self) -> String {
+        let width = self
+            .entrants
+            .iter()
+            .map(|entrant| entrant.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Entrant".len());
+        let ratings = self.elo_ratings();
+        let mut text = format!(
+            "{:width$}  {:>5}  {:>11}  {:>7}  {:>8}  {:>5}\n",
+            "Entrant", "Games", "W/D/L", "Margin", "Think ms", "Elo"
+        );
+        for (entrant, rating) in self.ranking(&ratings) {
+            let standing = self.standing(entrant, None);
+            let _ = writeln!(
+                text,
+                "{:width$}  {:>5}  {:>11}  {:>7.2}  {:>8.1}  {:>5.0}",
+                self.entrants[entrant].name,
+                standing.games,
+                standing.wdl(),
+                standing.average_margin(),
+                standing.average_think_ms(),
+                rating
+            );
+        }
+
+        let _ = write!(text, "\n{:width$}", "W/D/L");
+        for opponent in &self.entrants {
+            let _ = write!(text, "  {:>11}", opponent.name);
+        }
+        for (entrant, entrant_data) in self.entrants.iter().enumerate() {
+            let _ = write!(text, "\n{:width$}", entrant_data.name);
+            for opponent in 0..self.entrants.len() {
+                let wdl = if entrant == opponent {
+                    "-".to_string()
+                } else {
+                    self.standing(entrant, Some(opponent)).wdl()
+                };
+                let _ = write!(text, "  {wdl:>11}");
+            }
+        }
+        text + "\n"
+    }
+
+    /// Standings as CSV, one line per entrant and per pair of entrants. The opponent of the
+    /// standings against everyone is `All`.
+    pub fn to_csv(&self) -> String {
+        let ratings = self.elo_ratings();
+        let mut csv =
+            "entrant,opponent,games,wins,draws,losses,average_margin,average_think_ms,elo\n"
+                .to_string();
+        for (entrant, rating) in self.ranking(&ratings) {
+            let opponents = std::iter::once(None).chain(
+                (0..self.entrants.len())
+                    .filter(|opponent| *opponent != entrant)
+                    .map(Some),
+            );
+            for opponent in opponents {
+                let standing = self.standing(entrant, opponent);
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{:.3},{:.3},{:.0}",
+                    self.entrants[entrant].name,
+                    opponent.map_or("All", |opponent| &self.entrants[opponent].name),
+                    standing.games,
+                    standing.wins,
+                    standing.draws,
+                    standing.losses,
+                    standing.average_margin(),
+                    standing.average_think_ms(),
+                    rating
+                );
+            }
+        }
+        csv
+    }
+
+    /// Entrants with their ratings, the best first.
+    fn ranking(&self, ratings: &[f64]) -> Vec<(usize, f64)> {
+        let mut ranking = ratings.iter().copied().enumerate().collect::<Vec<_>>();
+        ranking.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranking
+    }
+}
+
+#[cfg(test)]
+mod tournament_tests {
+    use super::*;
+
+    fn game(first: usize, second: usize, points: [usize; 2]) -> TournamentGame {
+        TournamentGame {
+            first,
+            second,
+            width: 2,
+            height: 2,
+            seed: 0,
+            points,
+            think_time: [0.0; 2],
+            moves: [6; 2],
+        }
+    }
+
+    fn tournament_with_games(games: Vec<TournamentGame>) -> Tournament {
+        let entrants = ["Easy", "Medium", "Expert"]
+            .iter()
+            .map(|name| registered_entrant(name, 0.0).expect("entrant is registered"))
+            .collect();
+        let mut tournament = Tournament::new(entrants, &[], &[]);
+        tournament.games = games;
+        tournament
+    }
+
+    #[test]
+    fn every_pair_plays_with_either_entrant_first() {
+        let entrants = ["Easy", "Easy"]
+            .iter()
+            .map(|name| registered_entrant(name, 0.0).expect("entrant is registered"))
+            .collect();
+        let mut tournament = Tournament::new(entrants, &[(2, 2), (3, 1)], &[0, 1]);
+        assert_eq!(8, tournament.games_left());
+
+        tournament.play_all();
+
+        assert!(tournament.is_finished());
+        let games = tournament.games();
+        assert_eq!(4, games.iter().filter(|game| game.first == 0).count());
+        assert_eq!(4, games.iter().filter(|game| game.first == 1).count());
+        assert!(games
+            .iter()
+            .all(|game| game.points[0] + game.points[1] == game.width * game.height));
+        assert_eq!(8, tournament.standing(0, Some(1)).games);
+    }
+
+    #[test]
+    fn standings_count_wins_draws_and_losses() {
+        let tournament = tournament_with_games(vec![
+            game(0, 1, [3, 1]),
+            game(1, 0, [2, 2]),
+            game(2, 0, [4, 0]),
+        ]);
+
+        let standing = tournament.standing(0, None);
+
+        assert_eq!("1/1/1", standing.wdl());
+        assert_eq!(-2, standing.margin);
+        assert_eq!("1/1/0", tournament.standing(0, Some(1)).wdl());
+    }
+
+    #[test]
+    fn stronger_entrants_are_rated_higher() {
+        let tournament = tournament_with_games(vec![
+            game(0, 1, [1, 3]),
+            game(1, 0, [3, 1]),
+            game(1, 2, [1, 3]),
+            game(2, 1, [3, 1]),
+            game(0, 2, [0, 4]),
+        ]);
+
+        let ratings = tournament.elo_ratings();
+
+        assert!(
+            ratings[0] < ratings[1] && ratings[1] < ratings[2],
+            "{ratings:?}"
+        );
+        let average = ratings.iter().sum::<f64>() / 3.0;
+        assert!((average - TOURNAMENT_ELO_BASE).abs() < 1e-6);
+    }
+
+    #[test]
+    fn equal_results_give_equal_ratings() {
+        let tournament = tournament_with_games(vec![game(0, 1, [2, 2]), game(1, 2, [2, 2])]);
+
+        for rating in tournament.elo_ratings() {
+            assert!((rating - TOURNAMENT_ELO_BASE).abs() < 1e-6, "{rating}");
+        }
+    }
+
+    #[test]
+    fn entrants_may_be_limited_in_strength() {
+        let entrant = registered_entrant("Medium@40", 0.0).expect("entrant is registered");
+
+        assert_eq!("Medium@40", entrant.name);
+        assert!(registered_entrant("Medium@strong", 0.0).is_err());
+        assert!(registered_entrant("Unknown@40", 0.0).is_err());
+    }
+
+    #[test]
+    fn csv_has_a_line_per_entrant_and_pair() {
+        let tournament = tournament_with_games(vec![game(0, 1, [3, 1])]);
+
+        let csv = tournament.to_csv();
+
+        assert_eq!(1 + 3 * 3, csv.lines().count());
+        assert!(csv.contains("Easy,Medium,1,1,0,0,2.000,0.000,"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Transposition table of `MinmaxPlayer`. Positions are kept in a fixed number of slots picked
+//! by their key, so the table never takes more than the memory it is given. When two positions
+//! of the same search meet in a slot the one searched deeper stays, positions left by earlier
+//! searches give way to the current one. Scores of searches cut off by alpha-beta are kept as
+//! bounds and are only reused where the bound decides the node.
+
+use super::prelude::*;
+
+/// What the stored score tells about the value of the position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Bound {
+    Exact,
+    /// The value is at least the score.
+    Lower,
+    /// The value is at most the score.
+    Upper,
+}
+
+impl Bound {
+    /// Bound of the score of a search in the window `alpha..=beta`.
+    pub fn of(score: i32, alpha: i32, beta: i32) -> Self {
+        if score < alpha {
+            Bound::Upper
+        } else if score > beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        }
+    }
+}
+
+/// Result of the search of a position `depth` turns deep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableEntry {
+    key: u64,
+    generation: u32,
+    pub depth: usize,
+    pub score: i32,
+    pub bound: Bound,
+    /// Best moves, at most `TRANSPOSITION_TABLE_MOVES` of them.
+    pub moves: Vec<(RowType, ColType)>,
+}
+
+impl TableEntry {
+    /// Tells whether the entry decides the value of a search `depth` turns deep in the window
+    /// `alpha..=beta`.
+    pub fn decides(&self, depth: usize, alpha: i32, beta: i32) -> bool {
+        self.depth >= depth
+            && match self.bound {
+                Bound::Exact => true,
+                Bound::Lower => self.score > beta,
+                Bound::Upper => self.score < alpha,
+            }
+    }
+}
+
+pub struct TranspositionTable {
+    slots: Vec<Option<TableEntry>>,
+    memory: usize,
+    slot_count: usize,
+    used: usize,
+    generation: u32,
+}
+
+impl TranspositionTable {
+    /// Table taking at most `memory` bytes. The slots are allocated by the first `store`.
+    pub fn new(memory: usize) -> Self {
+        let entry_size = std::mem::size_of::<Option<TableEntry>>()
+            + TRANSPOSITION_TABLE_MOVES * std::mem::size_of::<(RowType, ColType)>();
+        Self {
+            slots: vec![],
+            memory,
+            slot_count: (memory / entry_size).max(1),
+            used: 0,
+            generation: 0,
+        }
+    }
+
+    /// Starts the search of the next move. The entries of the earlier searches are still found,
+    /// but any new result takes their slot.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slot_count
+    }
+
+    /// Number of the positions kept.
+    pub fn len(&self) -> usize {
+        self.used
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.used == 0
+    }
+
+    pub fn probe(&self, key: u64) -> Option<&TableEntry> {
+        self.slots
+            .get(self.slot(key))?
+            .as_ref()
+            .filter(|entry| entry.key == key)
+    }
+
+    /// Keeps the result unless the slot holds a position searched deeper by the current search.
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: usize,
+        score: i32,
+        bound: Bound,
+        moves: &[(RowType, ColType)],
+    ) {
+        if self.slots.is_empty() {
+            self.slots.resize(self.slot_count, None);
+        }
+        let slot = self.slot(key);
+        match &self.slots[slot] {
+            Some(entry) if entry.generation == self.generation && entry.depth > depth => return,
+            Some(_) => {}
+            None => self.used += 1,
+        }
+        self.slots[slot] = Some(TableEntry {
+            key,
+            generation: self.generation,
+            depth,
+            score,
+            bound,
+            moves: moves[..moves.len().min(TRANSPOSITION_TABLE_MOVES)].to_vec(),
+        });
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        (key % self.slot_count as u64) as usize
+    }
+}
+
+impl std::fmt::Debug for TranspositionTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("slot_count", &self.slot_count)
+            .field("used", &self.used)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod transposition_table_tests {
+    use super::*;
+
+    fn table_with_slots(slot_count: usize) -> TranspositionTable {
+        let mut table = TranspositionTable::new(0);
+        table.slot_count = slot_count;
+        table
+    }
+
+    #[test]
+    fn memory_limits_the_slots() {
+        let small = TranspositionTable::new(1024);
+        let large = TranspositionTable::new(1024 * 1024);
+
+        let entry_size = 1024 * 1024 / large.slot_count();
+
+        assert!(small.slot_count() >= 1);
+        assert!(large.slot_count() >= 1000 * small.slot_count());
+        assert!(entry_size >= std::mem::size_of::<Option<TableEntry>>());
+        assert!(large.is_empty());
+    }
+
+    #[test]
+    fn deeper_searches_stay_in_the_slot() {
+        let mut table = table_with_slots(4);
+        table.store(1, 3, 10, Bound::Exact, &[(0, 0)]);
+        table.store(5, 2, 20, Bound::Exact, &[(1, 0)]);
+
+        assert_eq!(10, table.probe(1).expect("deeper entry stays").score);
+        assert!(table.probe(5).is_none());
+
+        table.store(5, 3, 30, Bound::Lower, &[(1, 0)]);
+
+        assert!(table.probe(1).is_none());
+        assert_eq!(Bound::Lower, table.probe(5).expect("entry is kept").bound);
+        assert_eq!(1, table.len());
+    }
+
+    #[test]
+    fn earlier_searches_give_way() {
+        let mut table = table_with_slots(4);
+        table.store(1, 6, 10, Bound::Exact, &[(0, 0)]);
+        table.new_search();
+
+        assert_eq!(10, table.probe(1).expect("earlier entry is found").score);
+
+        table.store(5, 1, 20, Bound::Exact, &[(1, 0)]);
+
+        assert!(table.probe(1).is_none());
+        assert_eq!(20, table.probe(5).expect("entry is kept").score);
+
+        table.store(1, 0, 30, Bound::Exact, &[(0, 0)]);
+
+        assert_eq!(20, table.probe(5).expect("deeper entry stays").score);
+        assert_eq!(1, table.len());
+    }
+
+    #[test]
+    fn bounds_decide_only_outside_the_window() {
+        let mut table = table_with_slots(4);
+        table.store(0, 2, 5, Bound::Lower, &[]);
+        table.store(1, 2, 5, Bound::Upper, &[]);
+        table.store(2, 2, 5, Bound::Exact, &[]);
+        let lower = table.probe(0).expect("entry is kept");
+        let upper = table.probe(1).expect("entry is kept");
+        let exact = table.probe(2).expect("entry is kept");
+
+        assert!(lower.decides(2, 0, 4));
+        assert!(!lower.decides(2, 0, 5));
+        assert!(upper.decides(1, 6, 10));
+        assert!(!upper.decides(2, 5, 10));
+        assert!(exact.decides(2, 6, 10));
+        assert!(!exact.decides(3, 0, 10));
+
+        assert_eq!(Bound::Upper, Bound::of(-1, 0, 4));
+        assert_eq!(Bound::Exact, Bound::of(4, 0, 4));
+        assert_eq!(Bound::Lower, Bound::of(5, 0, 4));
+    }
+
+    #[test]
+    fn stored_moves_are_limited() {
+        let mut table = table_with_slots(1);
+        let moves = (0..2 * TRANSPOSITION_TABLE_MOVES)
+            .map(|col| (0, col))
+            .collect::<Vec<_>>();
+        table.store(0, 1, 0, Bound::Exact, &moves);
+
+        assert_eq!(
+            moves[..TRANSPOSITION_TABLE_MOVES],
+            table.probe(0).expect("entry is kept").moves
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Builds the opening book embedded in the game, see `opening_book`. Every position with up to
+//! the given number of clicked walls is solved exactly when at most
+//! `OPENING_BOOK_SOLVER_WALLS` walls are left. The positions of larger boards, which cannot be
+//! solved, are listed up to `--played-walls` walls: every safe wall is played out by two
+//! `ExpertPlayer`s from the given number of seeds, and the walls whose average margin is within
+//! `OPENING_BOOK_MARGIN_TOLERANCE` houses of the best one are recommended. The best moves are
+//! written to the standard output unless they take in every safe wall, which tells the CPU
+//! players nothing, e.g.
+//!
+//! `cargo run --release --bin build_opening_book > src/ai/opening_book.txt`
+
+use std::collections::HashMap;
+
+use streets_and_houses::ai::prelude::*;
+use streets_and_houses::game::game_match::Match;
+use streets_and_houses::game::settings::parse_board_size;
+
+const USAGE: &str = "Usage: build_opening_book [--sizes <width>x<height>,...] [--walls <count>] \
+[--played-walls <count>] [--games <count>]";
+
+#[derive(Debug)]
+struct Options {
+    sizes: Vec<(usize, usize)>,
+    walls: usize,
+    played_walls: usize,
+    games: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            sizes: vec![(3, 3), (4, 4), (5, 5), (6, 6)],
+            walls: 3,
+            played_walls: 1,
+            games: 256,
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or(format!("Missing value of option '{option}'"))?;
+        match option.as_str() {
+            "--sizes" => {
+                options.sizes = value
+                    .split(',')
+                    .map(parse_board_size)
+                    .collect::<Result<_, _>>()?
+            }
+            "--walls" => {
+                options.walls = value
+                    .parse()
+                    .map_err(|_| format!("Wrong number of walls '{value}'"))?
+            }
+            "--played-walls" => {
+                options.played_walls = value
+                    .parse()
+                    .map_err(|_| format!("Wrong number of walls '{value}'"))?
+            }
+            "--games" => {
+                options.games = value
+                    .parse()
+                    .ok()
+                    .filter(|games| *games > 0)
+                    .ok_or(format!("Wrong number of games '{value}'"))?
+            }
+            _ => return Err(format!("Unknown option '{option}'")),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut book = OpeningBook::new();
+    for (width, height) in &options.sizes {
+        // playing the positions out takes much longer than solving them, so fewer are listed
+        let all_walls = BoardState::from(&Board::new(*width, *height)).unclicked_count();
+        let max_walls = if all_walls <= OPENING_BOOK_SOLVER_WALLS + options.walls {
+            options.walls
+        } else {
+            options.played_walls
+        };
+        let positions = opening_positions(*width, *height, max_walls);
+        // the solved positions of a board share most of their continuations
+        let mut solver = EndgameSolver::new();
+        let mut kept = 0;
+        for walls in &positions {
+            let mut board = Board::new(*width, *height);
+            for (row, col) in walls {
+                board
+                    .click_wall(*row, *col, Player::Player1)
+                    .expect("wall of the position can be clicked");
+            }
+            let mut state = BoardState::from(&board);
+            let mut moves = if state.unclicked_count() <= OPENING_BOOK_SOLVER_WALLS {
+                let (_, moves) = solver.best_moves(&mut state);
+                moves.iter().map(|wall| state.wall_id(*wall)).collect()
+            } else {
+                played_moves(&board, walls, options.games)
+            };
+            if takes_in_every_safe_wall(&state, &moves) {
+                continue;
+            }
+            moves.sort();
+            book.insert(*width, *height, walls, &moves);
+            kept += 1;
+        }
+        eprintln!("{width}x{height}: {kept} of {} positions", positions.len());
+    }
+    print!("{book}");
+}
+
+/// Safe walls whose games, played out from the position by `ExpertPlayer`s, end on average
+/// within `OPENING_BOOK_MARGIN_TOLERANCE` houses of the best one. Walls which give symmetric
+/// positions share their games.
+fn played_moves(
+    board: &Board,
+    walls: &[(RowType, ColType)],
+    games: u64,
+) -> Vec<(RowType, ColType)> {
+    let state = BoardState::from(board);
+    let mut margins = HashMap::new();
+    let moves = ChainAnalysis::new(&state)
+        .safe_walls
+        .iter()
+        .map(|wall| {
+            let (row, col) = state.wall_id(*wall);
+            let mut child = walls.to_vec();
+            child.push((row, col));
+            let key = symmetric_position_key(&child, board.width, board.height);
+            let margin = *margins
+                .entry(key)
+                .or_insert_with(|| average_margin(board, (row, col), games));
+            ((row, col), margin)
+        })
+        .collect::<Vec<_>>();
+    let best = moves
+        .iter()
+        .map(|(_, margin)| *margin)
+        .fold(f64::MIN, f64::max);
+    moves
+        .into_iter()
+        .filter(|(_, margin)| *margin >= best - OPENING_BOOK_MARGIN_TOLERANCE)
+        .map(|(wall, _)| wall)
+        .collect()
+}
+
+/// Houses the player clicking the wall gets more than the opponent, on average over the games
+/// played out by `ExpertPlayer`s from the seeds `0..games`.
+fn average_margin(board: &Board, (row, col): (RowType, ColType), games: u64) -> f64 {
+    let total = (0..games)
+        .map(|seed| {
+            let mut game_match = Match::new(board.clone(), &[Player::Player1, Player::CPU]);
+            game_match.play(row, col).expect("safe wall can be clicked");
+            let mut move_generators = [ExpertPlayer::default(), ExpertPlayer::default()];
+            for (idx, move_generator) in move_generators.iter_mut().enumerate() {
+                move_generator.opening_book = false;
+                move_generator.reseed(2 * seed + idx as u64);
+            }
+            let statistics = game_match.play_out(&[&move_generators[0], &move_generators[1]]);
+            statistics.get_points(Player::Player1) as f64
+                - statistics.get_points(Player::CPU) as f64
+        })
+        .sum::<f64>();
+    total / games as f64
+}
+
+/// Tells whether the moves leave no safe wall out, so they do not tell the good moves from
+/// the bad ones.
+fn takes_in_every_safe_wall(state: &BoardState, moves: &[(RowType, ColType)]) -> bool {
+    ChainAnalysis::new(state)
+        .safe_walls
+        .iter()
+        .all(|wall| moves.contains(&state.wall_id(*wall)))
+}
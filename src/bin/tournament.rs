@@ -0,0 +1,118 @@
+//! Plays a round robin between the CPU players without the user interface and prints the
+//! standings, e.g.
+//!
+//! `cargo run --release --bin tournament -- --entrants Easy,Medium,Hard --sizes 3x3,4x4 --seeds 4`
+
+use streets_and_houses::ai::prelude::*;
+use streets_and_houses::ai::tournament::*;
+use streets_and_houses::game::settings::parse_board_size;
+
+const USAGE: &str = "Usage: tournament [--entrants <name>,...] [--sizes <width>x<height>,...] \
+[--seeds <count>] [--time-limit <seconds>] [--format text|csv]";
+
+#[derive(Debug)]
+struct Options {
+    entrants: Vec<String>,
+    sizes: Vec<(usize, usize)>,
+    seeds: u64,
+    time_limit: f64,
+    csv: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            entrants: ["Easy", "Medium", "Hard"].map(String::from).to_vec(),
+            sizes: vec![(3, 3)],
+            seeds: 2,
+            time_limit: MINMAX_TIME_LIMIT,
+            csv: false,
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or(format!("Missing value of option '{option}'"))?;
+        match option.as_str() {
+            "--entrants" => options.entrants = value.split(',').map(String::from).collect(),
+            "--sizes" => {
+                options.sizes = value
+                    .split(',')
+                    .map(parse_board_size)
+                    .collect::<Result<_, _>>()?
+            }
+            "--seeds" => {
+                options.seeds = value
+                    .parse()
+                    .map_err(|_| format!("Wrong number of seeds '{value}'"))?
+            }
+            "--time-limit" => {
+                options.time_limit = value
+                    .parse()
+                    .map_err(|_| format!("Wrong time limit '{value}'"))?
+            }
+            "--format" => {
+                options.csv = match value.as_str() {
+                    "text" => false,
+                    "csv" => true,
+                    _ => return Err(format!("Unknown format '{value}'")),
+                }
+            }
+            _ => return Err(format!("Unknown option '{option}'")),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!(
+                "{err}\n{USAGE}\nEntrants: {}",
+                REGISTERED_ENTRANTS.join(", ")
+            );
+            std::process::exit(2);
+        }
+    };
+    let entrants = match options
+        .entrants
+        .iter()
+        .map(|name| registered_entrant(name.trim(), options.time_limit))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(entrants) => entrants,
+        Err(err) => {
+            eprintln!("{err}\nEntrants: {}", REGISTERED_ENTRANTS.join(", "));
+            std::process::exit(2);
+        }
+    };
+
+    let seeds = (0..options.seeds).collect::<Vec<_>>();
+    let mut tournament = Tournament::new(entrants, &options.sizes, &seeds);
+    while !tournament.is_finished() {
+        let games_left = tournament.games_left();
+        let game = tournament.play_next().expect("games are left").clone();
+        let names = tournament.entrants();
+        eprintln!(
+            "{} - {} on {}x{}, seed {}: {}:{} ({games_left} left)",
+            names[game.first].name,
+            names[game.second].name,
+            game.width,
+            game.height,
+            game.seed,
+            game.points[0],
+            game.points[1],
+        );
+    }
+
+    if options.csv {
+        print!("{}", tournament.to_csv());
+    } else {
+        print!("{}", tournament.to_text());
+    }
+}
@@ -19,6 +19,86 @@ pub fn save_file(filename : &str, contents : &str)-> std::io::Result<()> {
     Ok(())
 }
 
+/// Opens the browser file picker. The chosen file is available through `take_uploaded_file`
+/// once the browser has read it.
+pub fn open_file_picker() {
+    unsafe {
+        js_open_file_picker();
+    }
+}
+
+pub fn take_uploaded_file() -> Option<String> {
+    let len = unsafe { js_uploaded_file_len() };
+    if len < 0 {
+        return None;
+    }
+    let mut data = vec![0u8; len as usize];
+    unsafe {
+        js_take_uploaded_file(data.as_mut_ptr(), data.len());
+    }
+    String::from_utf8(data).ok()
+}
+
+/// Reads the most recently modified file in the working directory whose name ends with `suffix`.
+pub fn load_latest_file(suffix : &str) -> std::io::Result<String> {
+    use std::fs;
+    use std::io::{Error, ErrorKind};
+
+    let latest = fs::read_dir(".")?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(suffix))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no file ending with '{suffix}'")))?;
+
+    fs::read_to_string(latest)
+}
+
+/// Reads the contents kept by `store` under the key. The browser keeps them in its local
+/// storage, the native build in a file of that name in the working directory.
+pub fn load_stored(key: &str) -> Option<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let len = unsafe { js_storage_len(key.as_ptr(), key.len()) };
+        if len < 0 {
+            return None;
+        }
+        let mut data = vec![0u8; len as usize];
+        unsafe {
+            js_storage_get(key.as_ptr(), key.len(), data.as_mut_ptr(), data.len());
+        }
+        String::from_utf8(data).ok()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read_to_string(key).ok()
+    }
+}
+
+pub fn store(key: &str, contents: &str) -> std::io::Result<()> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        unsafe {
+            js_storage_set(key.as_ptr(), key.len(), contents.as_ptr(), contents.len());
+        }
+        Ok(())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        save_file(key, contents)
+    }
+}
+
 unsafe extern "C" {
     unsafe fn js_create_download(ptr: *const u8, len : usize, name_ptr: *const u8, name_len: usize);
+    unsafe fn js_open_file_picker();
+    unsafe fn js_uploaded_file_len() -> i32;
+    unsafe fn js_take_uploaded_file(ptr: *mut u8, len : usize);
+    #[cfg(target_arch = "wasm32")]
+    unsafe fn js_storage_len(key_ptr: *const u8, key_len: usize) -> i32;
+    #[cfg(target_arch = "wasm32")]
+    unsafe fn js_storage_get(key_ptr: *const u8, key_len: usize, ptr: *mut u8, len: usize);
+    #[cfg(target_arch = "wasm32")]
+    unsafe fn js_storage_set(key_ptr: *const u8, key_len: usize, ptr: *const u8, len: usize);
 }
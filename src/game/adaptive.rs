@@ -0,0 +1,274 @@
+//! History of the one-player games against the adaptive CPU. The strength of the CPU, see
+//! `LimitedStrengthPlayer`, goes up by `ADAPTIVE_STRENGTH_STEP` after every win of the human and
+//! down after every loss, so it settles where the human wins about half of the games. Every
+//! human seat keeps its own strength, stored by `file::store` between the sessions.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::*;
+use crate::file;
+
+/// Result of a game for the human of a one-player game.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameOutcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl GameOutcome {
+    pub fn of(statistics: &GameStatistics, player: Player) -> Self {
+        match statistics.winner {
+            Some(winner) if winner == player => GameOutcome::Win,
+            Some(_) => GameOutcome::Loss,
+            None => GameOutcome::Draw,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            GameOutcome::Win => 'W',
+            GameOutcome::Draw => 'D',
+            GameOutcome::Loss => 'L',
+        }
+    }
+
+    fn from_symbol(symbol: char) -> Result<Self, String> {
+        match symbol {
+            'W' => Ok(GameOutcome::Win),
+            'D' => Ok(GameOutcome::Draw),
+            'L' => Ok(GameOutcome::Loss),
+            _ => Err(format!("Unknown game outcome '{symbol}'")),
+        }
+    }
+}
+
+/// Strength of the CPU before and after a game against the adaptive CPU.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LevelChange {
+    pub previous: usize,
+    pub current: usize,
+}
+
+/// Strength of the adaptive CPU for one human and the outcomes of the last games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerHistory {
+    pub strength: usize,
+    pub outcomes: Vec<GameOutcome>,
+}
+
+impl Default for PlayerHistory {
+    fn default() -> Self {
+        Self {
+            strength: ADAPTIVE_INITIAL_STRENGTH,
+            outcomes: vec![],
+        }
+    }
+}
+
+impl PlayerHistory {
+    /// Adds the outcome and moves the strength towards the one the human wins half of the
+    /// games against. Only the last `ADAPTIVE_HISTORY_LENGTH` outcomes are kept.
+    pub fn record(&mut self, outcome: GameOutcome) -> LevelChange {
+        let previous = self.strength;
+        self.strength = match outcome {
+            GameOutcome::Win => (self.strength + ADAPTIVE_STRENGTH_STEP).min(MAX_CPU_STRENGTH),
+            GameOutcome::Draw => self.strength,
+            GameOutcome::Loss => self.strength.saturating_sub(ADAPTIVE_STRENGTH_STEP),
+        };
+        self.outcomes.push(outcome);
+        let excess = self.outcomes.len().saturating_sub(ADAPTIVE_HISTORY_LENGTH);
+        self.outcomes.drain(..excess);
+
+        LevelChange {
+            previous,
+            current: self.strength,
+        }
+    }
+
+    /// Share of the kept games won by the human, draws count as half a win.
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let points = self
+            .outcomes
+            .iter()
+            .map(|outcome| match outcome {
+                GameOutcome::Win => 1.0,
+                GameOutcome::Draw => 0.5,
+                GameOutcome::Loss => 0.0,
+            })
+            .sum::<f64>();
+        Some(points / self.outcomes.len() as f64)
+    }
+}
+
+/// Histories of all the human seats which played against the adaptive CPU.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdaptiveHistory {
+    players: HashMap<Player, PlayerHistory>,
+}
+
+impl AdaptiveHistory {
+    /// History stored by `save`, or an empty one when nothing is stored yet.
+    pub fn load() -> Self {
+        let Some(contents) = file::load_stored(ADAPTIVE_HISTORY_FILE) else {
+            return Self::default();
+        };
+        Self::from_str(&contents).unwrap_or_else(|err| {
+            eprintln!("Failed to load the adaptive history. Error occurred: '{err}'");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = file::store(ADAPTIVE_HISTORY_FILE, &self.to_string()) {
+            eprintln!("Failed to save the adaptive history. Error occurred: {err}");
+        }
+    }
+
+    pub fn player(&self, player: Player) -> PlayerHistory {
+        self.players.get(&player).cloned().unwrap_or_default()
+    }
+
+    /// Strength of the adaptive CPU in the next game of the player.
+    pub fn strength(&self, player: Player) -> usize {
+        self.player(player).strength
+    }
+
+    pub fn record(&mut self, player: Player, outcome: GameOutcome) -> LevelChange {
+        self.players.entry(player).or_default().record(outcome)
+    }
+}
+
+/// Writes a line per player, e.g. `Player1:60:WWLDW`.
+impl std::fmt::Display for AdaptiveHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut players = self.players.iter().collect::<Vec<_>>();
+        players.sort_by_key(|(player, _)| player.index());
+        for (player, history) in players {
+            let outcomes = history
+                .outcomes
+                .iter()
+                .map(GameOutcome::symbol)
+                .collect::<String>();
+            writeln!(f, "{player:?}:{}:{outcomes}", history.strength)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AdaptiveHistory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut players = HashMap::new();
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let mut fields = line.trim().split(':');
+            let (Some(player), Some(strength), Some(outcomes), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(format!("Unexpected line '{line}'"));
+            };
+            let strength = usize::from_str(strength)
+                .map_err(|_| format!("Wrong strength '{strength}'"))?
+                .min(MAX_CPU_STRENGTH);
+            let outcomes = outcomes
+                .chars()
+                .map(GameOutcome::from_symbol)
+                .collect::<Result<_, _>>()?;
+            players.insert(
+                Player::from_str(player)?,
+                PlayerHistory { strength, outcomes },
+            );
+        }
+        Ok(Self { players })
+    }
+}
+
+#[cfg(test)]
+mod adaptive_tests {
+    use super::*;
+
+    #[test]
+    fn strength_follows_the_results() {
+        let mut history = AdaptiveHistory::default();
+
+        let change = history.record(Player::Player1, GameOutcome::Win);
+        history.record(Player::Player1, GameOutcome::Win);
+        history.record(Player::Player1, GameOutcome::Draw);
+        history.record(Player::Player2, GameOutcome::Loss);
+
+        assert_eq!(ADAPTIVE_INITIAL_STRENGTH, change.previous);
+        assert_eq!(
+            ADAPTIVE_INITIAL_STRENGTH + ADAPTIVE_STRENGTH_STEP,
+            change.current
+        );
+        assert_eq!(
+            ADAPTIVE_INITIAL_STRENGTH + 2 * ADAPTIVE_STRENGTH_STEP,
+            history.strength(Player::Player1)
+        );
+        assert_eq!(
+            ADAPTIVE_INITIAL_STRENGTH - ADAPTIVE_STRENGTH_STEP,
+            history.strength(Player::Player2)
+        );
+        assert_eq!(ADAPTIVE_INITIAL_STRENGTH, history.strength(Player::Player3));
+    }
+
+    #[test]
+    fn strength_stays_in_range_and_old_results_are_dropped() {
+        let mut history = PlayerHistory::default();
+        for _ in 0..ADAPTIVE_HISTORY_LENGTH {
+            history.record(GameOutcome::Win);
+        }
+        history.record(GameOutcome::Loss);
+
+        assert_eq!(MAX_CPU_STRENGTH - ADAPTIVE_STRENGTH_STEP, history.strength);
+        assert_eq!(ADAPTIVE_HISTORY_LENGTH, history.outcomes.len());
+        assert_eq!(Some(GameOutcome::Loss), history.outcomes.last().copied());
+
+        for _ in 0..ADAPTIVE_HISTORY_LENGTH {
+            history.record(GameOutcome::Loss);
+        }
+        assert_eq!(0, history.strength);
+        assert_eq!(Some(0.0), history.win_rate());
+    }
+
+    #[test]
+    fn history_is_written_and_read_back() {
+        let mut history = AdaptiveHistory::default();
+        history.record(Player::Player2, GameOutcome::Draw);
+        history.record(Player::Player1, GameOutcome::Win);
+        history.record(Player::Player1, GameOutcome::Loss);
+
+        let text = history.to_string();
+
+        assert_eq!(
+            format!(
+                "Player1:{ADAPTIVE_INITIAL_STRENGTH}:WL\nPlayer2:{ADAPTIVE_INITIAL_STRENGTH}:D\n"
+            ),
+            text
+        );
+        assert_eq!(Ok(history), AdaptiveHistory::from_str(&text));
+        assert!(AdaptiveHistory::from_str("Player1:60:WX").is_err());
+        assert!(AdaptiveHistory::from_str("Player1:60").is_err());
+    }
+
+    #[test]
+    fn outcome_is_seen_by_the_given_player() {
+        let mut board = Board::new(1, 1);
+        for (row, col) in [(0, 0), (1, 0), (1, 1)] {
+            let _ = board.click_wall(row, col, Player::CPU);
+        }
+        let _ = board.click_wall(2, 0, Player::Player1);
+        let statistics = board.get_statistics();
+
+        assert_eq!(
+            GameOutcome::Win,
+            GameOutcome::of(&statistics, Player::Player1)
+        );
+        assert_eq!(GameOutcome::Loss, GameOutcome::of(&statistics, Player::CPU));
+    }
+}
@@ -46,9 +46,9 @@ impl<S: GameStateMarker> Game<S> {
 
 // Specific implementations for each state
 impl Game<MainMenuState> {
-    pub fn new(ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext) -> Self {
+    pub fn new(ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext, seed: u64) -> Self {
         Self {
-            current_scene: Box::new(MainMenuScene::new(ctx, quad_ctx).expect("scene has been created")),
+            current_scene: Box::new(MainMenuScene::new(ctx, quad_ctx, seed).expect("scene has been created")),
         }
     }
 
@@ -100,9 +100,13 @@ impl Game<PlayingState> {
 }
 
 impl Game<TitleScreenState> {
-    pub fn new(ctx: &mut ggez::Context, quad_ctx: &mut ggez::event::GraphicsContext) -> Self {
+    pub fn new(
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut ggez::event::GraphicsContext,
+        seed: u64,
+    ) -> Self {
         Self {
-            current_scene: Box::new(TitleScreenScene::new(ctx, quad_ctx)),
+            current_scene: Box::new(TitleScreenScene::new(ctx, quad_ctx, seed)),
         }
     }
     pub fn transition(self, transition: Transition) -> Box<dyn GameInstance> {
@@ -179,6 +183,9 @@ impl<S: GameStateMarker + 'static> GameInstance for Game<S> {
             Transition::ToTitleScreen(scene) => Box::new(Game {
                 current_scene: scene,
             }),
+            Transition::ToAnalysis(scene) => Box::new(Game {
+                current_scene: scene,
+            }),
         }
     }
 }
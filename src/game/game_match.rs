@@ -0,0 +1,292 @@
+use super::*;
+
+/// Result of a single wall click in a match.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MoveOutcome {
+    AdditionalMove,
+    NextPlayer,
+    GameOver,
+}
+
+/// Rules of a single game independent of the user interface. The match owns the board, knows
+/// whose turn it is, grants an additional move after a house is built and detects the end of
+/// the game.
+#[derive(Debug, Clone)]
+pub struct Match {
+    board: Board,
+    turn_order: Vec<Player>,
+    current: usize,
+}
+
+impl Match {
+    pub fn new(board: Board, turn_order: &[Player]) -> Self {
+        assert!(!turn_order.is_empty(), "at least one player takes part in a match");
+        Self {
+            board,
+            turn_order: turn_order.to_vec(),
+            current: 0,
+        }
+    }
+
+    /// Continues a match in which it is `player`'s turn, e.g. a loaded one.
+    pub fn with_current_player(mut self, player: Player) -> Result<Self, String> {
+        self.current = self.player_index(player)?;
+        Ok(self)
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn turn_order(&self) -> &[Player] {
+        &self.turn_order
+    }
+
+    pub fn current_player(&self) -> Player {
+        self.turn_order[self.current]
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.board.all_is_clicked()
+    }
+
+    pub fn get_statistics(&self) -> GameStatistics {
+        self.board.get_statistics()
+    }
+
+    pub fn next_player(&self, player: Player) -> Player {
+        let idx = self.player_index(player).expect("player takes part in the match");
+        self.turn_order[(idx + 1) % self.turn_order.len()]
+    }
+
+    /// Clicks the wall on behalf of the current player and passes the turn on if no house was
+    /// built.
+    pub fn play(&mut self, row: usize, col: usize) -> Result<MoveOutcome, String> {
+        if self.is_over() {
+            return Err("The match is over".to_string());
+        }
+        let additional_move = self.board.click_wall(row, col, self.current_player())?;
+
+        if self.is_over() {
+            Ok(MoveOutcome::GameOver)
+        } else if additional_move {
+            Ok(MoveOutcome::AdditionalMove)
+        } else {
+            self.current = (self.current + 1) % self.turn_order.len();
+            Ok(MoveOutcome::NextPlayer)
+        }
+    }
+
+    /// Asks the move generator for a move of the current player and plays it.
+    pub fn play_turn(&mut self, move_generator: &dyn MoveGenerator) -> Result<MoveOutcome, String> {
+        let (row, col) = move_generator
+            .next_move(&self.board, self.current_player())
+            .ok_or("There is no move available")?;
+        self.play(row, col)
+    }
+
+    /// Plays the match to the end. Move generators are given in the turn order.
+    pub fn play_out(&mut self, move_generators: &[&dyn MoveGenerator]) -> GameStatistics {
+        assert_eq!(
+            self.turn_order.len(),
+            move_generators.len(),
+            "every player has a move generator"
+        );
+        while !self.is_over() {
+            self.play_turn(move_generators[self.current])
+                .expect("move generators return valid moves");
+        }
+        self.get_statistics()
+    }
+
+    /// Counts a hint asked for by the current player. Hints are not reverted by `undo`.
+    pub fn record_hint(&mut self) {
+        let player = self.current_player();
+        self.board.statistics.add_hint(player);
+    }
+
+    /// Reverts the last move and gives the turn back to the player who made it.
+    pub fn undo(&mut self) -> Option<Move> {
+        let undone_move = self.board.undo()?;
+        self.current = self
+            .player_index(undone_move.player)
+            .expect("player takes part in the match");
+        Some(undone_move)
+    }
+
+    /// Replays the last reverted move.
+    pub fn redo(&mut self) -> Option<Move> {
+        let next_move = self.board.redo()?;
+        let player = if next_move.houses.is_empty() {
+            self.next_player(next_move.player)
+        } else {
+            next_move.player
+        };
+        self.current = self.player_index(player).expect("player takes part in the match");
+        Some(next_move)
+    }
+
+    pub fn get_next_redo(&self) -> Option<&Move> {
+        self.board.get_next_redo()
+    }
+
+    fn player_index(&self, player: Player) -> Result<usize, String> {
+        self.turn_order
+            .iter()
+            .position(|p| *p == player)
+            .ok_or(format!("{player:?} does not take part in the match"))
+    }
+}
+
+#[cfg(test)]
+mod match_tests {
+    use super::*;
+
+    #[test]
+    fn turn_passes_when_no_house_is_built() {
+        let mut game_match = Match::new(Board::new(2, 2), &[Player::Player1, Player::CPU]);
+
+        assert_eq!(Ok(MoveOutcome::NextPlayer), game_match.play(0, 0));
+        assert_eq!(Player::CPU, game_match.current_player());
+        assert_eq!(Ok(MoveOutcome::NextPlayer), game_match.play(1, 0));
+        assert_eq!(Player::Player1, game_match.current_player());
+    }
+
+    #[test]
+    fn building_a_house_gives_additional_move() {
+        let mut game_match = Match::new(Board::new(2, 1), &[Player::Player1, Player::Player2]);
+        let _ = game_match.play(0, 0);
+        let _ = game_match.play(1, 0);
+        let _ = game_match.play(2, 0);
+
+        assert_eq!(Player::Player2, game_match.current_player());
+        assert_eq!(Ok(MoveOutcome::AdditionalMove), game_match.play(1, 1));
+        assert_eq!(Player::Player2, game_match.current_player());
+        assert_eq!(1, game_match.get_statistics().get_points(Player::Player2));
+    }
+
+    #[test]
+    fn last_wall_ends_the_match() {
+        let mut game_match = Match::new(Board::new(1, 1), &[Player::Player1, Player::Player2]);
+        let _ = game_match.play(0, 0);
+        let _ = game_match.play(1, 0);
+        let _ = game_match.play(1, 1);
+
+        assert_eq!(Ok(MoveOutcome::GameOver), game_match.play(2, 0));
+        assert!(game_match.is_over());
+        assert_eq!(Some(Player::Player2), game_match.get_statistics().winner);
+        assert!(game_match.play(2, 0).is_err());
+    }
+
+    #[test]
+    fn clicking_the_same_wall_twice_does_not_pass_the_turn() {
+        let mut game_match = Match::new(Board::new(2, 2), &[Player::Player1, Player::CPU]);
+        let _ = game_match.play(0, 0);
+
+        assert!(game_match.play(0, 0).is_err());
+        assert_eq!(Player::CPU, game_match.current_player());
+    }
+
+    #[test]
+    fn hints_are_counted_for_the_player_on_turn() {
+        let mut game_match = Match::new(Board::new(2, 1), &[Player::Player1, Player::CPU]);
+        game_match.record_hint();
+        let _ = game_match.play(0, 0);
+        game_match.record_hint();
+        let _ = game_match.undo();
+
+        let statistics = game_match.get_statistics();
+        assert_eq!(1, statistics.get_hints(Player::Player1));
+        assert_eq!(1, statistics.get_hints(Player::CPU));
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_player_on_turn() {
+        let mut game_match = Match::new(Board::new(2, 1), &[Player::Player1, Player::CPU]);
+        let _ = game_match.play(0, 0);
+        let _ = game_match.play(1, 0);
+        let _ = game_match.play(2, 0);
+        let _ = game_match.play(1, 1);
+        assert_eq!(Player::CPU, game_match.current_player());
+
+        let _ = game_match.undo();
+        assert_eq!(Player::CPU, game_match.current_player());
+        let _ = game_match.undo();
+        assert_eq!(Player::Player1, game_match.current_player());
+
+        let _ = game_match.redo();
+        assert_eq!(Player::CPU, game_match.current_player());
+        let _ = game_match.redo();
+        assert_eq!(Player::CPU, game_match.current_player());
+        assert_eq!(1, game_match.get_statistics().get_points(Player::CPU));
+    }
+
+    #[test]
+    fn match_can_be_played_out_without_user_interface() {
+        let mut game_match = Match::new(Board::new(3, 3), &[Player::Player1, Player::CPU]);
+        let first_player = GreadyAlgorithmPlayer::default();
+        let second_player = RegionCountingPlayer::default();
+
+        let stats = game_match.play_out(&[&first_player, &second_player]);
+
+        assert!(game_match.is_over());
+        assert_eq!(9, stats.get_points(Player::Player1) + stats.get_points(Player::CPU));
+        assert_eq!(24, game_match.board().get_history().len());
+    }
+
+    #[test]
+    fn turn_passes_around_four_seats() {
+        let turn_order = [Player::Player1, Player::Player2, Player::Player3, Player::Player4];
+        let mut game_match = Match::new(Board::new(2, 2), &turn_order);
+
+        let walls = [(0, 0), (0, 1), (2, 0), (2, 1), (4, 0), (4, 1), (1, 0), (3, 0)];
+        let next_players = turn_order.iter().cycle().skip(1);
+
+        for ((row, col), player) in walls.into_iter().zip(next_players) {
+            assert_eq!(Ok(MoveOutcome::NextPlayer), game_match.play(row, col));
+            assert_eq!(*player, game_match.current_player());
+        }
+    }
+
+    #[test]
+    fn four_seats_can_be_played_out() {
+        let mut game_match = Match::new(
+            Board::new(3, 3),
+            &[Player::Player1, Player::Player2, Player::Player3, Player::Player4],
+        );
+        let greedy = GreadyAlgorithmPlayer::default();
+        let region = RegionCountingPlayer::default();
+
+        let stats = game_match.play_out(&[&greedy, &region, &greedy, &region]);
+
+        assert_eq!(9, stats.points.iter().sum::<usize>());
+        assert_eq!(0, stats.get_points(Player::CPU));
+    }
+
+    #[test]
+    fn two_cpu_seats_can_play_each_other_with_cpu_first() {
+        let mut settings = settings::GameSettings::new(3, 3, Difficulty::Easy);
+        settings.cycle_seat_kind(0);
+        settings.cycle_seat_kind(1);
+        settings.cycle_first_seat();
+        let game_mode = GameMode::from_settings(&settings);
+        let mut game_match = Match::new(Board::new(3, 3), &game_mode.turn_order())
+            .with_current_player(settings.first_player())
+            .expect("first player takes a seat");
+        assert_eq!(Player::CPU, game_match.current_player());
+        assert!(!game_mode.has_human_seat());
+
+        let move_generators = game_mode
+            .turn_order()
+            .into_iter()
+            .map(|player| game_mode.get_move_generator(player).expect("seat is taken by CPU"))
+            .collect::<Vec<_>>();
+        let stats = game_match.play_out(&move_generators);
+
+        assert_eq!(Player::CPU, game_match.board().get_history()[0].player);
+        assert_eq!(
+            9,
+            stats.get_points(Player::Player1) + stats.get_points(Player::CPU)
+        );
+    }
+}
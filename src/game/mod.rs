@@ -1,13 +1,105 @@
 use crate::ai::prelude::*;
 
-use std::cmp::Ordering;
-
+pub mod adaptive;
 pub mod controller;
+pub mod game_match;
+pub mod notation;
+pub mod record;
+pub mod saved_game;
+pub mod settings;
+pub mod shape;
+pub mod zobrist;
+
+use settings::{GameSettings, SeatKind};
+
+/// Decides who makes the moves of a seat.
+#[derive(Debug, Clone)]
+pub enum SeatController {
+    Human,
+    Cpu(Box<dyn MoveGenerator>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Seat {
+    pub player: Player,
+    pub controller: SeatController,
+}
+
+impl Seat {
+    pub fn human(player: Player) -> Self {
+        Self {
+            player,
+            controller: SeatController::Human,
+        }
+    }
+
+    pub fn cpu(player: Player, move_generator: Box<dyn MoveGenerator>) -> Self {
+        Self {
+            player,
+            controller: SeatController::Cpu(move_generator),
+        }
+    }
 
+    pub fn is_human(&self) -> bool {
+        matches!(self.controller, SeatController::Human)
+    }
+}
+
+/// Seats taking part in a game, listed in the turn order.
 #[derive(Debug, Clone)]
-pub enum GameMode {
-    OnePlayer(Box<dyn MoveGenerator>),
-    TwoPlayer,
+pub struct GameMode {
+    pub seats: Vec<Seat>,
+}
+
+impl GameMode {
+    pub fn new(seats: Vec<Seat>) -> Self {
+        Self { seats }
+    }
+
+    /// Builds the seats chosen in the main menu.
+    pub fn from_settings(settings: &GameSettings) -> Self {
+        let seats = settings
+            .seats
+            .iter()
+            .map(|seat| match seat.kind {
+                SeatKind::Human => Seat::human(seat.player),
+                SeatKind::Cpu(difficulty) => Seat::cpu(
+                    seat.player,
+                    get_cpu_player(&difficulty, settings.strength, settings.seed),
+                ),
+            })
+            .collect();
+        Self::new(seats)
+    }
+
+    pub fn turn_order(&self) -> Vec<Player> {
+        self.seats.iter().map(|seat| seat.player).collect()
+    }
+
+    pub fn get_move_generator(&self, player: Player) -> Option<&(dyn MoveGenerator + 'static)> {
+        self.seats
+            .iter()
+            .find(|seat| seat.player == player)
+            .and_then(|seat| match &seat.controller {
+                SeatController::Human => None,
+                SeatController::Cpu(move_generator) => Some(move_generator.as_ref()),
+            })
+    }
+
+    pub fn is_human(&self, player: Player) -> bool {
+        self.seats
+            .iter()
+            .any(|seat| seat.player == player && seat.is_human())
+    }
+
+    /// Returns true when exactly one seat is taken by a human.
+    pub fn is_one_player_game(&self) -> bool {
+        self.seats.iter().filter(|seat| seat.is_human()).count() == 1
+    }
+
+    pub fn has_human_seat(&self) -> bool {
+        self.seats.iter().any(|seat| seat.is_human())
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -15,38 +107,161 @@ pub enum Difficulty {
     Easy,
     Medium,
     Hard,
+    Expert,
 }
 
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Next harder difficulty, `Expert` stays the hardest.
+    pub fn harder(&self) -> Self {
+        Self::ALL[(self.index() + 1).min(Self::ALL.len() - 1)]
+    }
+
+    /// Next easier difficulty, `Easy` stays the easiest.
+    pub fn easier(&self) -> Self {
+        Self::ALL[self.index().saturating_sub(1)]
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|difficulty| difficulty == self)
+            .expect("every difficulty is listed")
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Easy" => Ok(Difficulty::Easy),
+            "Medium" => Ok(Difficulty::Medium),
+            "Hard" => Ok(Difficulty::Hard),
+            "Expert" => Ok(Difficulty::Expert),
+            _ => Err(format!("Unknown difficulty '{s}'")),
+        }
+    }
+}
+
+pub const PLAYER_COUNT: usize = 5;
+pub const MIN_SEAT_COUNT: usize = 2;
+pub const MAX_SEAT_COUNT: usize = 4;
+
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Player {
     Player1,
     Player2,
+    Player3,
+    Player4,
     CPU,
 }
 
+impl std::str::FromStr for Player {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Player1" => Ok(Player::Player1),
+            "Player2" => Ok(Player::Player2),
+            "Player3" => Ok(Player::Player3),
+            "Player4" => Ok(Player::Player4),
+            "CPU" => Ok(Player::CPU),
+            _ => Err(format!("Unknown player '{s}'")),
+        }
+    }
+}
+
 impl Player {
-    pub fn opponent(&self) -> Player {
+    pub const ALL: [Player; PLAYER_COUNT] = [
+        Player::Player1,
+        Player::Player2,
+        Player::Player3,
+        Player::Player4,
+        Player::CPU,
+    ];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Player::Player1 => 0,
+            Player::Player2 => 1,
+            Player::Player3 => 2,
+            Player::Player4 => 3,
+            Player::CPU => 4,
+        }
+    }
+
+    pub fn caption(&self) -> &'static str {
         match self {
-            Player::Player1 => Player::CPU,
-            Player::CPU => Player::Player1,
-            _ => unimplemented!("This should not be used in two player game for now!"),
+            Player::Player1 => "Player 1",
+            Player::Player2 => "Player 2",
+            Player::Player3 => "Player 3",
+            Player::Player4 => "Player 4",
+            Player::CPU => "CPU",
         }
     }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct GameStatistics {
-    pub player1_points: usize,
-    pub player2_points: usize,
-    pub cpu_points: usize,
+    pub points: [usize; PLAYER_COUNT],
+    pub hints: [usize; PLAYER_COUNT],
     pub winner: Option<Player>,
 }
 
+impl GameStatistics {
+    pub fn get_points(&self, player: Player) -> usize {
+        self.points[player.index()]
+    }
+
+    /// Number of hints the player asked for.
+    pub fn get_hints(&self, player: Player) -> usize {
+        self.hints[player.index()]
+    }
+
+    fn add_hint(&mut self, player: Player) {
+        self.hints[player.index()] += 1;
+    }
+
+    fn add_point(&mut self, player: Player) {
+        self.points[player.index()] += 1;
+    }
+
+    fn remove_point(&mut self, player: Player) {
+        self.points[player.index()] -= 1;
+    }
+}
+
+/// A single wall click together with the houses it completed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+    pub wall: (usize, usize),
+    pub player: Player,
+    pub houses: Vec<(usize, usize)>,
+}
+
+impl Move {
+    pub fn new(wall: (usize, usize), player: Player, houses: Vec<(usize, usize)>) -> Self {
+        Self {
+            wall,
+            player,
+            houses,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Cell {
     _id: (usize, usize),
     pub counter: usize,
     pub owner: Option<Player>,
+    pub exists: bool,
 }
 
 impl Cell {
@@ -55,6 +270,7 @@ impl Cell {
             _id: (row, col),
             counter: 0,
             owner: None,
+            exists: true,
         }
     }
 }
@@ -86,6 +302,11 @@ impl Wall {
         self.adjacent_joints.push((direction, row, col));
         self
     }
+
+    /// Walls which do not border any cell of a masked board are not part of the game.
+    pub fn exists(&self) -> bool {
+        !self.adjacent_cells.is_empty()
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -155,14 +376,26 @@ pub struct Board {
     pub joints: Vec<Vec<Joint>>,
     pub walls: Vec<Vec<Wall>>,
     pub statistics: GameStatistics,
+    zobrist_key: u64,
+    history: Vec<Move>,
+    undone_moves: Vec<Move>,
 }
 
 impl Board {
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_mask(width, height, &vec![vec![true; width]; height])
+    }
+
+    /// Builds a board of the cells flagged in `mask`, given as `height` rows of `width` flags.
+    /// Walls which do not border any of the cells are left out of the game.
+    pub fn with_mask(width: usize, height: usize, mask: &[Vec<bool>]) -> Self {
         let cells = (0..height)
             .map(|row| {
                 (0..width)
-                    .map(|col| Cell::new(row, col))
+                    .map(|col| Cell {
+                        exists: mask[row][col],
+                        ..Cell::new(row, col)
+                    })
                     .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
@@ -188,6 +421,7 @@ impl Board {
                             .filter_map(|(r, c)| {
                                 check_coordinates(width as isize, height as isize, *r, *c)
                             })
+                            .filter(|(r, c)| mask[*r][*c])
                             .collect::<Vec<_>>();
 
                             Wall::new(row, col)
@@ -208,6 +442,7 @@ impl Board {
                             .filter_map(|(r, c)| {
                                 check_coordinates(width as isize, height as isize, *r, *c)
                             })
+                            .filter(|(r, c)| mask[*r][*c])
                             .collect::<Vec<_>>();
 
                             Wall::new(row, col)
@@ -228,29 +463,90 @@ impl Board {
             joints,
             walls,
             statistics: GameStatistics::default(),
+            zobrist_key: 0,
+            history: vec![],
+            undone_moves: vec![],
         }
     }
 
     pub fn click_wall(&mut self, row: usize, col: usize, player: Player) -> Result<bool, String> {
-        if row > 2 * self.height || (row % 2 == 0 && col >= self.width) || col > self.width {
+        let houses = self.close_wall(row, col, player)?;
+        let additional_move = !houses.is_empty();
+
+        self.history.push(Move::new((row, col), player, houses));
+        self.undone_moves.clear();
+
+        Ok(additional_move)
+    }
+
+    /// Reverts the given wall and removes it from the move history.
+    pub fn unclick_wall(&mut self, row: usize, col: usize) -> Result<(), String> {
+        self.open_wall(row, col)?;
+        self.history.retain(|m| m.wall != (row, col));
+
+        Ok(())
+    }
+
+    /// Reverts the last move. The reverted move can be replayed with `redo`.
+    pub fn undo(&mut self) -> Option<Move> {
+        let last_move = self.history.pop()?;
+        self.open_wall(last_move.wall.0, last_move.wall.1)
+            .expect("walls in history are clicked");
+        self.undone_moves.push(last_move.clone());
+        Some(last_move)
+    }
+
+    /// Replays the last reverted move.
+    pub fn redo(&mut self) -> Option<Move> {
+        let next_move = self.undone_moves.pop()?;
+        let houses = self
+            .close_wall(next_move.wall.0, next_move.wall.1, next_move.player)
+            .expect("undone walls are not clicked");
+        let next_move = Move::new(next_move.wall, next_move.player, houses);
+        self.history.push(next_move.clone());
+        Some(next_move)
+    }
+
+    pub fn get_history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Returns the move which will be replayed by the next call to `redo`.
+    pub fn get_next_redo(&self) -> Option<&Move> {
+        self.undone_moves.last()
+    }
+
+    fn check_wall_coordinates(&self, row: usize, col: usize) -> Result<(), String> {
+        if row > 2 * self.height || (row.is_multiple_of(2) && col >= self.width) || col > self.width {
             return Err("Wrong coordinates of wall".to_string());
         }
+        if !self.walls[row][col].exists() {
+            return Err("Wall is not part of the board".to_string());
+        }
+        Ok(())
+    }
+
+    fn close_wall(
+        &mut self,
+        row: usize,
+        col: usize,
+        player: Player,
+    ) -> Result<Vec<(usize, usize)>, String> {
+        self.check_wall_coordinates(row, col)?;
         let wall = &mut self.walls[row][col];
-        let mut additional_move = false;
+        let mut houses = vec![];
         if !wall.is_clicked {
             wall.is_clicked = true;
+            self.zobrist_key ^= zobrist::wall_key(row, col);
             for (cell_row, cell_col) in &wall.adjacent_cells {
                 let cell = &mut self.cells[*cell_row][*cell_col];
                 if cell.counter < 4 {
                     cell.counter += 1;
                     if cell.counter == 4 {
                         cell.owner = Some(player);
-                        match player {
-                            Player::Player1 => self.statistics.player1_points += 1,
-                            Player::Player2 => self.statistics.player2_points += 1,
-                            Player::CPU => self.statistics.cpu_points += 1,
-                        }
-                        additional_move = true;
+                        self.zobrist_key ^= zobrist::owner_key(*cell_row, *cell_col, player);
+                        self.statistics.add_point(player);
+                        houses.push((*cell_row, *cell_col));
                     }
                 }
             }
@@ -260,29 +556,26 @@ impl Board {
         } else {
             return Err("Wall is already clicked!".to_string());
         }
-        
-        Ok(additional_move)
+
+        Ok(houses)
     }
 
-    pub fn unclick_wall(&mut self, row: usize, col: usize, player: Player) -> Result<(), String> {
-        if row > 2 * self.height || (row % 2 == 0 && col >= self.width) || col > self.width {
-            return Err("Wrong coordinates of wall".to_string());
-        }
+    fn open_wall(&mut self, row: usize, col: usize) -> Result<(), String> {
+        self.check_wall_coordinates(row, col)?;
         let wall = &mut self.walls[row][col];
         if wall.is_clicked {
             wall.is_clicked = false;
+            self.zobrist_key ^= zobrist::wall_key(row, col);
             for (cell_row, cell_col) in &wall.adjacent_cells {
                 let cell = &mut self.cells[*cell_row][*cell_col];
+                if cell.counter == 4 {
+                    if let Some(owner) = cell.owner.take() {
+                        self.zobrist_key ^= zobrist::owner_key(*cell_row, *cell_col, owner);
+                        self.statistics.remove_point(owner);
+                    }
+                }
                 if cell.counter > 0 {
                     cell.counter -= 1;
-                    if cell.counter < 4 {
-                        cell.owner = None;
-                        match player {
-                            Player::Player1 => self.statistics.player1_points -= 1,
-                            Player::Player2 => self.statistics.player2_points -= 1,
-                            Player::CPU => self.statistics.cpu_points -= 1,
-                        }
-                    }
                 }
             }
             for (direction, row, col) in &wall.adjacent_joints {
@@ -298,7 +591,7 @@ impl Board {
     pub fn all_is_clicked(&self) -> bool {
         for row in &self.walls {
             for wall in row {
-                if !wall.is_clicked {
+                if wall.exists() && !wall.is_clicked {
                     return false;
                 }
             }
@@ -306,28 +599,67 @@ impl Board {
         true
     }
 
+    /// Marks the wall as clicked without building houses or recording a move. Used to restore
+    /// a position, the owners of the houses are set with `set_owner`.
+    pub fn set_wall_clicked(&mut self, row: usize, col: usize) -> Result<(), String> {
+        self.check_wall_coordinates(row, col)?;
+        let wall = &mut self.walls[row][col];
+        wall.is_clicked = true;
+        self.zobrist_key ^= zobrist::wall_key(row, col);
+        for (row_cell, col_cell) in &wall.adjacent_cells {
+            self.cells[*row_cell][*col_cell].counter += 1;
+        }
+        for (direction, row, col) in &wall.adjacent_joints {
+            self.joints[*row][*col].set_wall_clicked(*direction);
+        }
+        Ok(())
+    }
+
+    pub fn set_owner(&mut self, row: usize, col: usize, player: Player) {
+        self.cells[row][col].owner = Some(player);
+        self.zobrist_key ^= zobrist::owner_key(row, col, player);
+        self.statistics.add_point(player);
+    }
+
+    /// Tells whether any wall of the game meets at the joint.
+    pub fn joint_exists(&self, row: usize, col: usize) -> bool {
+        let horizontal = [col.checked_sub(1), Some(col)]
+            .into_iter()
+            .flatten()
+            .filter_map(|col| self.walls.get(2 * row).and_then(|walls| walls.get(col)));
+        let vertical = [(2 * row).checked_sub(1), Some(2 * row + 1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|row| self.walls.get(row).and_then(|walls| walls.get(col)));
+        horizontal.chain(vertical).any(|wall| wall.exists())
+    }
+
+    /// Key of the position, see `zobrist`. It is updated with every clicked or reverted wall.
+    pub fn get_zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
+
     pub fn get_statistics(&self) -> GameStatistics {
-        let GameStatistics {
-            player1_points,
-            player2_points,
-            cpu_points,
-            winner: _,
-        } = self.statistics;
-        let winner = match player1_points.cmp(&player2_points) {
-            Ordering::Greater => compare_with_cpu(player1_points, cpu_points),
-            Ordering::Less => Some(Player::Player2),
-            Ordering::Equal => compare_with_cpu(player1_points, cpu_points),
+        let points = self.statistics.points;
+        let max_points = points.iter().max().copied().unwrap_or_default();
+        let mut leaders = Player::ALL
+            .iter()
+            .filter(|player| points[player.index()] == max_points);
+        let winner = match (leaders.next(), leaders.next()) {
+            (Some(player), None) => Some(*player),
+            _ => None,
         };
         GameStatistics {
-            player1_points,
-            player2_points,
-            cpu_points,
+            points,
+            hints: self.statistics.hints,
             winner,
         }
     }
 }
 
 const REPEAT_COUNT: usize = 5;
+const MISSING_WALL_SYMBOL: char = '.';
+const HOLE_SYMBOL: char = '#';
 
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -335,7 +667,10 @@ impl std::fmt::Display for Board {
             if row % 2 == 0 {
                 let mut s = String::new();
                 for col in 0..self.width {
-                    if self.walls[row][col].is_clicked {
+                    if !self.walls[row][col].exists() {
+                        s += format!(" {}", MISSING_WALL_SYMBOL.to_string().repeat(REPEAT_COUNT))
+                            .as_str();
+                    } else if self.walls[row][col].is_clicked {
                         s += format!(" {}", "X".repeat(REPEAT_COUNT)).as_str();
                     } else {
                         s += format!(" {}", "-".repeat(REPEAT_COUNT)).as_str();
@@ -346,18 +681,18 @@ impl std::fmt::Display for Board {
                 for _ in 0..REPEAT_COUNT {
                     let mut s = String::new();
                     for col in 0..self.width + 1 {
-                        if self.walls[row][col].is_clicked {
+                        if !self.walls[row][col].exists() {
+                            s.push(MISSING_WALL_SYMBOL);
+                        } else if self.walls[row][col].is_clicked {
                             s += "X";
                         } else {
                             s += "|";
                         }
                         if col < self.width {
-                            if let Some(player) = self.cells[row / 2][col].owner {
-                                match player {
-                                    Player::Player1 => s += &"A".repeat(REPEAT_COUNT),
-                                    Player::Player2 => s += &"B".repeat(REPEAT_COUNT),
-                                    Player::CPU => s += &"C".repeat(REPEAT_COUNT),
-                                }
+                            if !self.cells[row / 2][col].exists {
+                                s += &HOLE_SYMBOL.to_string().repeat(REPEAT_COUNT);
+                            } else if let Some(player) = self.cells[row / 2][col].owner {
+                                s += &owner_symbol(player).to_string().repeat(REPEAT_COUNT);
                             }
                             /*else if self.cells[row/2][col].counter > 0 && col < self.width {
                                 s += &format!("{}", self.cells[row/2][col].counter).repeat(5);
@@ -389,50 +724,70 @@ impl std::str::FromStr for Board {
             .filter_map(|(idx, line)| match idx % (REPEAT_COUNT + 1) {
                 0 => Some(
                     line.replace(&"X".repeat(REPEAT_COUNT), "X")
-                        .replace(&"-".repeat(REPEAT_COUNT), "-"),
+                        .replace(&"-".repeat(REPEAT_COUNT), "-")
+                        .replace(
+                            &MISSING_WALL_SYMBOL.to_string().repeat(REPEAT_COUNT),
+                            &MISSING_WALL_SYMBOL.to_string(),
+                        ),
                 ),
                 1 => Some(
-                    line.replace(&"A".repeat(REPEAT_COUNT), "A")
-                        .replace(&"B".repeat(REPEAT_COUNT), "B")
-                        .replace(&"C".repeat(REPEAT_COUNT), "C")
-                        .replace(&" ".repeat(REPEAT_COUNT), " "),
+                    Player::ALL
+                        .iter()
+                        .map(|player| owner_symbol(*player).to_string())
+                        .chain([" ".to_string(), HOLE_SYMBOL.to_string()])
+                        .fold(line.to_string(), |line, symbol| {
+                            line.replace(&symbol.repeat(REPEAT_COUNT), &symbol)
+                        }),
                 ),
                 _ => None,
             })
             .collect::<Vec<_>>();
 
-        let mut board = Board::new(rows[0].len() / 2, rows.len() / 2);
+        let width = rows[0].len() / 2;
+        let height = rows.len() / 2;
+        if width == 0 || rows.len() % 2 == 0 {
+            return Err("Board lines do not make whole rows".to_string());
+        }
+        for (row_idx, row) in rows.iter().enumerate() {
+            let expected_len = 2 * width + row_idx % 2;
+            if row.chars().count() != expected_len {
+                return Err(format!(
+                    "Board row {} does not match the width of {width} houses",
+                    row_idx + 1
+                ));
+            }
+        }
+        let mask = rows
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .take(height)
+            .map(|row| {
+                (0..width)
+                    .map(|col| row.chars().nth(2 * col + 1) != Some(HOLE_SYMBOL))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut board = Board::with_mask(width, height, &mask);
         for (row_idx, row) in rows.iter().enumerate() {
             if row_idx % 2 == 0 {
                 for (col_idx, cell) in row.chars().enumerate() {
                     if cell == 'X' {
-                        let wall = &mut board.walls[row_idx][col_idx / 2];
-                        wall.is_clicked = true;
-                        for (row_cell, col_cell) in &wall.adjacent_cells {
-                            board.cells[*row_cell][*col_cell].counter += 1;
-                        }
-                        for (direction, row, col) in &wall.adjacent_joints {
-                            board.joints[*row][*col].set_wall_clicked(*direction);
-                        }
+                        board.set_wall_clicked(row_idx, col_idx / 2)?;
                     }
                 }
             } else {
                 for (col_idx, ch) in row.chars().enumerate() {
                     match ch {
                         'X' => {
-                            let wall = &mut board.walls[row_idx][col_idx / 2];
-                            wall.is_clicked = true;
-                            for (row_cell, col_cell) in &wall.adjacent_cells {
-                                board.cells[*row_cell][*col_cell].counter += 1;
-                            }
-                            for (direction, row, col) in &wall.adjacent_joints {
-                                board.joints[*row][*col].set_wall_clicked(*direction);
+                            board.set_wall_clicked(row_idx, col_idx / 2)?;
+                        }
+                        symbol => {
+                            if let Some(player) = owner_from_symbol(symbol) {
+                                board.set_owner(row_idx / 2, col_idx / 2, player);
                             }
                         }
-                        'A' => board.cells[row_idx / 2][col_idx / 2].owner = Some(Player::Player1),
-                        'B' => board.cells[row_idx / 2][col_idx / 2].owner = Some(Player::Player2),
-                        'C' => board.cells[row_idx / 2][col_idx / 2].owner = Some(Player::CPU),
-                        _ => (),
                     }
                 }
             }
@@ -442,14 +797,23 @@ impl std::str::FromStr for Board {
     }
 }
 
-fn compare_with_cpu(player1_points: usize, cpu_points: usize) -> Option<Player> {
-    match player1_points.cmp(&cpu_points) {
-        Ordering::Greater => Some(Player::Player1),
-        Ordering::Less => Some(Player::CPU),
-        Ordering::Equal => None,
+fn owner_symbol(player: Player) -> char {
+    match player {
+        Player::Player1 => 'A',
+        Player::Player2 => 'B',
+        Player::CPU => 'C',
+        Player::Player3 => 'D',
+        Player::Player4 => 'E',
     }
 }
 
+fn owner_from_symbol(symbol: char) -> Option<Player> {
+    Player::ALL
+        .iter()
+        .find(|player| owner_symbol(**player) == symbol)
+        .copied()
+}
+
 fn check_coordinates(
     width: isize,
     height: isize,
@@ -538,3 +902,210 @@ mod joint_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod board_history_tests {
+    use super::*;
+
+    fn close_single_cell(board: &mut Board) {
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(1, 0, Player::CPU);
+        let _ = board.click_wall(1, 1, Player::Player1);
+        let _ = board.click_wall(2, 0, Player::CPU);
+    }
+
+    #[test]
+    fn moves_are_recorded_with_completed_houses() {
+        let mut board = Board::new(1, 1);
+
+        close_single_cell(&mut board);
+
+        let history = board.get_history();
+        assert_eq!(4, history.len());
+        assert_eq!(Move::new((0, 0), Player::Player1, vec![]), history[0]);
+        assert_eq!(Move::new((2, 0), Player::CPU, vec![(0, 0)]), history[3]);
+    }
+
+    #[test]
+    fn undo_restores_ownership_and_statistics() {
+        let mut board = Board::new(1, 1);
+        close_single_cell(&mut board);
+
+        let undone_move = board.undo();
+
+        assert_eq!(Some(Move::new((2, 0), Player::CPU, vec![(0, 0)])), undone_move);
+        assert!(!board.walls[2][0].is_clicked);
+        assert_eq!(None, board.cells[0][0].owner);
+        assert_eq!(3, board.cells[0][0].counter);
+        assert_eq!(0, board.statistics.get_points(Player::CPU));
+        assert_eq!(0, board.statistics.get_points(Player::Player1));
+        assert_eq!(8, board.joints[1][1].get_joint_mask());
+    }
+
+    #[test]
+    fn redo_replays_undone_moves() {
+        let mut board = Board::new(1, 1);
+        close_single_cell(&mut board);
+        let _ = board.undo();
+        let _ = board.undo();
+
+        assert_eq!(Some((1, 1)), board.get_next_redo().map(|m| m.wall));
+        assert_eq!(Some(Move::new((1, 1), Player::Player1, vec![])), board.redo());
+        assert_eq!(
+            Some(Move::new((2, 0), Player::CPU, vec![(0, 0)])),
+            board.redo()
+        );
+        assert_eq!(None, board.redo());
+        assert_eq!(Some(Player::CPU), board.cells[0][0].owner);
+        assert_eq!(1, board.statistics.get_points(Player::CPU));
+        assert_eq!(4, board.get_history().len());
+    }
+
+    #[test]
+    fn new_move_clears_undone_moves() {
+        let mut board = Board::new(1, 2);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.undo();
+
+        let _ = board.click_wall(1, 0, Player::Player1);
+
+        assert_eq!(None, board.get_next_redo());
+        assert_eq!(None, board.redo());
+    }
+
+    #[test]
+    fn unclick_wall_takes_points_from_the_owner() {
+        let mut board = Board::new(2, 1);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(1, 0, Player::Player1);
+        let _ = board.click_wall(2, 0, Player::Player1);
+        let _ = board.click_wall(0, 1, Player::CPU);
+        let _ = board.click_wall(1, 2, Player::CPU);
+        let _ = board.click_wall(2, 1, Player::CPU);
+        let _ = board.click_wall(1, 1, Player::CPU);
+        assert_eq!(2, board.statistics.get_points(Player::CPU));
+
+        let _ = board.unclick_wall(1, 1);
+
+        assert_eq!(0, board.statistics.get_points(Player::CPU));
+        assert_eq!(0, board.statistics.get_points(Player::Player1));
+        assert_eq!(None, board.cells[0][0].owner);
+        assert_eq!(None, board.cells[0][1].owner);
+        assert_eq!(6, board.get_history().len());
+    }
+}
+
+#[cfg(test)]
+mod game_statistics_tests {
+    use super::*;
+
+    fn close_cell(board: &mut Board, col: usize, player: Player) {
+        let _ = board.click_wall(0, col, player);
+        let _ = board.click_wall(1, col, player);
+        let _ = board.click_wall(1, col + 1, player);
+        let _ = board.click_wall(2, col, player);
+    }
+
+    #[test]
+    fn player_with_most_points_wins_among_many() {
+        let mut board = Board::new(4, 1);
+        close_cell(&mut board, 0, Player::Player3);
+        close_cell(&mut board, 1, Player::Player4);
+        close_cell(&mut board, 2, Player::Player3);
+        close_cell(&mut board, 3, Player::Player1);
+
+        let statistics = board.get_statistics();
+
+        assert_eq!(2, statistics.get_points(Player::Player3));
+        assert_eq!(1, statistics.get_points(Player::Player4));
+        assert_eq!(Some(Player::Player3), statistics.winner);
+    }
+
+    #[test]
+    fn shared_lead_is_a_tie() {
+        let mut board = Board::new(3, 1);
+        close_cell(&mut board, 0, Player::Player2);
+        close_cell(&mut board, 1, Player::Player4);
+        close_cell(&mut board, 2, Player::Player1);
+        let _ = board.unclick_wall(2, 2);
+
+        let statistics = board.get_statistics();
+
+        assert_eq!(0, statistics.get_points(Player::Player1));
+        assert_eq!(None, statistics.winner);
+    }
+
+    #[test]
+    fn seats_of_one_player_game_are_taken_by_cpu() {
+        let mut settings = GameSettings::new(3, 3, Difficulty::Easy);
+        settings.set_seat_count(3);
+        let game_mode = GameMode::from_settings(&settings);
+
+        assert_eq!(
+            vec![Player::Player1, Player::CPU, Player::Player3],
+            game_mode.turn_order()
+        );
+        assert!(game_mode.is_human(Player::Player1));
+        assert!(game_mode.get_move_generator(Player::Player3).is_some());
+        assert!(game_mode.is_one_player_game());
+    }
+}
+
+#[cfg(test)]
+mod masked_board_tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn corner_board() -> Board {
+        Board::with_mask(2, 2, &[vec![true, false], vec![true, true]])
+    }
+
+    #[test]
+    fn walls_around_holes_are_not_part_of_the_board() {
+        let mut board = corner_board();
+
+        assert!(!board.walls[0][1].exists());
+        assert!(!board.walls[1][2].exists());
+        assert!(board.walls[1][1].exists());
+        assert!(board.walls[2][1].exists());
+        assert!(!board.joint_exists(0, 2));
+        assert!(board.joint_exists(1, 2));
+        assert!(board.click_wall(0, 1, Player::Player1).is_err());
+    }
+
+    #[test]
+    fn game_is_over_when_walls_of_the_board_are_clicked() {
+        let mut board = corner_board();
+        let walls = board
+            .walls
+            .iter()
+            .flatten()
+            .filter(|wall| wall.exists())
+            .map(|wall| wall.id)
+            .collect::<Vec<_>>();
+        for (row, col) in &walls {
+            assert!(!board.all_is_clicked());
+            let _ = board.click_wall(*row, *col, Player::Player1);
+        }
+
+        assert_eq!(10, walls.len());
+        assert!(board.all_is_clicked());
+        assert_eq!(3, board.statistics.get_points(Player::Player1));
+    }
+
+    #[test]
+    fn holes_are_kept_in_text_form() {
+        let mut board = corner_board();
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(3, 2, Player::Player1);
+
+        let text = format!("{board}");
+        let restored = Board::from_str(&text).expect("board can be parsed");
+
+        assert!(text.starts_with(" XXXXX .....\n"));
+        assert_eq!(text, format!("{restored}"));
+        assert!(!restored.cells[0][1].exists);
+        assert_eq!(board.get_zobrist_key(), restored.get_zobrist_key());
+    }
+}
@@ -0,0 +1,63 @@
+//! Compact notation of walls. Horizontal walls are written as `h<line>c<column>` and
+//! vertical walls as `v<row>c<line>`, numbered from 1. Lines are counted from the top and the
+//! left edge of the board, so `h1c1` is the top wall of the top left cell and `v1c1` is its
+//! left wall.
+
+use std::str::FromStr;
+
+pub fn wall_to_notation(row: usize, col: usize) -> String {
+    if row.is_multiple_of(2) {
+        format!("h{}c{}", row / 2 + 1, col + 1)
+    } else {
+        format!("v{}c{}", row / 2 + 1, col + 1)
+    }
+}
+
+/// Returns the coordinates of the wall in `Board::walls`. Whether the wall is a part of the
+/// board is checked when it is clicked.
+pub fn wall_from_notation(notation: &str) -> Result<(usize, usize), String> {
+    let error = || format!("Wrong wall notation '{notation}'");
+    let (kind, numbers) = notation.split_at_checked(1).ok_or_else(error)?;
+    let (row, col) = numbers.split_once('c').ok_or_else(error)?;
+    let row = usize::from_str(row).map_err(|_| error())?;
+    let col = usize::from_str(col).map_err(|_| error())?;
+    if row == 0 || col == 0 {
+        return Err(error());
+    }
+
+    match kind {
+        "h" => Ok((2 * (row - 1), col - 1)),
+        "v" => Ok((2 * (row - 1) + 1, col - 1)),
+        _ => Err(error()),
+    }
+}
+
+#[cfg(test)]
+mod notation_tests {
+    use super::*;
+
+    #[test]
+    fn walls_are_written_in_notation() {
+        assert_eq!("h1c1", wall_to_notation(0, 0));
+        assert_eq!("h3c2", wall_to_notation(4, 1));
+        assert_eq!("v1c4", wall_to_notation(1, 3));
+        assert_eq!("v12c13", wall_to_notation(23, 12));
+    }
+
+    #[test]
+    fn notation_is_read_back() {
+        for wall in [(0, 0), (4, 1), (1, 3), (23, 12)] {
+            assert_eq!(
+                Ok(wall),
+                wall_from_notation(&wall_to_notation(wall.0, wall.1))
+            );
+        }
+    }
+
+    #[test]
+    fn wrong_notation_is_reported() {
+        for notation in ["", "h", "x1c1", "h0c1", "v1c0", "h1", "h1cx", "hac1"] {
+            assert!(wall_from_notation(notation).is_err(), "{notation}");
+        }
+    }
+}
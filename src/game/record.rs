@@ -0,0 +1,332 @@
+use std::str::FromStr;
+
+use super::game_match::Match;
+use super::notation::{wall_from_notation, wall_to_notation};
+use super::saved_game::{
+    format_seats, parse_adaptive, parse_dimension, parse_seats, parse_seed, parse_strength,
+};
+use super::settings::{GameSettings, SeatKind, SeatSettings};
+use super::shape::BoardShape;
+use super::*;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Portable record of a game: a header describing the board and the seats followed by the
+/// moves in the order they were played, see `notation`. Games continued from a saved board
+/// keep that board as the starting position.
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub settings: GameSettings,
+    pub date: String,
+    pub result: String,
+    pub start: Option<Board>,
+    pub moves: Vec<(usize, usize)>,
+}
+
+impl GameRecord {
+    /// Records the moves of the match. The first seat of `settings` is set to the player who
+    /// made the first move.
+    pub fn from_match(game_match: &Match, settings: &GameSettings, date: &str) -> Self {
+        let history = game_match.board().get_history();
+        let mut start = game_match.board().clone();
+        while start.undo().is_some() {}
+
+        let mut settings = settings.clone();
+        let first_player = history
+            .first()
+            .map_or(game_match.current_player(), |first_move| first_move.player);
+        if let Some(first_seat) = settings
+            .seats
+            .iter()
+            .position(|seat| seat.player == first_player)
+        {
+            settings.first_seat = first_seat;
+        }
+
+        Self {
+            settings,
+            date: date.to_string(),
+            result: describe_result(game_match),
+            start: start
+                .walls
+                .iter()
+                .flatten()
+                .any(|wall| wall.is_clicked)
+                .then_some(start),
+            moves: history.iter().map(|m| m.wall).collect(),
+        }
+    }
+
+    /// Plays the recorded moves from the starting position.
+    pub fn replay(&self) -> Result<Match, String> {
+        let board = self
+            .start
+            .clone()
+            .unwrap_or_else(|| self.settings.new_board());
+        let mut game_match = Match::new(board, &self.settings.turn_order())
+            .with_current_player(self.settings.first_player())?;
+        for (row, col) in &self.moves {
+            game_match.play(*row, *col).map_err(|error| {
+                format!("Move {} failed: {error}", wall_to_notation(*row, *col))
+            })?;
+        }
+        Ok(game_match)
+    }
+}
+
+impl std::fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "Width:{}", self.settings.width)?;
+        writeln!(f, "Height:{}", self.settings.height)?;
+        writeln!(f, "Shape:{}", self.settings.shape)?;
+        writeln!(f, "Seats:{}", format_seats(&self.settings))?;
+        writeln!(f, "Difficulty:{:?}", self.settings.difficulty)?;
+        writeln!(f, "Strength:{}", self.settings.strength)?;
+        writeln!(f, "Adaptive:{}", self.settings.adaptive)?;
+        writeln!(f, "Hints:{:?}", self.settings.hint_difficulty)?;
+        writeln!(f, "Seed:{}", self.settings.seed)?;
+        writeln!(f, "FirstPlayer:{:?}", self.settings.first_player())?;
+        writeln!(f, "Date:{}", self.date)?;
+        writeln!(f, "Result:{}", self.result)?;
+        if let Some(start) = &self.start {
+            writeln!(f, "Board:")?;
+            writeln!(f, "{start}")?;
+        }
+        let moves = self
+            .moves
+            .iter()
+            .map(|(row, col)| wall_to_notation(*row, *col))
+            .collect::<Vec<_>>();
+        writeln!(f, "Moves:{}", moves.join(" "))?;
+
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut height = None;
+        let mut shape = BoardShape::Rectangle;
+        let mut seats = None;
+        let mut difficulty = None;
+        let mut strength = MAX_CPU_STRENGTH;
+        let mut adaptive = false;
+        let mut hint_difficulty = Difficulty::Expert;
+        let mut seed = DEFAULT_RNG_SEED;
+        let mut first_player = None;
+        let mut date = String::new();
+        let mut result = String::new();
+        let mut start = None;
+        let mut moves = None;
+
+        let mut lines = s.lines().peekable();
+        while let Some(line) = lines.next() {
+            let Some((key, value)) = line.split_once(':') else {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Err(format!("Unexpected line '{line}'"));
+            };
+            let value = value.trim();
+            match key.trim() {
+                "Width" => width = Some(parse_dimension(value)?),
+                "Height" => height = Some(parse_dimension(value)?),
+                "Shape" => shape = BoardShape::from_str(value)?,
+                "Seats" => seats = Some(parse_seats(value)?),
+                "Difficulty" => difficulty = Some(Difficulty::from_str(value)?),
+                "Strength" => strength = parse_strength(value)?,
+                "Adaptive" => adaptive = parse_adaptive(value)?,
+                "Hints" => hint_difficulty = Difficulty::from_str(value)?,
+                "Seed" => seed = parse_seed(value)?,
+                "FirstPlayer" => first_player = Some(Player::from_str(value)?),
+                "Date" => date = value.to_string(),
+                "Result" => result = value.to_string(),
+                "Board" => {
+                    let mut rows = vec![];
+                    while let Some(row) = lines.next_if(|row| !row.contains(':')) {
+                        rows.push(row);
+                    }
+                    while rows.last().is_some_and(|row| row.trim().is_empty()) {
+                        rows.pop();
+                    }
+                    start = Some(Board::from_str(&rows.join("\n"))?);
+                }
+                "Moves" => {
+                    moves = Some(
+                        value
+                            .split_whitespace()
+                            .map(wall_from_notation)
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                }
+                _ => return Err(format!("Unknown key '{key}'")),
+            }
+        }
+
+        let width = width.ok_or("Width is missing")?;
+        let height = height.ok_or("Height is missing")?;
+        if start
+            .as_ref()
+            .is_some_and(|board: &Board| board.width != width || board.height != height)
+        {
+            return Err("Board does not match the declared dimensions".to_string());
+        }
+        let difficulty = difficulty.ok_or("Difficulty is missing")?;
+        let mut settings = GameSettings::new(width, height, difficulty);
+        settings.shape = shape;
+        settings.set_strength(strength);
+        settings.adaptive = adaptive;
+        settings.hint_difficulty = hint_difficulty;
+        settings.seed = seed;
+        settings.seats = seats
+            .ok_or("Seats are missing")?
+            .into_iter()
+            .map(|(player, kind)| {
+                SeatSettings::new(player, kind.unwrap_or(SeatKind::Cpu(difficulty)))
+            })
+            .collect();
+        let first_player = first_player.ok_or("First player is missing")?;
+        settings.first_seat = settings
+            .seats
+            .iter()
+            .position(|seat| seat.player == first_player)
+            .ok_or(format!("{first_player:?} does not take a seat"))?;
+
+        Ok(Self {
+            settings,
+            date,
+            result,
+            start,
+            moves: moves.ok_or("Moves are missing")?,
+        })
+    }
+}
+
+/// Tells whether the text is a game record rather than a saved board.
+pub fn is_game_record(s: &str) -> bool {
+    s.lines().any(|line| line.starts_with("Moves:"))
+}
+
+fn describe_result(game_match: &Match) -> String {
+    let statistics = game_match.get_statistics();
+    let points = game_match
+        .turn_order()
+        .iter()
+        .map(|player| format!("{player:?}={}", statistics.get_points(*player)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let outcome = match (game_match.is_over(), statistics.winner) {
+        (false, _) => "Unfinished".to_string(),
+        (true, Some(winner)) => format!("{winner:?} wins"),
+        (true, None) => "Tie".to_string(),
+    };
+    format!("{outcome} ({points})")
+}
+
+/// Formats a Unix timestamp as a `YYYY-MM-DD` date in UTC.
+pub fn format_date(timestamp: f64) -> String {
+    let days = (timestamp as i64).div_euclid(SECONDS_PER_DAY);
+    // days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod game_record_tests {
+    use super::*;
+
+    fn play_moves(game_match: &mut Match, moves: &[&str]) {
+        for notation in moves {
+            let (row, col) = wall_from_notation(notation).expect("notation is valid");
+            game_match.play(row, col).expect("move is valid");
+        }
+    }
+
+    #[test]
+    fn record_is_written_and_read_back() {
+        let mut settings = GameSettings::new(1, 2, Difficulty::Hard);
+        settings.seed = 42;
+        settings.set_strength(60);
+        settings.adaptive = true;
+        settings.hint_difficulty = Difficulty::Medium;
+        let mut game_match = Match::new(settings.new_board(), &settings.turn_order());
+        play_moves(&mut game_match, &["h1c1", "v1c1", "v1c2", "h2c1", "h3c1"]);
+
+        let record = GameRecord::from_match(&game_match, &settings, "2024-05-01");
+        let text = record.to_string();
+        let loaded = GameRecord::from_str(&text).expect("record can be parsed");
+
+        assert!(text.contains("Moves:h1c1 v1c1 v1c2 h2c1 h3c1\n"));
+        assert!(text.contains("Result:Unfinished (Player1=0,CPU=1)\n"));
+        assert!(is_game_record(&text));
+        assert_eq!(record.moves, loaded.moves);
+        assert_eq!(settings, loaded.settings);
+        assert_eq!("2024-05-01", loaded.date);
+
+        let replayed = loaded.replay().expect("moves can be replayed");
+        assert_eq!(
+            format!("{}", game_match.board()),
+            format!("{}", replayed.board())
+        );
+        assert_eq!(game_match.current_player(), replayed.current_player());
+    }
+
+    #[test]
+    fn game_continued_from_saved_board_keeps_the_start() {
+        let mut board = Board::new(1, 1);
+        let _ = board.set_wall_clicked(0, 0);
+        let _ = board.set_wall_clicked(1, 0);
+        let mut settings = GameSettings::new(1, 1, Difficulty::Easy);
+        settings.set_all_human_game(2);
+        let mut game_match = Match::new(board, &settings.turn_order())
+            .with_current_player(Player::Player2)
+            .expect("player takes a seat");
+        play_moves(&mut game_match, &["v1c2", "h2c1"]);
+
+        let record = GameRecord::from_match(&game_match, &settings, "2024-05-01");
+        let loaded = GameRecord::from_str(&record.to_string()).expect("record can be parsed");
+        let replayed = loaded.replay().expect("moves can be replayed");
+
+        assert_eq!(Player::Player2, loaded.settings.first_player());
+        assert!(replayed.is_over());
+        assert_eq!(Some(Player::Player1), replayed.get_statistics().winner);
+        assert!(record
+            .to_string()
+            .contains("Result:Player1 wins (Player1=1,Player2=0)\n"));
+    }
+
+    #[test]
+    fn illegal_moves_are_reported() {
+        let settings = GameSettings::new(2, 2, Difficulty::Easy);
+        let mut record = GameRecord::from_match(
+            &Match::new(settings.new_board(), &settings.turn_order()),
+            &settings,
+            "",
+        );
+        record.moves = vec![(0, 0), (0, 0)];
+
+        let loaded = GameRecord::from_str(&record.to_string()).expect("record can be parsed");
+
+        assert!(loaded.replay().is_err());
+        assert!(GameRecord::from_str("Width:2\nMoves:h1c1\n").is_err());
+    }
+
+    #[test]
+    fn dates_are_formatted_in_utc() {
+        assert_eq!("1970-01-01", format_date(0.0));
+        assert_eq!("2000-02-29", format_date(951_782_400.0));
+        assert_eq!("2026-10-18", format_date(1_792_300_000.0));
+    }
+}
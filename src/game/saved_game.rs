@@ -0,0 +1,387 @@
+use std::str::FromStr;
+
+use super::settings::{GameSettings, SeatKind, SeatSettings};
+use super::shape::BoardShape;
+use super::*;
+
+/// Game state written by the download button of the playing scene.
+#[derive(Debug, Clone)]
+pub struct SavedGame {
+    pub player: Player,
+    pub board: Board,
+    pub settings: GameSettings,
+}
+
+impl SavedGame {
+    pub fn new(player: Player, board: Board, settings: GameSettings) -> Self {
+        Self {
+            player,
+            board,
+            settings,
+        }
+    }
+}
+
+impl std::fmt::Display for SavedGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "Player:{:?}", self.player)?;
+        writeln!(f, "Width:{}", self.board.width)?;
+        writeln!(f, "Height:{}", self.board.height)?;
+        writeln!(f, "Shape:{}", self.settings.shape)?;
+        writeln!(f, "Board:")?;
+        writeln!(f, "{}", self.board)?;
+        writeln!(f, "Seats:{}", format_seats(&self.settings))?;
+        writeln!(f, "Difficulty:{:?}", self.settings.difficulty)?;
+        writeln!(f, "Strength:{}", self.settings.strength)?;
+        writeln!(f, "Adaptive:{}", self.settings.adaptive)?;
+        writeln!(f, "Hints:{:?}", self.settings.hint_difficulty)?;
+        writeln!(f, "Seed:{}", self.settings.seed)?;
+
+        Ok(())
+    }
+}
+
+impl FromStr for SavedGame {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut player = None;
+        let mut width = None;
+        let mut height = None;
+        let mut shape = BoardShape::Rectangle;
+        let mut board_lines = None;
+        let mut seats = None;
+        let mut difficulty = None;
+        let mut strength = MAX_CPU_STRENGTH;
+        let mut adaptive = false;
+        let mut hint_difficulty = Difficulty::Expert;
+        let mut seed = DEFAULT_RNG_SEED;
+
+        let mut lines = s.lines().peekable();
+        while let Some(line) = lines.next() {
+            let Some((key, value)) = line.split_once(':') else {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Err(format!("Unexpected line '{line}'"));
+            };
+            let value = value.trim();
+            match key.trim() {
+                "Player" => player = Some(Player::from_str(value)?),
+                "Width" => width = Some(parse_dimension(value)?),
+                "Height" => height = Some(parse_dimension(value)?),
+                "Shape" => shape = BoardShape::from_str(value)?,
+                "Board" => {
+                    let mut rows = vec![];
+                    while let Some(row) = lines.next_if(|row| !row.contains(':')) {
+                        rows.push(row);
+                    }
+                    while rows.last().is_some_and(|row| row.trim().is_empty()) {
+                        rows.pop();
+                    }
+                    board_lines = Some(rows.join("\n"));
+                }
+                "Seats" => seats = Some(parse_seats(value)?),
+                "OnePlayerMode" => {
+                    let one_player_mode = bool::from_str(value)
+                        .map_err(|_| format!("Wrong one player mode '{value}'"))?;
+                    seats = Some(if one_player_mode {
+                        vec![(Player::Player1, Some(SeatKind::Human)), (Player::CPU, None)]
+                    } else {
+                        vec![
+                            (Player::Player1, Some(SeatKind::Human)),
+                            (Player::Player2, Some(SeatKind::Human)),
+                        ]
+                    });
+                }
+                "Difficulty" => difficulty = Some(Difficulty::from_str(value)?),
+                "Strength" => strength = parse_strength(value)?,
+                "Adaptive" => adaptive = parse_adaptive(value)?,
+                "Hints" => hint_difficulty = Difficulty::from_str(value)?,
+                "Seed" => seed = parse_seed(value)?,
+                _ => return Err(format!("Unknown key '{key}'")),
+            }
+        }
+
+        let board = Board::from_str(&board_lines.ok_or("Board is missing")?)?;
+        if Some(board.width) != width || Some(board.height) != height {
+            return Err("Board does not match the declared dimensions".to_string());
+        }
+
+        let difficulty = difficulty.ok_or("Difficulty is missing")?;
+        let seats = seats
+            .ok_or("Seats are missing")?
+            .into_iter()
+            .map(|(player, kind)| {
+                SeatSettings::new(player, kind.unwrap_or(SeatKind::Cpu(difficulty)))
+            })
+            .collect::<Vec<_>>();
+        let player = player.ok_or("Player is missing")?;
+        let first_seat = seats
+            .iter()
+            .position(|seat| seat.player == player)
+            .ok_or(format!("{player:?} does not take a seat"))?;
+
+        let mut settings = GameSettings::new(board.width, board.height, difficulty);
+        settings.shape = shape;
+        settings.seats = seats;
+        settings.first_seat = first_seat;
+        settings.set_strength(strength);
+        settings.adaptive = adaptive;
+        settings.hint_difficulty = hint_difficulty;
+        settings.seed = seed;
+
+        Ok(Self {
+            player,
+            board,
+            settings,
+        })
+    }
+}
+
+pub(super) fn parse_dimension(value: &str) -> Result<usize, String> {
+    usize::from_str(value).map_err(|_| format!("Wrong board dimension '{value}'"))
+}
+
+pub(super) fn parse_strength(value: &str) -> Result<usize, String> {
+    usize::from_str(value).map_err(|_| format!("Wrong strength '{value}'"))
+}
+
+pub(super) fn parse_adaptive(value: &str) -> Result<bool, String> {
+    bool::from_str(value).map_err(|_| format!("Wrong adaptive flag '{value}'"))
+}
+
+pub(super) fn parse_seed(value: &str) -> Result<u64, String> {
+    u64::from_str(value).map_err(|_| format!("Wrong seed '{value}'"))
+}
+
+pub(super) fn format_seats(settings: &GameSettings) -> String {
+    settings
+        .seats
+        .iter()
+        .map(|seat| format!("{:?}={}", seat.player, seat.kind))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parses `Player1=Human,CPU=Hard` into seats. A seat given as `CPU` takes the difficulty
+/// of the saved game.
+pub(super) fn parse_seats(value: &str) -> Result<Vec<(Player, Option<SeatKind>)>, String> {
+    let seats = value
+        .split(',')
+        .map(|seat| {
+            let (player, controller) = seat
+                .split_once('=')
+                .ok_or(format!("Wrong seat '{seat}'"))?;
+            let kind = match controller.trim() {
+                "CPU" => None,
+                controller => Some(SeatKind::from_str(controller)?),
+            };
+            Ok((Player::from_str(player.trim())?, kind))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if !(MIN_SEAT_COUNT..=MAX_SEAT_COUNT).contains(&seats.len()) {
+        return Err(format!("Wrong number of seats '{}'", seats.len()));
+    }
+    Ok(seats)
+}
+
+#[cfg(test)]
+mod saved_game_tests {
+    use super::*;
+
+    #[test]
+    fn saved_game_can_be_loaded_back() {
+        let mut board = Board::new(3, 2);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(1, 0, Player::CPU);
+        let _ = board.click_wall(1, 1, Player::Player1);
+        let _ = board.click_wall(2, 0, Player::CPU);
+        let _ = board.click_wall(3, 3, Player::Player1);
+        let saved_game = SavedGame::new(Player::CPU, board, GameSettings::new(3, 2, Difficulty::Hard));
+
+        let loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+
+        assert_eq!(Player::CPU, loaded_game.player);
+        assert!(loaded_game.settings.is_one_player_game());
+        assert_eq!(vec![Player::Player1, Player::CPU], loaded_game.settings.turn_order());
+        assert_eq!(SeatKind::Cpu(Difficulty::Hard), loaded_game.settings.seats[1].kind);
+        assert_eq!(Difficulty::Hard, loaded_game.settings.difficulty);
+        assert_eq!(3, loaded_game.board.width);
+        assert_eq!(2, loaded_game.board.height);
+        assert_eq!(format!("{}", saved_game.board), format!("{}", loaded_game.board));
+        assert_eq!(Some(Player::CPU), loaded_game.board.cells[0][0].owner);
+        assert_eq!(1, loaded_game.board.statistics.get_points(Player::CPU));
+        assert_eq!(4, loaded_game.board.cells[0][0].counter);
+        assert_eq!(1, loaded_game.board.cells[1][2].counter);
+    }
+
+    #[test]
+    fn loaded_game_can_be_continued() {
+        let mut board = Board::new(1, 1);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(1, 0, Player::Player2);
+        let _ = board.click_wall(1, 1, Player::Player1);
+        let mut settings = GameSettings::new(1, 1, Difficulty::Easy);
+        settings.set_all_human_game(2);
+        let saved_game = SavedGame::new(Player::Player2, board, settings);
+
+        let mut loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+
+        assert_eq!(Ok(true), loaded_game.board.click_wall(2, 0, loaded_game.player));
+        assert!(loaded_game.board.all_is_clicked());
+        assert_eq!(Some(Player::Player2), loaded_game.board.get_statistics().winner);
+    }
+
+    #[test]
+    fn missing_fields_are_reported() {
+        let s = "Player:Player1\nWidth:1\nHeight:1\nOnePlayerMode:true\nDifficulty:Easy\n";
+
+        assert!(SavedGame::from_str(s).is_err());
+    }
+
+    #[test]
+    fn unknown_values_are_reported() {
+        let board = Board::new(1, 1);
+        let saved_game = SavedGame::new(Player::Player1, board, GameSettings::new(1, 1, Difficulty::Easy));
+        let s = saved_game.to_string().replace("Difficulty:Easy", "Difficulty:Impossible");
+
+        assert!(SavedGame::from_str(&s).is_err());
+    }
+
+    #[test]
+    fn malformed_boards_are_reported() {
+        let mut board = Board::new(1, 1);
+        for (row, col) in [(0, 0), (1, 0), (1, 1), (2, 0)] {
+            let _ = board.click_wall(row, col, Player::CPU);
+        }
+        let saved_game = SavedGame::new(Player::Player1, board, GameSettings::new(1, 1, Difficulty::Easy));
+        let s = saved_game.to_string();
+
+        let long_row = s.replace("XCCCCCX\n", "XCCCCCXCCCCCX\n");
+        let missing_row = s.replace("\n XXXXX\n\nSeats", "\n\nSeats");
+
+        assert!(SavedGame::from_str(&long_row).is_err());
+        assert!(SavedGame::from_str(&missing_row).is_err());
+        assert!(SavedGame::from_str(&s).is_ok());
+    }
+
+    #[test]
+    fn seats_of_four_player_game_are_loaded_back() {
+        let mut board = Board::new(1, 1);
+        let _ = board.click_wall(0, 0, Player::Player1);
+        let _ = board.click_wall(1, 0, Player::CPU);
+        let _ = board.click_wall(1, 1, Player::Player3);
+        let _ = board.click_wall(2, 0, Player::Player4);
+        let mut settings = GameSettings::new(1, 1, Difficulty::Medium);
+        settings.set_seat_count(4);
+        settings.cycle_seat_kind(3);
+        let saved_game = SavedGame::new(Player::Player4, board, settings);
+
+        let loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+
+        assert_eq!(
+            vec![Player::Player1, Player::CPU, Player::Player3, Player::Player4],
+            loaded_game.settings.turn_order()
+        );
+        assert_eq!(SeatKind::Human, loaded_game.settings.seats[0].kind);
+        assert_eq!(SeatKind::Cpu(Difficulty::Hard), loaded_game.settings.seats[3].kind);
+        assert_eq!(Player::Player4, loaded_game.settings.first_player());
+        assert_eq!(Some(Player::Player4), loaded_game.board.cells[0][0].owner);
+        assert_eq!(1, loaded_game.board.statistics.get_points(Player::Player4));
+    }
+
+    #[test]
+    fn cpu_seat_takes_difficulty_of_saved_game() {
+        let board = Board::new(1, 1);
+        let saved_game = SavedGame::new(Player::Player1, board, GameSettings::new(1, 1, Difficulty::Hard));
+        let s = saved_game.to_string().replace("CPU=Hard", "CPU=CPU");
+
+        let loaded_game = SavedGame::from_str(&s).expect("saved game can be parsed");
+
+        assert_eq!(SeatKind::Cpu(Difficulty::Hard), loaded_game.settings.seats[1].kind);
+    }
+
+    #[test]
+    fn board_with_holes_is_loaded_back() {
+        let mut settings = GameSettings::new(3, 3, Difficulty::Medium);
+        settings.shape = BoardShape::Ring;
+        let mut board = settings.new_board();
+        let _ = board.click_wall(2, 0, Player::Player1);
+        let saved_game = SavedGame::new(Player::CPU, board, settings);
+
+        let loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+
+        assert_eq!(BoardShape::Ring, loaded_game.settings.shape);
+        assert!(!loaded_game.board.cells[1][1].exists);
+        assert!(!loaded_game.board.walls[3][1].is_clicked);
+        assert!(loaded_game.board.walls[2][0].is_clicked);
+        assert_eq!(format!("{}", saved_game.board), format!("{}", loaded_game.board));
+    }
+
+    #[test]
+    fn seed_is_loaded_back() {
+        let mut settings = GameSettings::new(1, 1, Difficulty::Hard);
+        settings.seed = 1234;
+        let saved_game = SavedGame::new(Player::Player1, Board::new(1, 1), settings);
+
+        let loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+        let legacy_game = SavedGame::from_str(&saved_game.to_string().replace("Seed:1234\n", ""))
+            .expect("saved game can be parsed");
+
+        assert_eq!(1234, loaded_game.settings.seed);
+        assert_eq!(DEFAULT_RNG_SEED, legacy_game.settings.seed);
+    }
+
+    #[test]
+    fn strength_settings_are_loaded_back() {
+        let mut settings = GameSettings::new(1, 1, Difficulty::Hard);
+        settings.set_strength(30);
+        settings.adaptive = true;
+        let saved_game = SavedGame::new(Player::Player1, Board::new(1, 1), settings);
+
+        let loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+        let legacy_game = SavedGame::from_str(&saved_game.to_string().replace("Strength:30\n", ""))
+            .expect("saved game can be parsed");
+
+        assert_eq!(30, loaded_game.settings.strength);
+        assert!(loaded_game.settings.adaptive);
+        assert_eq!(MAX_CPU_STRENGTH, legacy_game.settings.strength);
+    }
+
+    #[test]
+    fn hint_difficulty_is_loaded_back() {
+        let mut settings = GameSettings::new(1, 1, Difficulty::Hard);
+        settings.hint_difficulty = Difficulty::Easy;
+        let saved_game = SavedGame::new(Player::Player1, Board::new(1, 1), settings);
+
+        let loaded_game =
+            SavedGame::from_str(&saved_game.to_string()).expect("saved game can be parsed");
+        let legacy_game = SavedGame::from_str(&saved_game.to_string().replace("Hints:Easy\n", ""))
+            .expect("saved game can be parsed");
+
+        assert_eq!(Difficulty::Easy, loaded_game.settings.hint_difficulty);
+        assert_eq!(Difficulty::Expert, legacy_game.settings.hint_difficulty);
+    }
+
+    #[test]
+    fn legacy_one_player_mode_is_loaded() {
+        let board = Board::new(1, 1);
+        let saved_game = SavedGame::new(Player::Player1, board, GameSettings::new(1, 1, Difficulty::Easy));
+        let s = saved_game
+            .to_string()
+            .replace("Seats:Player1=Human,CPU=Easy", "OnePlayerMode:true");
+
+        let loaded_game = SavedGame::from_str(&s).expect("saved game can be parsed");
+
+        assert_eq!(vec![Player::Player1, Player::CPU], loaded_game.settings.turn_order());
+        assert_eq!(SeatKind::Cpu(Difficulty::Easy), loaded_game.settings.seats[1].kind);
+    }
+}
@@ -0,0 +1,333 @@
+use std::str::FromStr;
+
+use super::shape::BoardShape;
+use super::*;
+
+/// Controller picked for a seat in the main menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SeatKind {
+    Human,
+    Cpu(Difficulty),
+}
+
+impl SeatKind {
+    /// Cycles Human -> Easy -> Medium -> Hard -> Expert -> Human.
+    pub fn next(&self) -> Self {
+        match self {
+            SeatKind::Human => SeatKind::Cpu(Difficulty::Easy),
+            SeatKind::Cpu(Difficulty::Easy) => SeatKind::Cpu(Difficulty::Medium),
+            SeatKind::Cpu(Difficulty::Medium) => SeatKind::Cpu(Difficulty::Hard),
+            SeatKind::Cpu(Difficulty::Hard) => SeatKind::Cpu(Difficulty::Expert),
+            SeatKind::Cpu(Difficulty::Expert) => SeatKind::Human,
+        }
+    }
+
+    pub fn is_human(&self) -> bool {
+        matches!(self, SeatKind::Human)
+    }
+}
+
+impl std::fmt::Display for SeatKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SeatKind::Human => write!(f, "Human"),
+            SeatKind::Cpu(difficulty) => write!(f, "{difficulty:?}"),
+        }
+    }
+}
+
+impl FromStr for SeatKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Human" => Ok(SeatKind::Human),
+            _ => Ok(SeatKind::Cpu(
+                Difficulty::from_str(s).map_err(|_| format!("Unknown controller '{s}'"))?,
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SeatSettings {
+    pub player: Player,
+    pub kind: SeatKind,
+}
+
+impl SeatSettings {
+    pub fn new(player: Player, kind: SeatKind) -> Self {
+        Self { player, kind }
+    }
+}
+
+/// Everything chosen in the main menu. Scenes pass the settings on, so the menu shows the
+/// previous choice when a game is over or cancelled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSettings {
+    pub width: usize,
+    pub height: usize,
+    pub shape: BoardShape,
+    pub seats: Vec<SeatSettings>,
+    pub first_seat: usize,
+    pub difficulty: Difficulty,
+    /// Strength of the CPU players from 0 to `MAX_CPU_STRENGTH`, see `LimitedStrengthPlayer`.
+    pub strength: usize,
+    /// Takes the strength from the history of the human of a one-player game, see `adaptive`.
+    pub adaptive: bool,
+    pub cpu_speed: usize,
+    /// Difficulty of the CPU player which suggests the moves of the hints.
+    pub hint_difficulty: Difficulty,
+    /// Seed of the random numbers of the CPU players, see `SeededRng`.
+    pub seed: u64,
+}
+
+impl GameSettings {
+    pub fn new(width: usize, height: usize, difficulty: Difficulty) -> Self {
+        let mut settings = Self {
+            width,
+            height,
+            shape: BoardShape::Rectangle,
+            seats: vec![],
+            first_seat: 0,
+            difficulty,
+            strength: MAX_CPU_STRENGTH,
+            adaptive: false,
+            cpu_speed: DEFAULT_CPU_SPEED,
+            hint_difficulty: Difficulty::Expert,
+            seed: DEFAULT_RNG_SEED,
+        };
+        settings.set_one_player_game(MIN_SEAT_COUNT);
+        settings
+    }
+
+    /// Builds an empty board of the chosen size and shape.
+    pub fn new_board(&self) -> Board {
+        Board::with_mask(
+            self.width,
+            self.height,
+            &self.shape.mask(self.width, self.height),
+        )
+    }
+
+    /// First seat is taken by a human, all the others by the CPU of the chosen difficulty.
+    pub fn set_one_player_game(&mut self, seat_count: usize) {
+        let kinds = (0..seat_count)
+            .map(|idx| {
+                if idx == 0 {
+                    SeatKind::Human
+                } else {
+                    SeatKind::Cpu(self.difficulty)
+                }
+            })
+            .collect::<Vec<_>>();
+        self.set_seat_kinds(&kinds);
+    }
+
+    pub fn set_all_human_game(&mut self, seat_count: usize) {
+        self.set_seat_kinds(&vec![SeatKind::Human; seat_count]);
+    }
+
+    pub fn is_one_player_game(&self) -> bool {
+        self.seats.iter().filter(|seat| seat.kind.is_human()).count() == 1
+    }
+
+    /// Human of a one-player game, or `None` when more humans or none play.
+    pub fn human_player(&self) -> Option<Player> {
+        if !self.is_one_player_game() {
+            return None;
+        }
+        self.seats
+            .iter()
+            .find(|seat| seat.kind.is_human())
+            .map(|seat| seat.player)
+    }
+
+    /// Human whose results change the strength of the CPU, when the adaptive CPU is chosen.
+    pub fn adaptive_player(&self) -> Option<Player> {
+        self.adaptive.then(|| self.human_player()).flatten()
+    }
+
+    pub fn is_all_human_game(&self) -> bool {
+        self.seats.iter().all(|seat| seat.kind.is_human())
+    }
+
+    pub fn has_cpu_seat(&self) -> bool {
+        !self.is_all_human_game()
+    }
+
+    /// Sets the difficulty of the menu and of every CPU seat.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        let kinds = self
+            .seats
+            .iter()
+            .map(|seat| match seat.kind {
+                SeatKind::Human => SeatKind::Human,
+                SeatKind::Cpu(_) => SeatKind::Cpu(difficulty),
+            })
+            .collect::<Vec<_>>();
+        self.set_seat_kinds(&kinds);
+    }
+
+    /// Adds or removes seats at the end. New seats are taken by the CPU unless every seat is
+    /// taken by a human.
+    pub fn set_seat_count(&mut self, seat_count: usize) {
+        let seat_count = seat_count.clamp(MIN_SEAT_COUNT, MAX_SEAT_COUNT);
+        let new_kind = if self.is_all_human_game() {
+            SeatKind::Human
+        } else {
+            SeatKind::Cpu(self.difficulty)
+        };
+        let mut kinds = self.seats.iter().map(|seat| seat.kind).collect::<Vec<_>>();
+        kinds.resize(seat_count, new_kind);
+        self.set_seat_kinds(&kinds);
+    }
+
+    pub fn cycle_seat_kind(&mut self, seat: usize) {
+        let mut kinds = self.seats.iter().map(|seat| seat.kind).collect::<Vec<_>>();
+        if let Some(kind) = kinds.get_mut(seat) {
+            *kind = kind.next();
+        }
+        self.set_seat_kinds(&kinds);
+    }
+
+    pub fn cycle_first_seat(&mut self) {
+        self.first_seat = (self.first_seat + 1) % self.seats.len();
+    }
+
+    pub fn first_player(&self) -> Player {
+        self.seats[self.first_seat].player
+    }
+
+    pub fn turn_order(&self) -> Vec<Player> {
+        self.seats.iter().map(|seat| seat.player).collect()
+    }
+
+    pub fn set_strength(&mut self, strength: usize) {
+        self.strength = strength.min(MAX_CPU_STRENGTH);
+    }
+
+    /// Picks another seed, so the CPU players choose differently between equally good moves.
+    pub fn roll_seed(&mut self) {
+        self.seed = u64::from(quad_rand::rand());
+    }
+
+    pub fn get_cpu_move_ticks(&self) -> usize {
+        CPU_MOVE_TICKS[self.cpu_speed.min(CPU_MOVE_TICKS.len() - 1)]
+    }
+
+    pub fn set_cpu_speed(&mut self, cpu_speed: usize) {
+        self.cpu_speed = cpu_speed.min(CPU_MOVE_TICKS.len() - 1);
+    }
+
+    /// Seats are named after their position, the second seat is named `CPU` when it is taken
+    /// by the CPU, as in the classic one player game.
+    fn set_seat_kinds(&mut self, kinds: &[SeatKind]) {
+        self.seats = kinds
+            .iter()
+            .enumerate()
+            .map(|(idx, kind)| {
+                let player = if idx == 1 && !kind.is_human() {
+                    Player::CPU
+                } else {
+                    Player::ALL[idx]
+                };
+                SeatSettings::new(player, *kind)
+            })
+            .collect();
+        self.first_seat = self.first_seat.min(self.seats.len() - 1);
+    }
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self::new(DEFAULT_BOARD_WIDTH, DEFAULT_BOARD_HEIGHT, Difficulty::Medium)
+    }
+}
+
+/// Parses a board size given as `<width>x<height>`, e.g. `4x3`.
+pub fn parse_board_size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or(format!("Wrong board size '{value}'"))?;
+    let parse = |dimension: &str| {
+        dimension
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|dimension| *dimension > 0)
+            .ok_or(format!("Wrong board size '{value}'"))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+#[cfg(test)]
+mod game_settings_tests {
+    use super::*;
+
+    #[test]
+    fn board_size_is_given_as_width_by_height() {
+        assert_eq!(Ok((4, 3)), parse_board_size("4x3"));
+        assert!(parse_board_size("4").is_err());
+        assert!(parse_board_size("0x3").is_err());
+    }
+
+    #[test]
+    fn default_settings_describe_one_player_game() {
+        let settings = GameSettings::default();
+
+        assert_eq!(vec![Player::Player1, Player::CPU], settings.turn_order());
+        assert_eq!(SeatKind::Cpu(Difficulty::Medium), settings.seats[1].kind);
+        assert!(settings.is_one_player_game());
+    }
+
+    #[test]
+    fn seats_are_renamed_when_controllers_change() {
+        let mut settings = GameSettings::default();
+        settings.set_seat_count(3);
+        settings.cycle_seat_kind(1);
+        settings.cycle_seat_kind(0);
+
+        assert_eq!(
+            vec![Player::Player1, Player::CPU, Player::Player3],
+            settings.turn_order()
+        );
+        assert_eq!(SeatKind::Cpu(Difficulty::Easy), settings.seats[0].kind);
+        assert_eq!(SeatKind::Cpu(Difficulty::Hard), settings.seats[1].kind);
+
+        settings.cycle_seat_kind(1);
+        assert_eq!(SeatKind::Cpu(Difficulty::Expert), settings.seats[1].kind);
+        settings.cycle_seat_kind(1);
+
+        assert_eq!(Player::Player2, settings.seats[1].player);
+        assert!(settings.seats[1].kind.is_human());
+    }
+
+    #[test]
+    fn first_seat_stays_within_seats() {
+        let mut settings = GameSettings::default();
+        settings.set_seat_count(4);
+        settings.cycle_first_seat();
+        settings.cycle_first_seat();
+        settings.cycle_first_seat();
+        assert_eq!(Player::Player4, settings.first_player());
+
+        settings.set_seat_count(2);
+
+        assert_eq!(Player::CPU, settings.first_player());
+    }
+
+    #[test]
+    fn adaptive_cpu_follows_the_human_of_one_player_games() {
+        let mut settings = GameSettings::default();
+        assert_eq!(Some(Player::Player1), settings.human_player());
+        assert_eq!(None, settings.adaptive_player());
+
+        settings.adaptive = true;
+        assert_eq!(Some(Player::Player1), settings.adaptive_player());
+
+        settings.set_all_human_game(2);
+        assert_eq!(None, settings.adaptive_player());
+    }
+}
@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+/// Built-in shapes of the board offered by the main menu. A shape is turned into a cell mask
+/// of the chosen size, see `Board::with_mask`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BoardShape {
+    Rectangle,
+    LShape,
+    Cross,
+    Ring,
+    Holes,
+}
+
+impl BoardShape {
+    pub const ALL: [BoardShape; 5] = [
+        BoardShape::Rectangle,
+        BoardShape::LShape,
+        BoardShape::Cross,
+        BoardShape::Ring,
+        BoardShape::Holes,
+    ];
+
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|shape| shape == self)
+            .expect("every shape is listed");
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Returns `height` rows of `width` flags telling which cells belong to the board. Holes
+    /// are only cut where the size leaves room for them, so the board is never empty.
+    pub fn mask(&self, width: usize, height: usize) -> Vec<Vec<bool>> {
+        (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|col| !self.is_hole(width, height, row, col))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn is_hole(&self, width: usize, height: usize, row: usize, col: usize) -> bool {
+        let (third_w, third_h) = (width / 3, height / 3);
+        let in_middle = |row: usize, col: usize| {
+            (third_h..height - third_h).contains(&row) && (third_w..width - third_w).contains(&col)
+        };
+        match self {
+            BoardShape::Rectangle => false,
+            BoardShape::LShape => row < height / 2 && col >= width.div_ceil(2),
+            BoardShape::Cross => {
+                !(third_h..height - third_h).contains(&row)
+                    && !(third_w..width - third_w).contains(&col)
+            }
+            BoardShape::Ring => width >= 3 && height >= 3 && in_middle(row, col),
+            BoardShape::Holes => {
+                row % 2 == 1 && col % 2 == 1 && row + 1 < height && col + 1 < width
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for BoardShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BoardShape::LShape => write!(f, "L-shape"),
+            shape => write!(f, "{shape:?}"),
+        }
+    }
+}
+
+impl FromStr for BoardShape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|shape| shape.to_string() == s || format!("{shape:?}") == s)
+            .copied()
+            .ok_or(format!("Unknown board shape '{s}'"))
+    }
+}
+
+#[cfg(test)]
+mod board_shape_tests {
+    use super::*;
+
+    fn draw(mask: &[Vec<bool>]) -> Vec<String> {
+        mask.iter()
+            .map(|row| row.iter().map(|&cell| if cell { 'o' } else { '.' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn shapes_cut_holes_out_of_the_rectangle() {
+        assert_eq!(vec!["ooo", "ooo", "ooo"], draw(&BoardShape::Rectangle.mask(3, 3)));
+        assert_eq!(vec!["oo..", "oo..", "oooo", "oooo"], draw(&BoardShape::LShape.mask(4, 4)));
+        assert_eq!(vec![".o.", "ooo", ".o."], draw(&BoardShape::Cross.mask(3, 3)));
+        assert_eq!(vec!["oooo", "o..o", "o..o", "oooo"], draw(&BoardShape::Ring.mask(4, 4)));
+        assert_eq!(
+            vec!["ooooo", "o.o.o", "ooooo", "o.o.o", "ooooo"],
+            draw(&BoardShape::Holes.mask(5, 5))
+        );
+    }
+
+    #[test]
+    fn shapes_of_small_boards_are_not_empty() {
+        for shape in BoardShape::ALL {
+            assert!(shape.mask(1, 1)[0][0], "{shape}");
+            for (width, height) in [(1, 3), (2, 2), (3, 2)] {
+                assert!(shape.mask(width, height).iter().flatten().any(|&cell| cell), "{shape}");
+            }
+        }
+        assert_eq!(vec!["oo", "oo"], draw(&BoardShape::Ring.mask(2, 2)));
+    }
+
+    #[test]
+    fn shape_names_can_be_parsed() {
+        for shape in BoardShape::ALL {
+            assert_eq!(Ok(shape), BoardShape::from_str(&shape.to_string()));
+        }
+        assert_eq!(BoardShape::Rectangle, BoardShape::Holes.next());
+    }
+}
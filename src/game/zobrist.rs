@@ -0,0 +1,77 @@
+//! Zobrist keys of board positions. Every clicked wall and every owned house contributes a
+//! fixed random number and the key of a position is their xor, so it can be updated with a
+//! single xor when a wall is clicked or a house changes its owner. The numbers are derived
+//! from the coordinates, so keys do not depend on the size of the board or on the order of
+//! the moves and can be stored in files.
+
+use super::*;
+
+const WALL_SEED: u64 = 0x5752_414c_4c5f_4b45;
+const OWNER_SEED: u64 = 0x4f57_4e45_525f_4b45;
+
+/// SplitMix64 finalizer, spreads the bits of `value` over the whole result.
+pub fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+pub fn wall_key(row: usize, col: usize) -> u64 {
+    mix(WALL_SEED ^ (((row as u64) << 32) | col as u64))
+}
+
+pub fn owner_key(row: usize, col: usize, player: Player) -> u64 {
+    mix(OWNER_SEED ^ (((row as u64) << 40) | ((col as u64) << 8) | player.index() as u64))
+}
+
+#[cfg(test)]
+mod zobrist_tests {
+    use super::*;
+
+    #[test]
+    fn same_position_has_the_same_key_whatever_the_order_of_moves() {
+        let mut board = Board::new(2, 2);
+        board.click_wall(0, 0, Player::Player1).expect("wall can be clicked");
+        board.click_wall(1, 2, Player::CPU).expect("wall can be clicked");
+
+        let mut other = Board::new(2, 2);
+        other.click_wall(1, 2, Player::Player1).expect("wall can be clicked");
+        other.click_wall(0, 0, Player::CPU).expect("wall can be clicked");
+
+        assert_eq!(board.get_zobrist_key(), other.get_zobrist_key());
+        assert_ne!(Board::new(2, 2).get_zobrist_key(), board.get_zobrist_key());
+    }
+
+    #[test]
+    fn undo_restores_the_key() {
+        let mut board = Board::new(1, 1);
+        board.click_wall(0, 0, Player::Player1).expect("wall can be clicked");
+        board.click_wall(1, 0, Player::Player1).expect("wall can be clicked");
+        board.click_wall(1, 1, Player::Player1).expect("wall can be clicked");
+        let key = board.get_zobrist_key();
+
+        board.click_wall(2, 0, Player::CPU).expect("wall can be clicked");
+        let closed_key = board.get_zobrist_key();
+        board.undo();
+
+        assert_eq!(key, board.get_zobrist_key());
+        board.redo();
+        assert_eq!(closed_key, board.get_zobrist_key());
+    }
+
+    #[test]
+    fn owner_of_the_house_changes_the_key() {
+        let mut board = Board::new(1, 1);
+        let mut other = Board::new(1, 1);
+        for (row, col) in [(0, 0), (1, 0), (1, 1)] {
+            board.click_wall(row, col, Player::Player1).expect("wall can be clicked");
+            other.click_wall(row, col, Player::Player1).expect("wall can be clicked");
+        }
+
+        board.click_wall(2, 0, Player::Player1).expect("wall can be clicked");
+        other.click_wall(2, 0, Player::CPU).expect("wall can be clicked");
+
+        assert_ne!(board.get_zobrist_key(), other.get_zobrist_key());
+    }
+}
@@ -0,0 +1,8 @@
+extern crate good_web_game as ggez;
+
+pub mod ai;
+pub mod file;
+pub mod game;
+pub mod rendering;
+pub mod scene;
+pub mod state;
@@ -7,24 +7,21 @@ use ggez::timer::time;
 use ggez::Context;
 use ggez::GameResult;
 
-use game::controller::*;
-use state::prelude::*;
-
-pub mod ai;
-pub mod file;
-pub mod game;
-pub mod rendering;
-pub mod scene;
-pub mod state;
+use streets_and_houses::game::controller::*;
+use streets_and_houses::state::prelude::*;
 
 pub struct GameManager {
     current_game: Box<dyn GameInstance>,
 }
 
 impl GameManager {
-    pub fn new(ctx: &mut ggez::Context, quad_ctx: &mut ggez::event::GraphicsContext) -> Self {
+    pub fn new(
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut ggez::event::GraphicsContext,
+        seed: u64,
+    ) -> Self {
         Self {
-            current_game: Box::new(Game::<TitleScreenState>::new(ctx, quad_ctx)),
+            current_game: Box::new(Game::<TitleScreenState>::new(ctx, quad_ctx, seed)),
         }
     }
 
@@ -79,6 +76,7 @@ impl event::EventHandler<ggez::GameError> for GameManager {
 
 pub fn main() -> GameResult {
     init_random();
+    let seed = seed_from_args().unwrap_or_else(|| u64::from(quad_rand::rand()));
     ggez::start(
         ggez::conf::Conf::default()
             .cache(Some(include_bytes!("resources.tar")))
@@ -86,7 +84,7 @@ pub fn main() -> GameResult {
             .window_height(800)
             .window_title("Streets'n'Houses".to_string())
             .window_resizable(true),
-        |context, quad_ctx| Box::new(GameManager::new(context, quad_ctx)),
+        move |context, quad_ctx| Box::new(GameManager::new(context, quad_ctx, seed)),
     )
 }
 
@@ -94,3 +92,12 @@ fn init_random() {
     let time = time() * 10_000_000.0;
     quad_rand::srand(time as u64);
 }
+
+/// Seed of the CPU players given as `--seed <number>`, e.g. the seed of a game to replay.
+fn seed_from_args() -> Option<u64> {
+    let value = std::env::args().skip_while(|arg| arg != "--seed").nth(1)?;
+    value
+        .parse()
+        .map_err(|_| eprintln!("Wrong seed '{value}'"))
+        .ok()
+}
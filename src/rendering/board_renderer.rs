@@ -8,13 +8,13 @@ pub struct BoardRenderer {
     spritebatch_path_v: graphics::spritebatch::SpriteBatch,
     spritebatch_street_h: graphics::spritebatch::SpriteBatch,
     spritebatch_street_v: graphics::spritebatch::SpriteBatch,
-    spritebatch_a: graphics::spritebatch::SpriteBatch,
-    spritebatch_b: graphics::spritebatch::SpriteBatch,
+    spritebatch_buildings: Vec<graphics::spritebatch::SpriteBatch>,
     spritebatch_joints: Vec<graphics::spritebatch::SpriteBatch>,
     board: Board,
     player: Player,
     wall_bounding_boxes: Vec<Vec<Rect>>,
     draw_footer: bool,
+    hint: Option<(usize, usize)>,
 }
 
 impl BoardRenderer {
@@ -30,15 +30,12 @@ impl BoardRenderer {
         let image_path_v = graphics::Image::new(ctx, quad_ctx, "roads/path_v.png")?;
         let image_street_h = graphics::Image::new(ctx, quad_ctx, "roads/street_h.png")?;
         let image_street_v = graphics::Image::new(ctx, quad_ctx, "roads/street_v.png")?;
-        let image_a = graphics::Image::new(ctx, quad_ctx, "buildings/textured_red.png")?;
-        let image_b = graphics::Image::new(ctx, quad_ctx, "buildings/textured_green.png")?;
         let batch_empty = graphics::spritebatch::SpriteBatch::new(image_empty);
         let batch_path_h = graphics::spritebatch::SpriteBatch::new(image_path_h);
         let batch_path_v = graphics::spritebatch::SpriteBatch::new(image_path_v);
         let batch_street_h = graphics::spritebatch::SpriteBatch::new(image_street_h);
         let batch_street_v = graphics::spritebatch::SpriteBatch::new(image_street_v);
-        let batch_a = graphics::spritebatch::SpriteBatch::new(image_a);
-        let batch_b = graphics::spritebatch::SpriteBatch::new(image_b);
+        let batch_buildings = generate_building_spritesheets(ctx, quad_ctx)?;
         let bacth_joints = generate_joint_spritesheets(ctx, quad_ctx)?;
         let wall_bounding_boxes =
             vec![vec![Rect::default(); board.width + 1]; 2 * board.height + 1];
@@ -49,13 +46,13 @@ impl BoardRenderer {
             spritebatch_path_v: batch_path_v,
             spritebatch_street_h: batch_street_h,
             spritebatch_street_v: batch_street_v,
-            spritebatch_a: batch_a,
-            spritebatch_b: batch_b,
+            spritebatch_buildings: batch_buildings,
             spritebatch_joints: bacth_joints,
             board,
             player,
             wall_bounding_boxes,
             draw_footer,
+            hint: None,
         };
         Ok(s)
     }
@@ -119,12 +116,9 @@ impl BoardRenderer {
                 tile_size.1 / IMAGE_HEIGHT,
             ));
 
-        if row < 2 * self.board.height {
+        if row < 2 * self.board.height && self.board.cells[row / 2][col].exists {
             if let Some(player) = self.board.cells[row / 2][col].owner {
-                match player {
-                    game::Player::Player1 => self.spritebatch_a.add(p),
-                    game::Player::Player2 | game::Player::CPU => self.spritebatch_b.add(p),
-                };
+                self.spritebatch_buildings[player.index()].add(p);
             } else {
                 self.spritebatch_empty.add(p);
             }
@@ -138,11 +132,14 @@ impl BoardRenderer {
         col: usize,
         dest: Point2<f32>,
     ) {
+        if !self.board.walls[row][col].exists() {
+            return;
+        }
         let p = graphics::DrawParam::new().dest(dest).scale(Vector2::new(
             tile_size.0 / IMAGE_WIDTH,
             tile_size.1 / IMAGE_HEIGHT,
         ));
-        if row % 2 == 0 {
+        if row.is_multiple_of(2) {
             if self.board.walls[row][col].is_clicked {
                 self.spritebatch_street_h.add(p);
             } else {
@@ -162,6 +159,9 @@ impl BoardRenderer {
         col: usize,
         dest: Point2<f32>,
     ) {
+        if !self.board.joint_exists(row / 2, col) {
+            return;
+        }
         let p = graphics::DrawParam::new().dest(dest).scale(Vector2::new(
             tile_size.0 / IMAGE_WIDTH,
             tile_size.1 / IMAGE_HEIGHT,
@@ -198,6 +198,16 @@ impl BoardRenderer {
         ))
     }
 
+    /// Walls which are not part of the board get an empty bounding box, so they cannot be
+    /// clicked.
+    fn get_wall_bounding_box(&self, bounding_box: Rect, row: usize, col: usize) -> Rect {
+        if self.board.walls[row][col].exists() {
+            bounding_box
+        } else {
+            Rect::default()
+        }
+    }
+
     pub fn get_wall_bounding_boxes(&self) -> Vec<Vec<Rect>> {
         self.wall_bounding_boxes.clone()
     }
@@ -206,11 +216,32 @@ impl BoardRenderer {
         self.board = board.clone();
     }
 
+    /// Player whose color is the background of the board.
+    pub fn set_player(&mut self, player: Player) {
+        self.player = player;
+    }
+
+    /// Highlights the suggested wall, `None` removes the highlight.
+    pub fn set_hint(&mut self, hint: Option<(usize, usize)>) {
+        self.hint = hint;
+    }
+
+    fn draw_hint(&self, ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext) -> GameResult {
+        let Some((row, col)) = self.hint else {
+            return Ok(());
+        };
+        let overlay = graphics::Mesh::new_rectangle(
+            ctx,
+            quad_ctx,
+            graphics::DrawMode::fill(),
+            self.wall_bounding_boxes[row][col],
+            graphics::Color::from_rgba_u32(HINT_OVERLAY_COLOR),
+        )?;
+        graphics::draw(ctx, quad_ctx, &overlay, graphics::DrawParam::new())
+    }
+
     pub fn get_bg_color(&self) -> u32 {
-        match self.player {
-            Player::Player1 => PLAYER1_BGCOLOR,
-            Player::Player2 | Player::CPU => PLAYER2_BGCOLOR,
-        }
+        get_player_bg_color(self.player)
     }
 }
 
@@ -237,8 +268,11 @@ impl Renderer for BoardRenderer {
                     let dest =
                         get_horizontal_wall_sprite_destination(tile_size, translation, row, col);
                     self.add_wall_sprite(tile_size, row, col, dest);
-                    self.wall_bounding_boxes[row][col] =
-                        get_horizontal_wall_sprite_bounding_box(tile_size, translation, row, col);
+                    self.wall_bounding_boxes[row][col] = self.get_wall_bounding_box(
+                        get_horizontal_wall_sprite_bounding_box(tile_size, translation, row, col),
+                        row,
+                        col,
+                    );
 
                     self.add_cell_sprite(tile_size, translation, row, col);
                 }
@@ -247,8 +281,11 @@ impl Renderer for BoardRenderer {
                     let mut dest =
                         get_vertical_wall_sprite_destination(tile_size, translation, row, col);
                     self.add_wall_sprite(tile_size, row, col, dest);
-                    self.wall_bounding_boxes[row][col] =
-                        get_vertical_wall_sprite_bounding_box(tile_size, translation, row, col);
+                    self.wall_bounding_boxes[row][col] = self.get_wall_bounding_box(
+                        get_vertical_wall_sprite_bounding_box(tile_size, translation, row, col),
+                        row,
+                        col,
+                    );
 
                     dest.y -= H_STREET_HEIGHT * tile_size.1 / IMAGE_HEIGHT;
                     self.add_joint_sprite(tile_size, row, col, dest);
@@ -267,24 +304,60 @@ impl Renderer for BoardRenderer {
             &mut self.spritebatch_path_v,
             &mut self.spritebatch_street_h,
             &mut self.spritebatch_street_v,
-            &mut self.spritebatch_a,
-            &mut self.spritebatch_b,
         ] {
             graphics::draw(ctx, quad_ctx, spritebatch, graphics::DrawParam::new())?;
             spritebatch.clear();
         }
 
+        for spritebatch in &mut self.spritebatch_buildings {
+            graphics::draw(ctx, quad_ctx, spritebatch, graphics::DrawParam::new())?;
+            spritebatch.clear();
+        }
+
         for spritebatch in &mut self.spritebatch_joints {
             graphics::draw(ctx, quad_ctx, spritebatch, graphics::DrawParam::new())?;
             spritebatch.clear();
         }
 
+        self.draw_hint(ctx, quad_ctx)?;
+
         graphics::present(ctx, quad_ctx)?;
 
         Ok(())
     }
 }
 
+pub fn get_player_bg_color(player: Player) -> u32 {
+    match player {
+        Player::Player1 => PLAYER1_BGCOLOR,
+        Player::Player2 | Player::CPU => PLAYER2_BGCOLOR,
+        Player::Player3 => PLAYER3_BGCOLOR,
+        Player::Player4 => PLAYER4_BGCOLOR,
+    }
+}
+
+fn get_building_image_path(player: Player) -> &'static str {
+    match player {
+        Player::Player1 => "buildings/textured_red.png",
+        Player::Player2 | Player::CPU => "buildings/textured_green.png",
+        Player::Player3 => "buildings/light_green.png",
+        Player::Player4 => "buildings/red.png",
+    }
+}
+
+/// Returns one sprite batch per player, indexed with `Player::index`.
+fn generate_building_spritesheets(
+    ctx: &mut Context,
+    quad_ctx: &mut miniquad::GraphicsContext,
+) -> GameResult<Vec<graphics::spritebatch::SpriteBatch>> {
+    let mut spritebatches = vec![];
+    for player in Player::ALL {
+        let image = graphics::Image::new(ctx, quad_ctx, get_building_image_path(player))?;
+        spritebatches.push(graphics::spritebatch::SpriteBatch::new(image));
+    }
+    Ok(spritebatches)
+}
+
 fn generate_joint_spritesheets(
     ctx: &mut Context,
     quad_ctx: &mut miniquad::GraphicsContext,
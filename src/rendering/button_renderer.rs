@@ -52,3 +52,47 @@ impl Renderer for ButtonRenderer {
         )
     }
 }
+
+#[derive(Debug)]
+pub struct TextButtonRenderer {
+    caption: String,
+    pos_x: f32,
+    pos_y: f32,
+    bounding_rect: Rect,
+}
+
+impl TextButtonRenderer {
+    pub fn new(caption: &str, pos_x: f32, pos_y: f32) -> Self {
+        Self {
+            caption: caption.to_string(),
+            pos_x,
+            pos_y,
+            bounding_rect: Rect::default(),
+        }
+    }
+
+    pub fn get_bouding_rect(&self) -> Rect {
+        self.bounding_rect
+    }
+
+    pub fn set_caption(&mut self, caption: &str) {
+        self.caption = caption.to_string();
+    }
+}
+
+impl Renderer for TextButtonRenderer {
+    fn draw(&mut self, ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext) -> GameResult {
+        let scene_scale = get_scene_scale(quad_ctx);
+        let (w, _) = quad_ctx.screen_size();
+
+        self.bounding_rect = draw_button(
+            ctx,
+            quad_ctx,
+            w - self.pos_x * scene_scale.0,
+            self.pos_y * scene_scale.1,
+            &self.caption,
+            false,
+        )?;
+        Ok(())
+    }
+}
@@ -9,14 +9,40 @@ pub const TITLE_SCREEN_BGCOLOR: u32 = 0xE8D8B3;
 pub const MAIN_MENU_BGCOLOR: u32 = 0xD6C7A3;
 pub const PLAYER1_BGCOLOR: u32 = 0xB60909;
 pub const PLAYER2_BGCOLOR: u32 = 0x1CAE12;
+pub const PLAYER3_BGCOLOR: u32 = 0x1F4FB5;
+pub const PLAYER4_BGCOLOR: u32 = 0xC98A0B;
+pub const HINT_OVERLAY_COLOR: u32 = 0xFFE0309A;
 
 pub const PLAYING_CANCEL_BUTTON_MARGIN_X : f32 = 20.0;
 pub const PLAYING_CANCEL_BUTTON_MARGIN_Y : f32 = 20.0;
 pub const PLAYING_DOWNLOAD_BUTTON_MARGIN_X : f32 = 100.0;
 pub const PLAYING_DOWNLOAD_BUTTON_MARGIN_Y : f32 = 20.0;
-pub const PLAYING_TICK_COUNT: usize = 90;
+pub const PLAYING_UNDO_BUTTON_X: f32 = 300.0;
+pub const PLAYING_UNDO_BUTTON_Y: f32 = 52.0;
+pub const PLAYING_REDO_BUTTON_X: f32 = 200.0;
+pub const PLAYING_REDO_BUTTON_Y: f32 = 52.0;
+pub const CPU_MOVE_TICKS: [usize; 5] = [180, 90, 45, 15, 1];
+pub const DEFAULT_CPU_SPEED: usize = 1;
+pub const PLAYING_SLOWER_BUTTON_X: f32 = 560.0;
+pub const PLAYING_SLOWER_BUTTON_Y: f32 = 52.0;
+pub const PLAYING_FASTER_BUTTON_X: f32 = 420.0;
+pub const PLAYING_FASTER_BUTTON_Y: f32 = 52.0;
+pub const PLAYING_HINT_BUTTON_X: f32 = 680.0;
+pub const PLAYING_HINT_BUTTON_Y: f32 = 52.0;
+pub const ANALYSIS_PREVIOUS_BUTTON_X: f32 = 420.0;
+pub const ANALYSIS_PREVIOUS_BUTTON_Y: f32 = 52.0;
+pub const ANALYSIS_NEXT_BUTTON_X: f32 = 310.0;
+pub const ANALYSIS_NEXT_BUTTON_Y: f32 = 52.0;
+pub const ANALYSIS_NEXT_MISTAKE_BUTTON_X: f32 = 170.0;
+pub const ANALYSIS_NEXT_MISTAKE_BUTTON_Y: f32 = 52.0;
+pub const ANALYSIS_CAPTION_Y: f32 = 100.0;
+pub const PLAYING_THINKING_X: f32 = 250.0;
+pub const PLAYING_THINKING_Y: f32 = 100.0;
+pub const CPU_THINKING_STEP: f64 = 0.01;
+pub const SAVED_GAME_FILE_SUFFIX: &str = "_board.txt";
 
 pub const PREPARE_PLAYER_DURATION: f32 = 0.75;
+pub const PREPARE_PLAYER_LEVEL_Y: f32 = 740.0;
 
 pub const TITLE_SCREEN_START_BUTTON_X: f32 = 330.0;
 pub const TITLE_SCREEN_START_BUTTON_Y: f32 = 658.0;
@@ -25,6 +51,8 @@ pub const TITLE_SCREEN_START_BUTTON_HEIGHT: f32 = 107.0;
 
 pub const DEFAULT_BOARD_WIDTH: usize = 5;
 pub const DEFAULT_BOARD_HEIGHT: usize = 5;
+pub const MIN_BOARD_SIZE: usize = 1;
+pub const MAX_BOARD_SIZE: usize = 15;
 
 pub const MAIN_MENU_ONE_PLAYER_X: f32 = 275.0;
 pub const MAIN_MENU_ONE_PLAYER_Y: f32 = 210.0;
@@ -36,6 +64,32 @@ pub const MAIN_MENU_START_BUTTON_Y: f32 = 468.0;
 pub const MAIN_MENU_START_BUTTON_WIDTH: f32 = 155.0;
 pub const MAIN_MENU_START_BUTTON_HEIGHT: f32 = 127.0;
 
+pub const MAIN_MENU_LOAD_BUTTON_X: f32 = 600.0;
+pub const MAIN_MENU_LOAD_BUTTON_Y: f32 = 530.0;
+
+pub const MAIN_MENU_SEAT_COUNT_X: f32 = 600.0;
+pub const MAIN_MENU_SEAT_COUNT_Y: f32 = 610.0;
+pub const MAIN_MENU_SEAT_COUNT_DECR_X: f32 = 520.0;
+pub const MAIN_MENU_SEAT_COUNT_INCR_X: f32 = 680.0;
+pub const MAIN_MENU_FIRST_SEAT_Y: f32 = 680.0;
+pub const MAIN_MENU_SEATS_X: f32 = 150.0;
+pub const MAIN_MENU_SEATS_Y: f32 = 530.0;
+pub const MAIN_MENU_SEATS_SPACING: f32 = 50.0;
+pub const MAIN_MENU_SHAPE_X: f32 = 650.0;
+pub const MAIN_MENU_SHAPE_Y: f32 = 345.0;
+pub const MAIN_MENU_SEED_X: f32 = 650.0;
+pub const MAIN_MENU_SEED_Y: f32 = 275.0;
+pub const MAIN_MENU_STRENGTH_X: f32 = 600.0;
+pub const MAIN_MENU_STRENGTH_Y: f32 = 750.0;
+pub const MAIN_MENU_STRENGTH_DECR_X: f32 = 500.0;
+pub const MAIN_MENU_STRENGTH_INCR_X: f32 = 700.0;
+pub const MAIN_MENU_ADAPTIVE_X: f32 = 250.0;
+pub const MAIN_MENU_ADAPTIVE_Y: f32 = 750.0;
+pub const MAIN_MENU_HINTS_X: f32 = 600.0;
+pub const MAIN_MENU_HINTS_Y: f32 = 100.0;
+pub const MAIN_MENU_HINTS_DECR_X: f32 = 500.0;
+pub const MAIN_MENU_HINTS_INCR_X: f32 = 700.0;
+
 pub const MAIN_MENU_INCR_BUTTON_X: f32 = 441.0;
 pub const MAIN_MENU_INCR_WIDTH_Y: f32 = 294.0;
 pub const MAIN_MENU_INCR_BUTTON_WIDTH: f32 = 48.0;
@@ -67,6 +121,8 @@ pub const MAIN_MENU_CHECKBOX_HARD_X: f32 = 525.0;
 pub const MAIN_MENU_CHECKBOX_HARD_Y: f32 = 421.0;
 pub const MAIN_MENU_HARD_X: f32 = 575.0;
 pub const MAIN_MENU_HARD_Y: f32 = 418.0;
+pub const MAIN_MENU_EXPERT_X: f32 = 740.0;
+pub const MAIN_MENU_EXPERT_Y: f32 = 440.0;
 
 pub const PLAYER_DATA_PANEL_TITLE_X_HORZ: f32 = 40.0;
 pub const PLAYER_DATA_PANEL_TITLE_Y_HORZ: f32 = 334.0;
@@ -77,6 +133,10 @@ pub const PLAYER_DATA_PANEL_TITLE_X_VERT: f32 = 288.0;
 pub const PLAYER_DATA_PANEL_TITLE_Y_VERT: f32 = 88.0;
 pub const PLAYER_DATA_PANEL_POINTS_X_VERT: f32 = 378.0;
 pub const PLAYER_DATA_PANEL_POINTS_Y_VERT: f32 = 151.0;
+pub const PLAYER_DATA_PANEL_TITLE_WIDTH: f32 = 224.0;
+pub const PLAYER_DATA_PANEL_TITLE_HEIGHT: f32 = 53.0;
+pub const PLAYER_DATA_PANEL_ROW_SPACING_HORZ: f32 = 300.0;
+pub const PLAYER_DATA_PANEL_ROW_SPACING_VERT: f32 = 400.0;
 
 pub const GAME_OVER_PLAYER_1_WINS_X: f32 = 200.0;
 pub const GAME_OVER_PLAYER_1_WINS_Y: f32 = 247.0;
@@ -84,22 +144,52 @@ pub const GAME_OVER_PLAYER_2_WINS_X: f32 = 200.0;
 pub const GAME_OVER_PLAYER_2_WINS_Y: f32 = 247.0;
 pub const GAME_OVER_CPU_WINS_X: f32 = 247.0;
 pub const GAME_OVER_CPU_WINS_Y: f32 = 247.0;
+pub const GAME_OVER_WINS_TEXT_X: f32 = 400.0;
+pub const GAME_OVER_WINS_TEXT_Y: f32 = 270.0;
 pub const GAME_OVER_TIE_X: f32 = 265.0;
 pub const GAME_OVER_TIE_Y: f32 = 247.0;
 pub const GAME_OVER_PLAYER_1_POINTS_X: f32 = 120.0;
 pub const GAME_OVER_PLAYER_1_POINTS_Y: f32 = 323.0;
-pub const GAME_OVER_PLAYER_2_POINTS_X: f32 = 120.0;
 pub const GAME_OVER_PLAYER_2_POINTS_Y: f32 = 399.0;
-pub const GAME_OVER_CPU_POINTS_X: f32 = 120.0;
-pub const GAME_OVER_CPU_POINTS_Y: f32 = 399.0;
+pub const GAME_OVER_POINTS_TITLE_WIDTH: f32 = 393.0;
+pub const GAME_OVER_POINTS_TITLE_HEIGHT: f32 = 53.0;
 pub const GAME_OVER_POINTS_1_X: f32 = 513.0;
 pub const GAME_OVER_POINTS_1_Y: f32 = 313.0;
-pub const GAME_OVER_POINTS_2_X: f32 = 513.0;
-pub const GAME_OVER_POINTS_2_Y: f32 = 389.0;
 
+pub const GAME_OVER_HINTS_X: f32 = 400.0;
+pub const GAME_OVER_HINTS_Y: f32 = 630.0;
+pub const GAME_OVER_ANALYSE_BUTTON_X: f32 = 400.0;
+pub const GAME_OVER_ANALYSE_BUTTON_Y: f32 = 700.0;
+pub const GAME_OVER_ADAPTIVE_LEVEL_X: f32 = 400.0;
+pub const GAME_OVER_ADAPTIVE_LEVEL_Y: f32 = 760.0;
 pub const GAME_OVER_START_BUTTON_X: f32 = 322.0;
 pub const GAME_OVER_START_BUTTON_Y: f32 = 458.0;
 pub const GAME_OVER_START_BUTTON_WIDTH: f32 = 178.0;
 pub const GAME_OVER_START_BUTTON_HEIGHT: f32 = 142.0;
 
-pub const MAX_MINMAX_DEPTH: usize = 3;
\ No newline at end of file
+pub const MAX_MINMAX_DEPTH: usize = 32;
+pub const MINMAX_TIME_LIMIT: f64 = 2.0;
+pub const MINMAX_NODE_LIMIT: usize = 1_500_000;
+pub const ENDGAME_SOLVER_WALLS: usize = 18;
+pub const OPENING_BOOK_SOLVER_WALLS: usize = 24;
+pub const OPENING_BOOK_MARGIN_TOLERANCE: f64 = 1.0;
+pub const TRANSPOSITION_TABLE_MEMORY: usize = 32 * 1024 * 1024;
+pub const TRANSPOSITION_TABLE_MOVES: usize = 8;
+pub const NIMSTRING_COMPONENT_WALLS: usize = 16;
+pub const MCTS_TIME_LIMIT: f64 = 2.0;
+pub const MCTS_EXPLORATION: f64 = 1.4;
+pub const ANALYSIS_TIME_LIMIT: f64 = 0.05;
+pub const ANALYSIS_MISTAKE_THRESHOLD: i32 = 1;
+pub const DEFAULT_RNG_SEED: u64 = 0;
+pub const TOURNAMENT_ELO_BASE: f64 = 1500.0;
+pub const TOURNAMENT_ELO_ITERATIONS: usize = 50;
+pub const MAX_CPU_STRENGTH: usize = 100;
+pub const CPU_STRENGTH_STEP: usize = 10;
+pub const STRENGTH_MAX_TEMPERATURE: f64 = 3.0;
+pub const STRENGTH_EVALUATION_DEPTH: usize = 2;
+pub const STRENGTH_EVALUATED_WALLS: usize = 24;
+pub const STRENGTH_TABLE_MEMORY: usize = 1024 * 1024;
+pub const ADAPTIVE_INITIAL_STRENGTH: usize = 50;
+pub const ADAPTIVE_STRENGTH_STEP: usize = 5;
+pub const ADAPTIVE_HISTORY_LENGTH: usize = 20;
+pub const ADAPTIVE_HISTORY_FILE: &str = "adaptive_history.txt";
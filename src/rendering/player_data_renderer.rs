@@ -1,3 +1,4 @@
+use super::prelude::draw_text;
 use super::prelude::*;
 use super::*;
 use crate::scene::prelude::*;
@@ -5,7 +6,10 @@ use crate::scene::prelude::*;
 #[derive(Debug)]
 pub struct PlayerDataRenderer {
     player: Player,
-    image_title: graphics::Image,
+    seat: usize,
+    seat_count: usize,
+    is_player_turn: bool,
+    image_title: Option<graphics::Image>,
     image_points: Vec<graphics::Image>,
     orientation: Orientation,
 }
@@ -15,22 +19,28 @@ impl PlayerDataRenderer {
         ctx: &mut Context,
         quad_ctx: &mut miniquad::GraphicsContext,
         player: Player,
+        seat: usize,
+        seat_count: usize,
         points: usize,
         is_player_turn : bool,
     ) -> GameResult<Self> {
         let image_title = match player {
-            Player::Player1 if is_player_turn => graphics::Image::new(ctx, quad_ctx, "ui/player_1_u.png")?,
-            Player::Player1  => graphics::Image::new(ctx, quad_ctx, "ui/player_1.png")?,
-            Player::Player2 if is_player_turn => graphics::Image::new(ctx, quad_ctx, "ui/player_2_u.png")?,
-            Player::Player2 => graphics::Image::new(ctx, quad_ctx, "ui/player_2.png")?,
-            Player::CPU if is_player_turn => graphics::Image::new(ctx, quad_ctx, "ui/CPU_u.png")?,
-            Player::CPU  => graphics::Image::new(ctx, quad_ctx, "ui/CPU.png")?,
+            Player::Player1 if is_player_turn => Some(graphics::Image::new(ctx, quad_ctx, "ui/player_1_u.png")?),
+            Player::Player1  => Some(graphics::Image::new(ctx, quad_ctx, "ui/player_1.png")?),
+            Player::Player2 if is_player_turn => Some(graphics::Image::new(ctx, quad_ctx, "ui/player_2_u.png")?),
+            Player::Player2 => Some(graphics::Image::new(ctx, quad_ctx, "ui/player_2.png")?),
+            Player::CPU if is_player_turn => Some(graphics::Image::new(ctx, quad_ctx, "ui/CPU_u.png")?),
+            Player::CPU  => Some(graphics::Image::new(ctx, quad_ctx, "ui/CPU.png")?),
+            Player::Player3 | Player::Player4 => None,
         };
         let image_points = convert_points_to_list_of_images(ctx, quad_ctx, points)?;
         let orientation = get_scene_orientation(quad_ctx);
 
         Ok(Self {
             player,
+            seat,
+            seat_count,
+            is_player_turn,
             image_title,
             image_points,
             orientation,
@@ -58,16 +68,14 @@ impl PlayerDataRenderer {
         scene_scale: (f32, f32),
         translation: (f32, f32),
     ) -> Result<(), ggez::GameError> {        
-        graphics::draw(
+        self.draw_title(
             ctx,
             quad_ctx,
-            &self.image_title,
-            graphics::DrawParam::new()
-                .dest(Point2::new(
-                    PLAYER_DATA_PANEL_TITLE_X_HORZ * scene_scale.0 + translation.0,
-                    PLAYER_DATA_PANEL_TITLE_Y_HORZ * scene_scale.1 + translation.1,
-                ))
-                .scale(Vector2::new(scene_scale.0, scene_scale.1)),
+            scene_scale,
+            Point2::new(
+                PLAYER_DATA_PANEL_TITLE_X_HORZ * scene_scale.0 + translation.0,
+                PLAYER_DATA_PANEL_TITLE_Y_HORZ * scene_scale.1 + translation.1,
+            ),
         )?;
         for (idx, image) in self.image_points.iter().enumerate() {
             graphics::draw(
@@ -98,16 +106,14 @@ impl PlayerDataRenderer {
         scene_scale: (f32, f32),
         translation: (f32, f32),        
     ) -> Result<(), ggez::GameError> {
-        graphics::draw(
+        self.draw_title(
             ctx,
             quad_ctx,
-            &self.image_title,
-            graphics::DrawParam::new()
-                .dest(Point2::new(
-                    PLAYER_DATA_PANEL_TITLE_X_VERT * scene_scale.0 + translation.0,
-                    PLAYER_DATA_PANEL_TITLE_Y_VERT * scene_scale.1 + translation.1,
-                ))
-                .scale(Vector2::new(scene_scale.0, scene_scale.1)),
+            scene_scale,
+            Point2::new(
+                PLAYER_DATA_PANEL_TITLE_X_VERT * scene_scale.0 + translation.0,
+                PLAYER_DATA_PANEL_TITLE_Y_VERT * scene_scale.1 + translation.1,
+            ),
         )?;
         for (idx, image) in self.image_points.iter().enumerate() {
             graphics::draw(
@@ -131,6 +137,43 @@ impl PlayerDataRenderer {
         Ok(())
     }
 
+    /// Players without a title image are captioned with text, marked when it is their turn.
+    fn draw_title(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::Context,
+        scene_scale: (f32, f32),
+        dest: Point2<f32>,
+    ) -> GameResult {
+        match &self.image_title {
+            Some(image) => graphics::draw(
+                ctx,
+                quad_ctx,
+                image,
+                graphics::DrawParam::new()
+                    .dest(dest)
+                    .scale(Vector2::new(scene_scale.0, scene_scale.1)),
+            ),
+            None => {
+                let caption = if self.is_player_turn {
+                    format!("> {} <", self.player.caption())
+                } else {
+                    self.player.caption().to_string()
+                };
+                draw_text(
+                    ctx,
+                    quad_ctx,
+                    dest.x + PLAYER_DATA_PANEL_TITLE_WIDTH * scene_scale.0 / 2.0,
+                    dest.y + PLAYER_DATA_PANEL_TITLE_HEIGHT * scene_scale.1 / 2.0,
+                    &caption,
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Seats alternate between both sides of the board. With more than two seats every side
+    /// holds a column of panels centred on the side.
     fn get_translation(
         &mut self,
         quad_ctx: &mut miniquad::Context,
@@ -139,15 +182,18 @@ impl PlayerDataRenderer {
         let mut translation = get_scene_translation(quad_ctx, scene_scale);
         let (w, h) = quad_ctx.display().screen_size();
 
-        match self.player {
-            Player::Player1 => match self.orientation {
-                Orientation::Horizontal => translation.0 = 0.0,
-                Orientation::Vertical => translation.1 = 0.0,
-            },
-            Player::CPU | Player::Player2 => match self.orientation {
-                Orientation::Horizontal => translation.0 = w - translation.0,
-                Orientation::Vertical => translation.1 = h - translation.1,
-            },
+        let rows = self.seat_count.div_ceil(2);
+        let row_offset = (self.seat / 2) as f32 - (rows - 1) as f32 / 2.0;
+
+        match self.orientation {
+            Orientation::Horizontal => {
+                translation.0 = if self.seat.is_multiple_of(2) { 0.0 } else { w - translation.0 };
+                translation.1 += row_offset * PLAYER_DATA_PANEL_ROW_SPACING_HORZ * scene_scale.1;
+            }
+            Orientation::Vertical => {
+                translation.1 = if self.seat.is_multiple_of(2) { 0.0 } else { h - translation.1 };
+                translation.0 += row_offset * PLAYER_DATA_PANEL_ROW_SPACING_VERT * scene_scale.0;
+            }
         }
         translation
     }
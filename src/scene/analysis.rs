@@ -0,0 +1,230 @@
+use crate::ai::prelude::{GameAnalyser, MoveAnalysis};
+use crate::game::game_match::Match;
+use crate::game::notation::wall_to_notation;
+use crate::game::settings::GameSettings;
+use crate::game::Board;
+use crate::rendering::prelude::{BoardRenderer, ButtonRenderer, TextButtonRenderer};
+use crate::rendering::Renderer;
+use crate::scene::prelude::*;
+
+/// Steps through a finished game. The moves are analysed by the engine one per frame and the
+/// move it prefers is highlighted on the board.
+#[derive(Debug)]
+pub struct AnalysisScene {
+    board_renderer: BoardRenderer,
+    cancel_button_renderer: ButtonRenderer,
+    previous_button_renderer: TextButtonRenderer,
+    next_button_renderer: TextButtonRenderer,
+    next_mistake_button_renderer: TextButtonRenderer,
+    cancel_bounding_box: Rect,
+    previous_bounding_box: Rect,
+    next_bounding_box: Rect,
+    next_mistake_bounding_box: Rect,
+    final_board: Board,
+    analyser: GameAnalyser,
+    shown_moves: usize,
+    settings: GameSettings,
+}
+
+impl AnalysisScene {
+    pub fn new(
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        game_match: &Match,
+        settings: GameSettings,
+    ) -> GameResult<Self> {
+        let final_board = game_match.board().clone();
+        let analyser = GameAnalyser::new(&final_board);
+        let mut start = final_board.clone();
+        while start.undo().is_some() {}
+
+        let board_renderer =
+            BoardRenderer::new(ctx, quad_ctx, game_match.current_player(), start, false)?;
+        let cancel_button_renderer = ButtonRenderer::new(
+            ctx,
+            quad_ctx,
+            "ui/cancel.png",
+            PLAYING_CANCEL_BUTTON_MARGIN_X,
+            PLAYING_CANCEL_BUTTON_MARGIN_Y,
+        )?;
+
+        Ok(Self {
+            board_renderer,
+            cancel_button_renderer,
+            previous_button_renderer: TextButtonRenderer::new(
+                "Previous",
+                ANALYSIS_PREVIOUS_BUTTON_X,
+                ANALYSIS_PREVIOUS_BUTTON_Y,
+            ),
+            next_button_renderer: TextButtonRenderer::new(
+                "Next",
+                ANALYSIS_NEXT_BUTTON_X,
+                ANALYSIS_NEXT_BUTTON_Y,
+            ),
+            next_mistake_button_renderer: TextButtonRenderer::new(
+                "Next mistake",
+                ANALYSIS_NEXT_MISTAKE_BUTTON_X,
+                ANALYSIS_NEXT_MISTAKE_BUTTON_Y,
+            ),
+            cancel_bounding_box: Rect::default(),
+            previous_bounding_box: Rect::default(),
+            next_bounding_box: Rect::default(),
+            next_mistake_bounding_box: Rect::default(),
+            final_board,
+            analyser,
+            shown_moves: 0,
+            settings,
+        })
+    }
+
+    fn move_count(&self) -> usize {
+        self.final_board.get_history().len()
+    }
+
+    /// Analysis of the last shown move, once the engine got to it.
+    fn shown_analysis(&self) -> Option<&MoveAnalysis> {
+        self.shown_moves
+            .checked_sub(1)
+            .and_then(|idx| self.analyser.analysis().get(idx))
+    }
+
+    /// Shows the board after the given number of moves.
+    fn show_moves(&mut self, shown_moves: usize) {
+        self.shown_moves = shown_moves.min(self.move_count());
+        let mut board = self.final_board.clone();
+        for _ in self.shown_moves..self.move_count() {
+            board.undo();
+        }
+        if let Some(last_move) = board.get_history().last() {
+            self.board_renderer.set_player(last_move.player);
+        }
+        self.board_renderer.set_board(&board);
+        self.update_hint();
+    }
+
+    /// Highlights the move preferred by the engine when it is not the move played.
+    fn update_hint(&mut self) {
+        let hint = self
+            .shown_analysis()
+            .filter(|analysis| analysis.best_move != analysis.played.wall)
+            .map(|analysis| analysis.best_move);
+        self.board_renderer.set_hint(hint);
+    }
+
+    fn show_next_mistake(&mut self) {
+        if let Some(idx) = self
+            .analyser
+            .analysis()
+            .iter()
+            .enumerate()
+            .skip(self.shown_moves)
+            .find(|(_, analysis)| self.analyser.is_mistake(analysis))
+            .map(|(idx, _)| idx)
+        {
+            self.show_moves(idx + 1);
+        }
+    }
+
+    fn caption(&self) -> String {
+        let progress = if self.analyser.is_finished() {
+            String::new()
+        } else {
+            format!(
+                " (analysed {}/{})",
+                self.analyser.analysis().len(),
+                self.move_count()
+            )
+        };
+        if self.shown_moves == 0 {
+            return format!("Start of the game{progress}");
+        }
+        let Some(analysis) = self.shown_analysis() else {
+            return format!("Move {}/{}{progress}", self.shown_moves, self.move_count());
+        };
+
+        let (row, col) = analysis.played.wall;
+        let mut caption = format!(
+            "Move {}/{}: {} {}",
+            self.shown_moves,
+            self.move_count(),
+            analysis.played.player.caption(),
+            wall_to_notation(row, col)
+        );
+        if self.analyser.is_mistake(analysis) {
+            let (row, col) = analysis.best_move;
+            caption += &format!(
+                " is a mistake (-{}), {} is better",
+                analysis.loss(),
+                wall_to_notation(row, col)
+            );
+        }
+        caption + &progress
+    }
+}
+
+impl Scene for AnalysisScene {
+    type State = AnalysisState;
+
+    fn update(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        _quad_ctx: &mut event::GraphicsContext,
+    ) -> Result<Option<Transition>, ggez::GameError> {
+        if !self.analyser.is_finished() {
+            self.analyser.analyse_next();
+            self.update_hint();
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext) -> GameResult {
+        self.board_renderer.draw(ctx, quad_ctx)?;
+        self.cancel_button_renderer.draw(ctx, quad_ctx)?;
+        self.previous_button_renderer.draw(ctx, quad_ctx)?;
+        self.next_button_renderer.draw(ctx, quad_ctx)?;
+        self.next_mistake_button_renderer.draw(ctx, quad_ctx)?;
+
+        let scene_scale = get_scene_scale(quad_ctx);
+        let (w, _) = quad_ctx.screen_size();
+        draw_text(
+            ctx,
+            quad_ctx,
+            w / 2.0,
+            ANALYSIS_CAPTION_Y * scene_scale.1,
+            &self.caption(),
+        )?;
+
+        self.cancel_bounding_box = self.cancel_button_renderer.get_bouding_rect();
+        self.previous_bounding_box = self.previous_button_renderer.get_bouding_rect();
+        self.next_bounding_box = self.next_button_renderer.get_bouding_rect();
+        self.next_mistake_bounding_box = self.next_mistake_button_renderer.get_bouding_rect();
+
+        Ok(())
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        _button: ggez::event::MouseButton,
+        x: f32,
+        y: f32,
+    ) -> Option<Transition> {
+        let point = Point2::new(x, y);
+
+        if self.cancel_bounding_box.contains(point) {
+            let game = MainMenuScene::from(ctx, quad_ctx, self.settings.clone());
+            return Some(Transition::ToMainMenu(Box::new(
+                game.expect("scene has been created"),
+            )));
+        }
+        if self.previous_bounding_box.contains(point) {
+            self.show_moves(self.shown_moves.saturating_sub(1));
+        } else if self.next_bounding_box.contains(point) {
+            self.show_moves(self.shown_moves + 1);
+        } else if self.next_mistake_bounding_box.contains(point) {
+            self.show_next_mistake();
+        }
+        None
+    }
+}
@@ -1,21 +1,27 @@
-use crate::game::Difficulty;
+use crate::game::adaptive::LevelChange;
+use crate::game::game_match::Match;
+use crate::game::settings::GameSettings;
 use crate::game::GameStatistics;
+use crate::game::Player;
 use crate::scene::prelude::*;
 
+#[derive(Debug)]
+struct PointsRow {
+    player: Player,
+    image_title: Option<graphics::Image>,
+    image_points: Vec<graphics::Image>,
+}
+
 #[derive(Debug)]
 pub struct GameOverScene {
+    game_match: Match,
     statistics: GameStatistics,
     retry_button_bounding_box: Rect,
-    is_one_player_game: bool,
-    difficulty: Difficulty,
-    width: usize,
-    height: usize,
+    analyse_button_bounding_box: Rect,
+    settings: GameSettings,
+    level_change: Option<LevelChange>,
     image_background: graphics::Image,
-    image_player_1_points: graphics::Image,
-    image_player_2_points: graphics::Image,
-    image_cpu_points: graphics::Image,
-    image_points_1: Vec<graphics::Image>,
-    image_points_2: Vec<graphics::Image>,
+    points_rows: Vec<PointsRow>,
     image_player_1_wins: graphics::Image,
     image_player_2_wins: graphics::Image,
     image_cpu_wins: graphics::Image,
@@ -24,26 +30,35 @@ pub struct GameOverScene {
 }
 
 impl GameOverScene {
+    /// Scene of the finished game. `level_change` is the change of the strength of the
+    /// adaptive CPU, when it played.
     pub fn new(
         ctx: &mut Context,
         quad_ctx: &mut miniquad::GraphicsContext,
-        statistics: GameStatistics,
+        game_match: &Match,
         game_mode: &GameMode,
-        difficulty: Difficulty,
-        width: usize,
-        height: usize,
+        settings: GameSettings,
+        level_change: Option<LevelChange>,
     ) -> GameResult<Self> {
-        let (points_1, points_2, is_one_player_game) = match game_mode {
-            GameMode::OnePlayer(_) => (statistics.player1_points, statistics.cpu_points, true),
-            GameMode::TwoPlayer => (statistics.player1_points, statistics.player2_points, false),
-        };
+        let statistics = game_match.get_statistics();
+        let mut points_rows = vec![];
+        for player in game_mode.turn_order() {
+            let image_title = match player {
+                Player::Player1 => Some(graphics::Image::new(ctx, quad_ctx, "ui/player_1_points.png")?),
+                Player::Player2 => Some(graphics::Image::new(ctx, quad_ctx, "ui/player_2_points.png")?),
+                Player::CPU => Some(graphics::Image::new(ctx, quad_ctx, "ui/CPU_points.png")?),
+                Player::Player3 | Player::Player4 => None,
+            };
+            let image_points =
+                convert_points_to_list_of_images(ctx, quad_ctx, statistics.get_points(player))?;
+            points_rows.push(PointsRow {
+                player,
+                image_title,
+                image_points,
+            });
+        }
 
         let image_background = graphics::Image::new(ctx, quad_ctx, "ui/game_over.png")?;
-        let image_player_1_points = graphics::Image::new(ctx, quad_ctx, "ui/player_1_points.png")?;
-        let image_player_2_points = graphics::Image::new(ctx, quad_ctx, "ui/player_2_points.png")?;
-        let image_cpu_points = graphics::Image::new(ctx, quad_ctx, "ui/CPU_points.png")?;
-        let image_points_1 = convert_points_to_list_of_images(ctx, quad_ctx, points_1)?;
-        let image_points_2 = convert_points_to_list_of_images(ctx, quad_ctx, points_2)?;
         let image_player_1_wins = graphics::Image::new(ctx, quad_ctx, "ui/player_1_wins.png")?;
         let image_player_2_wins = graphics::Image::new(ctx, quad_ctx, "ui/player_2_wins.png")?;
         let image_cpu_wins = graphics::Image::new(ctx, quad_ctx, "ui/CPU_wins.png")?;
@@ -51,18 +66,14 @@ impl GameOverScene {
         let image_start_game = graphics::Image::new(ctx, quad_ctx, "ui/start_game.png")?;
 
         Ok(Self {
+            game_match: game_match.clone(),
             statistics,
             retry_button_bounding_box: Rect::default(),
-            is_one_player_game,
-            difficulty,
-            width,
-            height,
+            analyse_button_bounding_box: Rect::default(),
+            settings,
+            level_change,
             image_background,
-            image_player_1_points,
-            image_player_2_points,
-            image_cpu_points,
-            image_points_1,
-            image_points_2,
+            points_rows,
             image_player_1_wins,
             image_player_2_wins,
             image_cpu_wins,
@@ -96,148 +107,161 @@ impl GameOverScene {
         scene_scale: (f32, f32),
         translation: (f32, f32),
     ) -> Result<(), ggez::GameError> {
-        if let Some(player) = self.statistics.winner {
-            match player {
-                game::Player::Player1 => graphics::draw(
+        let (image, x, y) = match self.statistics.winner {
+            Some(Player::Player1) => (
+                &self.image_player_1_wins,
+                GAME_OVER_PLAYER_1_WINS_X,
+                GAME_OVER_PLAYER_1_WINS_Y,
+            ),
+            Some(Player::Player2) => (
+                &self.image_player_2_wins,
+                GAME_OVER_PLAYER_2_WINS_X,
+                GAME_OVER_PLAYER_2_WINS_Y,
+            ),
+            Some(Player::CPU) => (&self.image_cpu_wins, GAME_OVER_CPU_WINS_X, GAME_OVER_CPU_WINS_Y),
+            Some(player) => {
+                draw_text(
                     ctx,
                     quad_ctx,
-                    &self.image_player_1_wins,
-                    graphics::DrawParam::new()
-                        .dest(Point2::new(
-                            GAME_OVER_PLAYER_1_WINS_X * scene_scale.0 + translation.0,
-                            GAME_OVER_PLAYER_1_WINS_Y * scene_scale.1 + translation.1,
-                        ))
-                        .scale(Vector2::new(scene_scale.0, scene_scale.1)),
-                )?,
-                game::Player::Player2 => graphics::draw(
+                    GAME_OVER_WINS_TEXT_X * scene_scale.0 + translation.0,
+                    GAME_OVER_WINS_TEXT_Y * scene_scale.1 + translation.1,
+                    &format!("{} wins", player.caption()),
+                )?;
+                return Ok(());
+            }
+            None => (&self.image_tie, GAME_OVER_TIE_X, GAME_OVER_TIE_Y),
+        };
+        graphics::draw(
+            ctx,
+            quad_ctx,
+            image,
+            graphics::DrawParam::new()
+                .dest(Point2::new(
+                    x * scene_scale.0 + translation.0,
+                    y * scene_scale.1 + translation.1,
+                ))
+                .scale(Vector2::new(scene_scale.0, scene_scale.1)),
+        )?;
+        Ok(())
+    }
+
+    /// Draws the points of every seat. Rows of games with more than two seats are shrunk to fit
+    /// the space of the two rows of the background.
+    fn draw_players_points(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::Context,
+        scene_scale: (f32, f32),
+        translation: (f32, f32),
+    ) -> Result<(), ggez::GameError> {
+        let row_scale = (2.0 / self.points_rows.len() as f32).min(1.0);
+        let row_spacing = (GAME_OVER_PLAYER_2_POINTS_Y - GAME_OVER_PLAYER_1_POINTS_Y) * row_scale;
+
+        for (row_idx, row) in self.points_rows.iter().enumerate() {
+            let title_y = GAME_OVER_PLAYER_1_POINTS_Y + row_idx as f32 * row_spacing;
+            let points_y = GAME_OVER_POINTS_1_Y + row_idx as f32 * row_spacing;
+
+            match &row.image_title {
+                Some(image) => graphics::draw(
                     ctx,
                     quad_ctx,
-                    &self.image_player_2_wins,
+                    image,
                     graphics::DrawParam::new()
                         .dest(Point2::new(
-                            GAME_OVER_PLAYER_2_WINS_X * scene_scale.0 + translation.0,
-                            GAME_OVER_PLAYER_2_WINS_Y * scene_scale.1 + translation.1,
+                            GAME_OVER_PLAYER_1_POINTS_X * scene_scale.0 + translation.0,
+                            title_y * scene_scale.1 + translation.1,
                         ))
-                        .scale(Vector2::new(scene_scale.0, scene_scale.1)),
+                        .scale(Vector2::new(
+                            row_scale * scene_scale.0,
+                            row_scale * scene_scale.1,
+                        )),
                 )?,
-                game::Player::CPU => graphics::draw(
+                None => {
+                    draw_text(
+                        ctx,
+                        quad_ctx,
+                        (GAME_OVER_PLAYER_1_POINTS_X + GAME_OVER_POINTS_TITLE_WIDTH * row_scale / 2.0)
+                            * scene_scale.0
+                            + translation.0,
+                        (title_y + GAME_OVER_POINTS_TITLE_HEIGHT * row_scale / 2.0) * scene_scale.1
+                            + translation.1,
+                        &format!("{} points", row.player.caption()),
+                    )?;
+                }
+            }
+
+            for (idx, image) in row.image_points.iter().enumerate() {
+                graphics::draw(
                     ctx,
                     quad_ctx,
-                    &self.image_cpu_wins,
+                    image,
                     graphics::DrawParam::new()
                         .dest(Point2::new(
-                            GAME_OVER_CPU_WINS_X * scene_scale.0 + translation.0,
-                            GAME_OVER_CPU_WINS_Y * scene_scale.1 + translation.1,
+                            (GAME_OVER_POINTS_1_X
+                                + 2.0 * idx as f32 * MAIN_MENU_DIGIT_WIDTH * row_scale / 3.0)
+                                * scene_scale.0
+                                + translation.0,
+                            points_y * scene_scale.1 + translation.1,
                         ))
-                        .scale(Vector2::new(scene_scale.0, scene_scale.1)),
-                )?,
+                        .scale(Vector2::new(
+                            MAIN_MENU_DIGIT_WIDTH * row_scale / image.width() as f32 * scene_scale.0,
+                            MAIN_MENU_DIGIT_HEIGHT * row_scale / image.height() as f32
+                                * scene_scale.1,
+                        )),
+                )?;
             }
-        } else {
-            graphics::draw(
-                ctx,
-                quad_ctx,
-                &self.image_tie,
-                graphics::DrawParam::new()
-                    .dest(Point2::new(
-                        GAME_OVER_TIE_X * scene_scale.0 + translation.0,
-                        GAME_OVER_TIE_Y * scene_scale.1 + translation.1,
-                    ))
-                    .scale(Vector2::new(scene_scale.0, scene_scale.1)),
-            )?
         }
         Ok(())
     }
 
-    fn draw_first_player_points(
+    /// Tells how many hints the seats asked for, if any.
+    fn draw_hints(
         &mut self,
         ctx: &mut Context,
         quad_ctx: &mut miniquad::Context,
         scene_scale: (f32, f32),
         translation: (f32, f32),
     ) -> Result<(), ggez::GameError> {
-        graphics::draw(
+        let hints = self
+            .points_rows
+            .iter()
+            .filter(|row| self.statistics.get_hints(row.player) > 0)
+            .map(|row| format!("{} {}", row.player.caption(), self.statistics.get_hints(row.player)))
+            .collect::<Vec<_>>();
+        if hints.is_empty() {
+            return Ok(());
+        }
+        draw_text(
             ctx,
             quad_ctx,
-            &self.image_player_1_points,
-            graphics::DrawParam::new()
-                .dest(Point2::new(
-                    GAME_OVER_PLAYER_1_POINTS_X * scene_scale.0 + translation.0,
-                    GAME_OVER_PLAYER_1_POINTS_Y * scene_scale.1 + translation.1,
-                ))
-                .scale(Vector2::new(scene_scale.0, scene_scale.1)),
+            GAME_OVER_HINTS_X * scene_scale.0 + translation.0,
+            GAME_OVER_HINTS_Y * scene_scale.1 + translation.1,
+            &format!("Hints used: {}", hints.join(", ")),
         )?;
-        for (idx, image) in self.image_points_1.iter().enumerate() {
-            graphics::draw(
-                ctx,
-                quad_ctx,
-                image,
-                graphics::DrawParam::new()
-                    .dest(Point2::new(
-                        (GAME_OVER_POINTS_1_X + 2.0 * idx as f32 * MAIN_MENU_DIGIT_WIDTH / 3.0)
-                            * scene_scale.0
-                            + translation.0,
-                        GAME_OVER_POINTS_1_Y * scene_scale.1 + translation.1,
-                    ))
-                    .scale(Vector2::new(
-                        MAIN_MENU_DIGIT_WIDTH / image.width() as f32 * scene_scale.0,
-                        MAIN_MENU_DIGIT_HEIGHT / image.height() as f32 * scene_scale.1,
-                    )),
-            )?;
-        }
         Ok(())
     }
 
-    fn draw_second_player_points(
+    /// Tells how the strength of the adaptive CPU changed after the game.
+    fn draw_level_change(
         &mut self,
         ctx: &mut Context,
         quad_ctx: &mut miniquad::Context,
         scene_scale: (f32, f32),
         translation: (f32, f32),
     ) -> Result<(), ggez::GameError> {
-        if self.is_one_player_game {
-            graphics::draw(
-                ctx,
-                quad_ctx,
-                &self.image_cpu_points,
-                graphics::DrawParam::new()
-                    .dest(Point2::new(
-                        GAME_OVER_CPU_POINTS_X * scene_scale.0 + translation.0,
-                        GAME_OVER_CPU_POINTS_Y * scene_scale.1 + translation.1,
-                    ))
-                    .scale(Vector2::new(scene_scale.0, scene_scale.1)),
-            )?;
-        } else {
-            graphics::draw(
-                ctx,
-                quad_ctx,
-                &self.image_player_2_points,
-                graphics::DrawParam::new()
-                    .dest(Point2::new(
-                        GAME_OVER_PLAYER_2_POINTS_X * scene_scale.0 + translation.0,
-                        GAME_OVER_PLAYER_2_POINTS_Y * scene_scale.1 + translation.1,
-                    ))
-                    .scale(Vector2::new(scene_scale.0, scene_scale.1)),
-            )?;
-        }
-
-        for (idx, image) in self.image_points_2.iter().enumerate() {
-            graphics::draw(
-                ctx,
-                quad_ctx,
-                image,
-                graphics::DrawParam::new()
-                    .dest(Point2::new(
-                        (GAME_OVER_POINTS_2_X + 2.0 * idx as f32 * MAIN_MENU_DIGIT_WIDTH / 3.0)
-                            * scene_scale.0
-                            + translation.0,
-                        GAME_OVER_POINTS_2_Y * scene_scale.1 + translation.1,
-                    ))
-                    .scale(Vector2::new(
-                        MAIN_MENU_DIGIT_WIDTH / image.width() as f32 * scene_scale.0,
-                        MAIN_MENU_DIGIT_HEIGHT / image.height() as f32 * scene_scale.1,
-                    )),
-            )?;
-        }
+        let Some(level_change) = self.level_change else {
+            return Ok(());
+        };
+        draw_text(
+            ctx,
+            quad_ctx,
+            GAME_OVER_ADAPTIVE_LEVEL_X * scene_scale.0 + translation.0,
+            GAME_OVER_ADAPTIVE_LEVEL_Y * scene_scale.1 + translation.1,
+            &format!(
+                "CPU level: {} -> {}",
+                level_change.previous, level_change.current
+            ),
+        )?;
         Ok(())
     }
 
@@ -293,9 +317,18 @@ impl Scene for GameOverScene {
 
         self.draw_background(ctx, quad_ctx, scene_scale, translation)?;
         self.draw_game_result(ctx, quad_ctx, scene_scale, translation)?;
-        self.draw_first_player_points(ctx, quad_ctx, scene_scale, translation)?;
-        self.draw_second_player_points(ctx, quad_ctx, scene_scale, translation)?;
+        self.draw_players_points(ctx, quad_ctx, scene_scale, translation)?;
+        self.draw_hints(ctx, quad_ctx, scene_scale, translation)?;
+        self.draw_level_change(ctx, quad_ctx, scene_scale, translation)?;
         self.draw_retry_button(ctx, quad_ctx, scene_scale, translation)?;
+        self.analyse_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            GAME_OVER_ANALYSE_BUTTON_X * scene_scale.0 + translation.0,
+            GAME_OVER_ANALYSE_BUTTON_Y * scene_scale.1 + translation.1,
+            "Analyse the game",
+            false,
+        )?;
 
         graphics::present(ctx, quad_ctx)?;
         Ok(())
@@ -310,15 +343,14 @@ impl Scene for GameOverScene {
         y: f32,
     ) -> Option<Transition> {
         let point = Point2::new(x, y);
+        if self.analyse_button_bounding_box.contains(point) {
+            let game = AnalysisScene::new(ctx, quad_ctx, &self.game_match, self.settings.clone());
+            return Some(Transition::ToAnalysis(Box::new(
+                game.expect("scene has been created"),
+            )));
+        }
         self.retry_button_bounding_box.contains(point).then(|| {
-            let game = MainMenuScene::from(
-                ctx,
-                quad_ctx,
-                self.width,
-                self.height,
-                self.is_one_player_game,
-                self.difficulty,
-            );
+            let game = MainMenuScene::from(ctx, quad_ctx, self.settings.clone());
             Transition::ToMainMenu(Box::new(game.expect("scene has been created")))
         })
     }
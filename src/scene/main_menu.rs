@@ -1,29 +1,47 @@
+use std::str::FromStr;
+
 use crate::ai::prelude::*;
+use crate::file;
+use crate::game::adaptive::AdaptiveHistory;
+use crate::game::game_match::Match;
+use crate::game::record::{is_game_record, GameRecord};
+use crate::game::saved_game::SavedGame;
+use crate::game::settings::GameSettings;
 use crate::game::Difficulty;
 use crate::rendering::ui::{RadioButton, SceneTransformation};
 use crate::scene::prelude::*;
 
 #[derive(Debug)]
 pub struct MainMenuScene {
-    width: usize,
-    height: usize,
     one_player_bounding_box: Rect,
     two_players_bounding_box: Rect,
     easy_difficulty_bounding_box: Rect,
     medium_difficulty_bounding_box: Rect,
     hard_difficulty_bounding_box: Rect,
+    expert_difficulty_bounding_box: Rect,
     width_decr_button_bounding_box: Rect,
     width_incr_button_bounding_box: Rect,
     height_decr_button_bounding_box: Rect,
     height_incr_button_bounding_box: Rect,
     start_button_bounding_box: Rect,
-    one_player_game: bool,
-    difficulty: game::Difficulty,
+    load_button_bounding_box: Rect,
+    seat_count_decr_button_bounding_box: Rect,
+    seat_count_incr_button_bounding_box: Rect,
+    strength_decr_button_bounding_box: Rect,
+    strength_incr_button_bounding_box: Rect,
+    adaptive_bounding_box: Rect,
+    hints_decr_button_bounding_box: Rect,
+    hints_incr_button_bounding_box: Rect,
+    seat_bounding_boxes: Vec<Rect>,
+    first_seat_bounding_box: Rect,
+    shape_bounding_box: Rect,
+    seed_bounding_box: Rect,
+    settings: GameSettings,
     image_background: graphics::Image,
     image_plus: graphics::Image,
     image_minus: graphics::Image,
-    image_width: graphics::Image,
-    image_height: graphics::Image,
+    images_width: Vec<graphics::Image>,
+    images_height: Vec<graphics::Image>,
     image_one_player: graphics::Image,
     image_two_players: graphics::Image,
     image_disabled_checked_radio: graphics::Image,
@@ -35,85 +53,32 @@ pub struct MainMenuScene {
     image_checked_radio: graphics::Image,
     spritebatch_plus: graphics::spritebatch::SpriteBatch,
     spritebatch_minus: graphics::spritebatch::SpriteBatch,
-    spritebatch_width: graphics::spritebatch::SpriteBatch,
-    spritebatch_height: graphics::spritebatch::SpriteBatch,
 }
 
 impl MainMenuScene {
-    pub fn new(ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext) -> GameResult<Self> {
-        let image_background = graphics::Image::new(ctx, quad_ctx, "ui/settings.png")?;
-        let image_plus = graphics::Image::new(ctx, quad_ctx, "ui/plus.png")?;
-        let image_minus = graphics::Image::new(ctx, quad_ctx, "ui/minus.png")?;
-        let image_width =
-            graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", DEFAULT_BOARD_WIDTH))?;
-        let image_height =
-            graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", DEFAULT_BOARD_HEIGHT))?;
-        let image_one_player = graphics::Image::new(ctx, quad_ctx, "ui/1_player.png")?;
-        let image_two_players = graphics::Image::new(ctx, quad_ctx, "ui/2_players.png")?;
-        let image_disabled_checked_radio =
-            graphics::Image::new(ctx, quad_ctx, "ui/disabled_checked_radio.png")?;
-        let image_disabled_unchecked_radio =
-            graphics::Image::new(ctx, quad_ctx, "ui/disabled_radio.png")?;
-        let image_easy = graphics::Image::new(ctx, quad_ctx, "ui/easy.png")?;
-        let image_medium = graphics::Image::new(ctx, quad_ctx, "ui/medium.png")?;
-        let image_hard = graphics::Image::new(ctx, quad_ctx, "ui/hard.png")?;
-        let image_unchecked_radio = graphics::Image::new(ctx, quad_ctx, "ui/unchecked_radio.png")?;
-        let image_checked_radio = graphics::Image::new(ctx, quad_ctx, "ui/checked_radio.png")?;
-        let batch_plus = graphics::spritebatch::SpriteBatch::new(image_plus.clone());
-        let batch_minus = graphics::spritebatch::SpriteBatch::new(image_minus.clone());
-        let batch_width = graphics::spritebatch::SpriteBatch::new(image_width.clone());
-        let batch_height = graphics::spritebatch::SpriteBatch::new(image_height.clone());
-
-        let s = MainMenuScene {
-            width: DEFAULT_BOARD_WIDTH,
-            height: DEFAULT_BOARD_HEIGHT,
-            one_player_bounding_box: Rect::default(),
-            two_players_bounding_box: Rect::default(),
-            easy_difficulty_bounding_box: Rect::default(),
-            medium_difficulty_bounding_box: Rect::default(),
-            hard_difficulty_bounding_box: Rect::default(),
-            width_decr_button_bounding_box: Rect::default(),
-            width_incr_button_bounding_box: Rect::default(),
-            height_decr_button_bounding_box: Rect::default(),
-            height_incr_button_bounding_box: Rect::default(),
-            start_button_bounding_box: Rect::default(),
-            one_player_game: true,
-            difficulty: Difficulty::Medium,
-            image_background,
-            image_plus,
-            image_minus,
-            image_width,
-            image_height,
-            image_one_player,
-            image_two_players,
-            image_disabled_checked_radio,
-            image_disabled_unchecked_radio,
-            image_easy,
-            image_medium,
-            image_hard,
-            image_unchecked_radio,
-            image_checked_radio,
-            spritebatch_plus: batch_plus,
-            spritebatch_minus: batch_minus,
-            spritebatch_width: batch_width,
-            spritebatch_height: batch_height,
+    /// Menu of the first game, the CPU players use the given seed.
+    pub fn new(
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        seed: u64,
+    ) -> GameResult<Self> {
+        let settings = GameSettings {
+            seed,
+            ..GameSettings::default()
         };
-        Ok(s)
+        Self::from(ctx, quad_ctx, settings)
     }
 
     pub fn from(
         ctx: &mut Context,
         quad_ctx: &mut miniquad::GraphicsContext,
-        width: usize,
-        height: usize,
-        one_player_game: bool,
-        difficulty: Difficulty,
+        settings: GameSettings,
     ) -> GameResult<Self> {
         let image_background = graphics::Image::new(ctx, quad_ctx, "ui/settings.png")?;
         let image_plus = graphics::Image::new(ctx, quad_ctx, "ui/plus.png")?;
         let image_minus = graphics::Image::new(ctx, quad_ctx, "ui/minus.png")?;
-        let image_width = graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", width))?;
-        let image_height = graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", height))?;
+        let images_width = convert_points_to_list_of_images(ctx, quad_ctx, settings.width)?;
+        let images_height = convert_points_to_list_of_images(ctx, quad_ctx, settings.height)?;
         let image_one_player = graphics::Image::new(ctx, quad_ctx, "ui/1_player.png")?;
         let image_two_players = graphics::Image::new(ctx, quad_ctx, "ui/2_players.png")?;
         let image_disabled_checked_radio =
@@ -127,29 +92,37 @@ impl MainMenuScene {
         let image_checked_radio = graphics::Image::new(ctx, quad_ctx, "ui/checked_radio.png")?;
         let batch_plus = graphics::spritebatch::SpriteBatch::new(image_plus.clone());
         let batch_minus = graphics::spritebatch::SpriteBatch::new(image_minus.clone());
-        let batch_width = graphics::spritebatch::SpriteBatch::new(image_width.clone());
-        let batch_height = graphics::spritebatch::SpriteBatch::new(image_height.clone());
 
         let s = Self {
-            width,
-            height,
             one_player_bounding_box: Rect::default(),
             two_players_bounding_box: Rect::default(),
             easy_difficulty_bounding_box: Rect::default(),
             medium_difficulty_bounding_box: Rect::default(),
             hard_difficulty_bounding_box: Rect::default(),
+            expert_difficulty_bounding_box: Rect::default(),
             width_decr_button_bounding_box: Rect::default(),
             width_incr_button_bounding_box: Rect::default(),
             height_decr_button_bounding_box: Rect::default(),
             height_incr_button_bounding_box: Rect::default(),
             start_button_bounding_box: Rect::default(),
-            one_player_game,
-            difficulty,
+            load_button_bounding_box: Rect::default(),
+            seat_count_decr_button_bounding_box: Rect::default(),
+            seat_count_incr_button_bounding_box: Rect::default(),
+            strength_decr_button_bounding_box: Rect::default(),
+            strength_incr_button_bounding_box: Rect::default(),
+            adaptive_bounding_box: Rect::default(),
+            hints_decr_button_bounding_box: Rect::default(),
+            hints_incr_button_bounding_box: Rect::default(),
+            seat_bounding_boxes: vec![],
+            first_seat_bounding_box: Rect::default(),
+            shape_bounding_box: Rect::default(),
+            seed_bounding_box: Rect::default(),
+            settings,
             image_background,
             image_plus,
             image_minus,
-            image_width,
-            image_height,
+            images_width,
+            images_height,
             image_one_player,
             image_two_players,
             image_disabled_checked_radio,
@@ -161,8 +134,6 @@ impl MainMenuScene {
             image_checked_radio,
             spritebatch_plus: batch_plus,
             spritebatch_minus: batch_minus,
-            spritebatch_width: batch_width,
-            spritebatch_height: batch_height,
         };
         Ok(s)
     }
@@ -218,14 +189,45 @@ impl MainMenuScene {
         )
     }
 
+    /// Draws the digits of a board dimension in the place of a single digit. Numbers with more
+    /// digits are drawn narrower.
+    fn draw_dimension(
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        images: &[graphics::Image],
+        x: f32,
+        y: f32,
+        transformation: SceneTransformation,
+    ) -> GameResult {
+        let (scene_scale, translation) = (transformation.scene_scale, transformation.translation);
+        let digit_width = MAIN_MENU_DIGIT_WIDTH / images.len() as f32;
+        for (idx, image) in images.iter().enumerate() {
+            graphics::draw(
+                ctx,
+                quad_ctx,
+                image,
+                graphics::DrawParam::new()
+                    .dest(Point2::new(
+                        (x + idx as f32 * digit_width) * scene_scale.0 + translation.0,
+                        y * scene_scale.1 + translation.1,
+                    ))
+                    .scale(Vector2::new(
+                        digit_width / image.width() as f32 * scene_scale.0,
+                        MAIN_MENU_DIGIT_HEIGHT / image.height() as f32 * scene_scale.1,
+                    )),
+            )?;
+        }
+        Ok(())
+    }
+
     fn get_radio_button_image(&self, difficulty: Difficulty) -> &graphics::Image {
-        if self.one_player_game {
-            if self.difficulty == difficulty {
+        if self.settings.has_cpu_seat() {
+            if self.settings.difficulty == difficulty {
                 return &self.image_checked_radio;
             } else {
                 return &self.image_unchecked_radio;
             }
-        } else if self.difficulty == difficulty {
+        } else if self.settings.difficulty == difficulty {
             return &self.image_disabled_checked_radio;
         }
 
@@ -273,6 +275,235 @@ impl MainMenuScene {
         .expect("draw label");
         bounding_box
     }
+
+    fn draw_seat_count_stepper(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        scene_scale: (f32, f32),
+        translation: (f32, f32),
+    ) -> GameResult {
+        let y = MAIN_MENU_SEAT_COUNT_Y * scene_scale.1 + translation.1;
+        draw_text(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_SEAT_COUNT_X * scene_scale.0 + translation.0,
+            y,
+            &format!("Players: {}", self.settings.seats.len()),
+        )?;
+        self.seat_count_decr_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_SEAT_COUNT_DECR_X * scene_scale.0 + translation.0,
+            y,
+            "-",
+            false,
+        )?;
+        self.seat_count_incr_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_SEAT_COUNT_INCR_X * scene_scale.0 + translation.0,
+            y,
+            "+",
+            false,
+        )?;
+        Ok(())
+    }
+
+    /// Draws the strength of the CPU players with buttons changing it by `CPU_STRENGTH_STEP`,
+    /// and the button choosing the adaptive CPU whose strength follows the results instead.
+    fn draw_strength_stepper(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        scene_scale: (f32, f32),
+        translation: (f32, f32),
+    ) -> GameResult {
+        let y = MAIN_MENU_STRENGTH_Y * scene_scale.1 + translation.1;
+        draw_text(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_STRENGTH_X * scene_scale.0 + translation.0,
+            y,
+            &if self.settings.adaptive {
+                "Strength: adaptive".to_string()
+            } else {
+                format!("Strength: {}", self.settings.strength)
+            },
+        )?;
+        self.strength_decr_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_STRENGTH_DECR_X * scene_scale.0 + translation.0,
+            y,
+            "-",
+            false,
+        )?;
+        self.strength_incr_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_STRENGTH_INCR_X * scene_scale.0 + translation.0,
+            y,
+            "+",
+            false,
+        )?;
+        self.adaptive_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_ADAPTIVE_X * scene_scale.0 + translation.0,
+            MAIN_MENU_ADAPTIVE_Y * scene_scale.1 + translation.1,
+            "Adaptive",
+            self.settings.adaptive,
+        )?;
+        Ok(())
+    }
+
+    /// Draws the difficulty of the CPU player suggesting the moves of the hints.
+    fn draw_hints_stepper(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        scene_scale: (f32, f32),
+        translation: (f32, f32),
+    ) -> GameResult {
+        let y = MAIN_MENU_HINTS_Y * scene_scale.1 + translation.1;
+        draw_text(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_HINTS_X * scene_scale.0 + translation.0,
+            y,
+            &format!("Hints: {:?}", self.settings.hint_difficulty),
+        )?;
+        self.hints_decr_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_HINTS_DECR_X * scene_scale.0 + translation.0,
+            y,
+            "-",
+            false,
+        )?;
+        self.hints_incr_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_HINTS_INCR_X * scene_scale.0 + translation.0,
+            y,
+            "+",
+            false,
+        )?;
+        Ok(())
+    }
+
+    /// Draws a button per seat cycling its controller and a button choosing the first seat.
+    fn draw_seat_buttons(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        scene_scale: (f32, f32),
+        translation: (f32, f32),
+    ) -> GameResult {
+        let x = MAIN_MENU_SEATS_X * scene_scale.0 + translation.0;
+        let mut seat_bounding_boxes = vec![];
+        for (idx, seat) in self.settings.seats.iter().enumerate() {
+            seat_bounding_boxes.push(draw_button(
+                ctx,
+                quad_ctx,
+                x,
+                (MAIN_MENU_SEATS_Y + idx as f32 * MAIN_MENU_SEATS_SPACING) * scene_scale.1
+                    + translation.1,
+                &format!("{}: {}", seat.player.caption(), seat.kind),
+                idx == self.settings.first_seat,
+            )?);
+        }
+        self.seat_bounding_boxes = seat_bounding_boxes;
+
+        self.first_seat_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_SEAT_COUNT_X * scene_scale.0 + translation.0,
+            MAIN_MENU_FIRST_SEAT_Y * scene_scale.1 + translation.1,
+            &format!("Starts: {}", self.settings.first_player().caption()),
+            false,
+        )?;
+        Ok(())
+    }
+
+    fn set_width(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        width: usize,
+    ) {
+        self.settings.width = width.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+        self.images_width = convert_points_to_list_of_images(ctx, quad_ctx, self.settings.width)
+            .expect("image can be loaded");
+    }
+
+    fn set_height(
+        &mut self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        height: usize,
+    ) {
+        self.settings.height = height.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+        self.images_height = convert_points_to_list_of_images(ctx, quad_ctx, self.settings.height)
+            .expect("image can be loaded");
+    }
+
+    fn load_game(
+        &self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+    ) -> Option<Transition> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (ctx, quad_ctx);
+            file::open_file_picker();
+            None
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match file::load_latest_file(SAVED_GAME_FILE_SUFFIX) {
+                Ok(contents) => self.start_saved_game(ctx, quad_ctx, &contents),
+                Err(err) => {
+                    eprintln!("Failed to load game. Error occurred: {err}");
+                    None
+                }
+            }
+        }
+    }
+
+    fn start_saved_game(
+        &self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        contents: &str,
+    ) -> Option<Transition> {
+        let (game_match, settings) = match parse_game(contents) {
+            Ok(game) => game,
+            Err(error) => {
+                eprintln!("Failed to load game. Error occurred: '{error}'");
+                return None;
+            }
+        };
+        let game_mode = GameMode::from_settings(&settings);
+        let game = PlayingScene::new(ctx, quad_ctx, game_match, game_mode, settings)
+            .expect("board was initialized");
+
+        Some(Transition::ToPlaying(Box::new(game)))
+    }
+}
+
+/// Reads a game record or, for files written by older versions, a saved board.
+fn parse_game(contents: &str) -> Result<(Match, GameSettings), String> {
+    if is_game_record(contents) {
+        let record = GameRecord::from_str(contents)?;
+        return Ok((record.replay()?, record.settings));
+    }
+    let saved_game = SavedGame::from_str(contents)?;
+    let game_mode = GameMode::from_settings(&saved_game.settings);
+    let game_match = Match::new(saved_game.board, &game_mode.turn_order())
+        .with_current_player(saved_game.player)?;
+    Ok((game_match, saved_game.settings))
 }
 
 impl Scene for MainMenuScene {
@@ -280,9 +511,15 @@ impl Scene for MainMenuScene {
 
     fn update(
         &mut self,
-        _ctx: &mut ggez::Context,
-        _quad_ctx: &mut ggez::event::GraphicsContext,
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut ggez::event::GraphicsContext,
     ) -> Result<Option<Transition>, ggez::GameError> {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(contents) = file::take_uploaded_file() {
+            return Ok(self.start_saved_game(ctx, quad_ctx, &contents));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = (ctx, quad_ctx);
         Ok(None)
     }
 
@@ -339,9 +576,9 @@ impl Scene for MainMenuScene {
             self.image_two_players.height() as f32 * scene_scale.1,
         );
 
-        if self.one_player_game {
+        if self.settings.is_one_player_game() {
             draw_selection_rect(ctx, quad_ctx, self.one_player_bounding_box, scene_scale)?;
-        } else {
+        } else if self.settings.is_all_human_game() {
             draw_selection_rect(ctx, quad_ctx, self.two_players_bounding_box, scene_scale)?;
         }
 
@@ -381,27 +618,22 @@ impl Scene for MainMenuScene {
         #[cfg(feature = "draw_bounding_rects")]
         draw_bounding_rect(ctx, quad_ctx, self.height_decr_button_bounding_box)?;
 
-        let mut p = graphics::DrawParam::new()
-            .dest(Point2::new(
-                MAIN_MENU_IMAGE_WIDTH_X * scene_scale.0 + translation.0,
-                MAIN_MENU_IMAGE_WIDTH_Y * scene_scale.1 + translation.1,
-            ))
-            .scale(Vector2::new(
-                MAIN_MENU_DIGIT_WIDTH / self.image_width.width() as f32 * scene_scale.0,
-                MAIN_MENU_DIGIT_HEIGHT / self.image_width.height() as f32 * scene_scale.1,
-            ));
-        self.spritebatch_width.add(p);
-
-        p = graphics::DrawParam::new()
-            .dest(Point2::new(
-                MAIN_MENU_IMAGE_HEIGHT_X * scene_scale.0 + translation.0,
-                MAIN_MENU_IMAGE_HEIGHT_Y * scene_scale.1 + translation.1,
-            ))
-            .scale(Vector2::new(
-                MAIN_MENU_DIGIT_WIDTH / self.image_height.width() as f32 * scene_scale.0,
-                MAIN_MENU_DIGIT_HEIGHT / self.image_height.height() as f32 * scene_scale.1,
-            ));
-        self.spritebatch_height.add(p);
+        Self::draw_dimension(
+            ctx,
+            quad_ctx,
+            &self.images_width,
+            MAIN_MENU_IMAGE_WIDTH_X,
+            MAIN_MENU_IMAGE_WIDTH_Y,
+            SceneTransformation::new(scene_scale, translation),
+        )?;
+        Self::draw_dimension(
+            ctx,
+            quad_ctx,
+            &self.images_height,
+            MAIN_MENU_IMAGE_HEIGHT_X,
+            MAIN_MENU_IMAGE_HEIGHT_Y,
+            SceneTransformation::new(scene_scale, translation),
+        )?;
 
         #[cfg(feature = "draw_bounding_rects")]
         draw_bounding_rect(ctx, quad_ctx, self.start_button_bounding_box)?;
@@ -409,8 +641,6 @@ impl Scene for MainMenuScene {
         for spritebatch in [
             &mut self.spritebatch_minus,
             &mut self.spritebatch_plus,
-            &mut self.spritebatch_width,
-            &mut self.spritebatch_height,
         ] {
             graphics::draw(ctx, quad_ctx, spritebatch, graphics::DrawParam::new())?;
             spritebatch.clear();
@@ -452,6 +682,15 @@ impl Scene for MainMenuScene {
             SceneTransformation::new(scene_scale, translation),
         );
 
+        self.expert_difficulty_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_EXPERT_X * scene_scale.0 + translation.0,
+            MAIN_MENU_EXPERT_Y * scene_scale.1 + translation.1,
+            "Expert",
+            self.settings.has_cpu_seat() && self.settings.difficulty == Difficulty::Expert,
+        )?;
+
         self.start_button_bounding_box = graphics::Rect::new(
             translation.0 + MAIN_MENU_START_BUTTON_X * scene_scale.0,
             translation.1 + MAIN_MENU_START_BUTTON_Y * scene_scale.1,
@@ -459,6 +698,38 @@ impl Scene for MainMenuScene {
             MAIN_MENU_START_BUTTON_HEIGHT * scene_scale.1,
         );
 
+        self.load_button_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_LOAD_BUTTON_X * scene_scale.0 + translation.0,
+            MAIN_MENU_LOAD_BUTTON_Y * scene_scale.1 + translation.1,
+            "Load game",
+            false,
+        )?;
+
+        self.shape_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_SHAPE_X * scene_scale.0 + translation.0,
+            MAIN_MENU_SHAPE_Y * scene_scale.1 + translation.1,
+            &format!("Shape: {}", self.settings.shape),
+            false,
+        )?;
+
+        self.seed_bounding_box = draw_button(
+            ctx,
+            quad_ctx,
+            MAIN_MENU_SEED_X * scene_scale.0 + translation.0,
+            MAIN_MENU_SEED_Y * scene_scale.1 + translation.1,
+            &format!("Seed: {}", self.settings.seed),
+            false,
+        )?;
+
+        self.draw_seat_count_stepper(ctx, quad_ctx, scene_scale, translation)?;
+        self.draw_seat_buttons(ctx, quad_ctx, scene_scale, translation)?;
+        self.draw_strength_stepper(ctx, quad_ctx, scene_scale, translation)?;
+        self.draw_hints_stepper(ctx, quad_ctx, scene_scale, translation)?;
+
         graphics::present(ctx, quad_ctx)?;
         Ok(())
     }
@@ -472,92 +743,145 @@ impl Scene for MainMenuScene {
         y: f32,
     ) -> Option<Transition> {
         let point = Point2::new(x, y);
+        if self.load_button_bounding_box.contains(point) {
+            return self.load_game(ctx, quad_ctx);
+        }
+
         self.height_decr_button_bounding_box
+            .contains(point)
+            .then(|| self.set_height(ctx, quad_ctx, self.settings.height.saturating_sub(1)));
+
+        self.height_incr_button_bounding_box
+            .contains(point)
+            .then(|| self.set_height(ctx, quad_ctx, self.settings.height + 1));
+
+        self.width_decr_button_bounding_box
+            .contains(point)
+            .then(|| self.set_width(ctx, quad_ctx, self.settings.width.saturating_sub(1)));
+
+        self.width_incr_button_bounding_box
+            .contains(point)
+            .then(|| self.set_width(ctx, quad_ctx, self.settings.width + 1));
+
+        self.seat_count_decr_button_bounding_box
             .contains(point)
             .then(|| {
-                self.height = std::cmp::max(self.height - 1, 1);
-                self.image_height =
-                    graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", self.height))
-                        .expect("image can be loaded");
-                self.spritebatch_height.set_image(self.image_height.clone());
+                self.settings
+                    .set_seat_count(self.settings.seats.len().saturating_sub(1));
             });
 
-        self.height_incr_button_bounding_box
+        self.seat_count_incr_button_bounding_box
             .contains(point)
             .then(|| {
-                self.height = std::cmp::min(self.height + 1, 9);
-                self.image_height =
-                    graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", self.height))
-                        .expect("image can be loaded");
-                self.spritebatch_height.set_image(self.image_height.clone());
+                self.settings.set_seat_count(self.settings.seats.len() + 1);
             });
 
-        self.width_decr_button_bounding_box
+        self.strength_decr_button_bounding_box
             .contains(point)
             .then(|| {
-                self.width = std::cmp::max(self.width - 1, 1);
-                self.image_width =
-                    graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", self.width))
-                        .expect("image can be loaded");
-                self.spritebatch_width.set_image(self.image_width.clone());
+                self.settings
+                    .set_strength(self.settings.strength.saturating_sub(CPU_STRENGTH_STEP));
             });
 
-        self.width_incr_button_bounding_box
+        self.strength_incr_button_bounding_box
+            .contains(point)
+            .then(|| {
+                self.settings
+                    .set_strength(self.settings.strength + CPU_STRENGTH_STEP);
+            });
+
+        self.adaptive_bounding_box.contains(point).then(|| {
+            self.settings.adaptive = !self.settings.adaptive;
+        });
+
+        self.hints_decr_button_bounding_box
             .contains(point)
             .then(|| {
-                self.width = std::cmp::min(self.width + 1, 9);
-                self.image_width =
-                    graphics::Image::new(ctx, quad_ctx, format!("ui/{:?}.png", self.width))
-                        .expect("image can be loaded");
-                self.spritebatch_width.set_image(self.image_width.clone());
+                self.settings.hint_difficulty = self.settings.hint_difficulty.easier();
             });
 
+        self.hints_incr_button_bounding_box
+            .contains(point)
+            .then(|| {
+                self.settings.hint_difficulty = self.settings.hint_difficulty.harder();
+            });
+
+        if let Some(seat) = self
+            .seat_bounding_boxes
+            .iter()
+            .position(|bounding_box| bounding_box.contains(point))
+        {
+            self.settings.cycle_seat_kind(seat);
+        }
+
+        self.first_seat_bounding_box.contains(point).then(|| {
+            self.settings.cycle_first_seat();
+        });
+
+        self.shape_bounding_box.contains(point).then(|| {
+            self.settings.shape = self.settings.shape.next();
+        });
+
+        self.seed_bounding_box.contains(point).then(|| {
+            self.settings.roll_seed();
+        });
+
         self.one_player_bounding_box.contains(point).then(|| {
-            self.one_player_game = true;
+            self.settings.set_one_player_game(self.settings.seats.len());
         });
 
         self.two_players_bounding_box.contains(point).then(|| {
-            self.one_player_game = false;
+            self.settings.set_all_human_game(self.settings.seats.len());
         });
 
         self.easy_difficulty_bounding_box.contains(point).then(|| {
-            if self.one_player_game {
-                self.difficulty = Difficulty::Easy;
+            if self.settings.has_cpu_seat() {
+                self.settings.set_difficulty(Difficulty::Easy);
             }
         });
 
         self.medium_difficulty_bounding_box
             .contains(point)
             .then(|| {
-                if self.one_player_game {
-                    self.difficulty = Difficulty::Medium;
+                if self.settings.has_cpu_seat() {
+                    self.settings.set_difficulty(Difficulty::Medium);
                 }
             });
 
          self.hard_difficulty_bounding_box.contains(point).then(|| {
-            if self.one_player_game {
-                self.difficulty = Difficulty::Hard;
+            if self.settings.has_cpu_seat() {
+                self.settings.set_difficulty(Difficulty::Hard);
             }
         });
 
+        self.expert_difficulty_bounding_box
+            .contains(point)
+            .then(|| {
+                if self.settings.has_cpu_seat() {
+                    self.settings.set_difficulty(Difficulty::Expert);
+                }
+            });
+
         self.start_button_bounding_box.contains(point).then(|| {
-            let game_mode = if self.one_player_game {
-                let ai_player = get_cpu_player(&self.difficulty);
-                GameMode::OnePlayer(ai_player)
-            } else {
-                GameMode::TwoPlayer
-            };
-            let game = PlayingScene::new(
-                ctx,
-                quad_ctx,
-                game::Player::Player1,
-                Board::new(self.width, self.height),
-                game_mode,
-                self.difficulty,
-            )
-            .expect("board was initialized");
+            // the adaptive CPU plays at the strength the human reached so far
+            let mut settings = self.settings.clone();
+            if let Some(player) = settings.adaptive_player() {
+                settings.set_strength(AdaptiveHistory::load().strength(player));
+            }
+            let game_mode = GameMode::from_settings(&settings);
+            let game_match = Match::new(settings.new_board(), &game_mode.turn_order())
+                .with_current_player(settings.first_player())
+                .expect("first player takes a seat");
+
+            if settings.adaptive_player().is_some() {
+                let game =
+                    PreparePlayerScene::new(ctx, quad_ctx, &game_match, &game_mode, &settings);
+                return Transition::ToPreparePlayer(Box::new(game));
+            }
+            let game = PlayingScene::new(ctx, quad_ctx, game_match, game_mode, settings)
+                .expect("board was initialized");
 
-            Transition::ToPlaying(Box::new(game))            
+            Transition::ToPlaying(Box::new(game))
         })
     }
 }
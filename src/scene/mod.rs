@@ -2,7 +2,6 @@ extern crate good_web_game as ggez;
 
 use crate::game;
 
-use game::Board;
 use game::GameMode;
 
 use ggez::cgmath::Point2;
@@ -20,6 +19,7 @@ use crate::rendering::constants::*;
 use crate::state::marker::*;
 use crate::state::transition::*;
 
+pub mod analysis;
 pub mod game_over;
 pub mod main_menu;
 pub mod playing;
@@ -1,8 +1,12 @@
 use ggez::timer;
 
 use super::*;
-use crate::game::GameMode;
-use crate::game::Player;
+use crate::ai::move_search::{start_thinking, MoveSearch};
+use crate::ai::prelude::get_cpu_player;
+use crate::game::adaptive::{AdaptiveHistory, GameOutcome, LevelChange};
+use crate::game::game_match::{Match, MoveOutcome};
+use crate::game::record::{format_date, GameRecord};
+use crate::game::settings::GameSettings;
 use crate::rendering::prelude::*;
 use crate::rendering::Renderer;
 use crate::scene::prelude::*;
@@ -10,57 +14,58 @@ use crate::scene::prelude::*;
 #[derive(Debug)]
 pub struct PlayingScene {
     board_renderer: BoardRenderer,
-    first_player_renderer: PlayerDataRenderer,
-    second_player_renderer: PlayerDataRenderer,
+    player_renderers: Vec<PlayerDataRenderer>,
     cancel_button_renderer: ButtonRenderer,
     download_button_renderer: ButtonRenderer,
-    board: game::Board,
-    player: game::Player,
+    undo_button_renderer: TextButtonRenderer,
+    redo_button_renderer: TextButtonRenderer,
+    slower_button_renderer: TextButtonRenderer,
+    faster_button_renderer: TextButtonRenderer,
+    hint_button_renderer: TextButtonRenderer,
+    game_match: Match,
     wall_bounding_boxes: Vec<Vec<Rect>>,
     cancel_bounding_box: Rect,
     download_bounding_box: Rect,
+    undo_bounding_box: Rect,
+    redo_bounding_box: Rect,
+    slower_bounding_box: Rect,
+    faster_bounding_box: Rect,
+    hint_bounding_box: Rect,
     game_mode: game::GameMode,
-    difficulty: game::Difficulty,
+    settings: GameSettings,
     already_drawn: bool,
     deferred_transition: Option<Transition>,
+    thinking: Option<Box<dyn MoveSearch>>,
+    hint: Option<Box<dyn MoveSearch>>,
 }
 
 impl PlayingScene {
     pub fn new(
         ctx: &mut Context,
         quad_ctx: &mut miniquad::GraphicsContext,
-        player: Player,
-        board: Board,
+        game_match: Match,
         game_mode: game::GameMode,
-        difficulty: game::Difficulty,
+        settings: GameSettings,
     ) -> GameResult<PlayingScene> {
+        let player = game_match.current_player();
+        let board = game_match.board();
         let wall_bounding_boxes =
             vec![vec![Rect::default(); board.width + 1]; 2 * board.height + 1];
 
         let board_renderer = BoardRenderer::new(ctx, quad_ctx, player, board.clone(), false)?;
-        let first_player_renderer = PlayerDataRenderer::new(
-            ctx,
-            quad_ctx,
-            Player::Player1,
-            board.statistics.player1_points,
-            player == Player::Player1,
-        )?;
-        let second_player_renderer = match game_mode {
-            GameMode::OnePlayer(_) => PlayerDataRenderer::new(
+        let seat_count = game_mode.seats.len();
+        let mut player_renderers = vec![];
+        for (seat, seat_player) in game_mode.turn_order().into_iter().enumerate() {
+            player_renderers.push(PlayerDataRenderer::new(
                 ctx,
                 quad_ctx,
-                Player::CPU,
-                board.statistics.cpu_points,
-                player == Player::CPU,
-            )?,
-            GameMode::TwoPlayer => PlayerDataRenderer::new(
-                ctx,
-                quad_ctx,
-                Player::Player2,
-                board.statistics.player2_points,
-                player == Player::Player2,
-            )?,
-        };
+                seat_player,
+                seat,
+                seat_count,
+                board.statistics.get_points(seat_player),
+                player == seat_player,
+            )?);
+        }
         let cancel_button_renderer = ButtonRenderer::new(
             ctx,
             quad_ctx,
@@ -75,22 +80,45 @@ impl PlayingScene {
             PLAYING_DOWNLOAD_BUTTON_MARGIN_X,
             PLAYING_DOWNLOAD_BUTTON_MARGIN_Y,
         )?;
+        let undo_button_renderer =
+            TextButtonRenderer::new("Undo", PLAYING_UNDO_BUTTON_X, PLAYING_UNDO_BUTTON_Y);
+        let redo_button_renderer =
+            TextButtonRenderer::new("Redo", PLAYING_REDO_BUTTON_X, PLAYING_REDO_BUTTON_Y);
+        let slower_button_renderer =
+            TextButtonRenderer::new("Slower", PLAYING_SLOWER_BUTTON_X, PLAYING_SLOWER_BUTTON_Y);
+        let faster_button_renderer = TextButtonRenderer::new(
+            &get_faster_button_caption(&settings),
+            PLAYING_FASTER_BUTTON_X,
+            PLAYING_FASTER_BUTTON_Y,
+        );
+        let hint_button_renderer =
+            TextButtonRenderer::new("Hint", PLAYING_HINT_BUTTON_X, PLAYING_HINT_BUTTON_Y);
 
         let s = PlayingScene {
             board_renderer,
-            first_player_renderer,
-            second_player_renderer,
+            player_renderers,
             cancel_button_renderer,
             download_button_renderer,
-            board,
-            player,
+            undo_button_renderer,
+            redo_button_renderer,
+            slower_button_renderer,
+            faster_button_renderer,
+            hint_button_renderer,
+            game_match,
             wall_bounding_boxes,
             cancel_bounding_box: Rect::default(),
             download_bounding_box: Rect::default(),
+            undo_bounding_box: Rect::default(),
+            redo_bounding_box: Rect::default(),
+            slower_bounding_box: Rect::default(),
+            faster_bounding_box: Rect::default(),
+            hint_bounding_box: Rect::default(),
             game_mode,
-            difficulty,
+            settings,
             already_drawn: false,
             deferred_transition: None,
+            thinking: None,
+            hint: None,
         };
         Ok(s)
     }
@@ -102,33 +130,15 @@ impl PlayingScene {
         row: usize,
         col: usize,
     ) {
-        match self.board.click_wall(row, col, self.player) {
-            Ok(additional_move) => {
-                self.board_renderer.set_board(&self.board);
+        match self.game_match.play(row, col) {
+            Ok(outcome) => {
+                self.hint = None;
+                self.board_renderer.set_hint(None);
+                self.board_renderer.set_board(self.game_match.board());
                 self.update_points(ctx, quad_ctx);
 
-                if !additional_move {
-                    let new_player = match self.player {
-                        game::Player::Player1 => match &self.game_mode {
-                            GameMode::OnePlayer(_) => game::Player::CPU,
-                            GameMode::TwoPlayer => game::Player::Player2,
-                        },
-                        game::Player::Player2 | game::Player::CPU => game::Player::Player1,
-                    };
-
-                    if !self.board.all_is_clicked() {
-                        let game = PlayingScene::new(
-                            ctx,
-                            quad_ctx,
-                            new_player,
-                            self.board.clone(),
-                            self.game_mode.clone(),
-                            self.difficulty,
-                        )
-                        .expect("board was initialized");
-
-                        self.deferred_transition = Some(Transition::ToPlaying(Box::new(game)));
-                    }
+                if outcome == MoveOutcome::NextPlayer {
+                    self.deferred_transition = Some(self.restart_turn(ctx, quad_ctx));
                 }
                 self.already_drawn = false;
             }
@@ -136,25 +146,142 @@ impl PlayingScene {
         }
     }
 
-    fn update_points(&mut self, ctx: &mut Context, quad_ctx: &mut miniquad::Context) {
-        self.first_player_renderer
-            .set_points(ctx, quad_ctx, self.board.statistics.player1_points)
-            .expect("Player 1 points can be set in the renderer");
-        match &self.game_mode {
-            GameMode::OnePlayer(_) => self
-                .second_player_renderer
-                .set_points(ctx, quad_ctx, self.board.statistics.cpu_points)
-                .expect("CPU points can be set in the renderer"),
-            GameMode::TwoPlayer => self
-                .second_player_renderer
-                .set_points(ctx, quad_ctx, self.board.statistics.player2_points)
-                .expect("Player 2 points can be set in the renderer"),
+    /// Reverts the last move. Moves of CPU seats are reverted as well until the last move of a
+    /// human seat, so the game continues from a human's turn.
+    fn undo(
+        &mut self,
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut event::GraphicsContext,
+    ) -> Option<Transition> {
+        let mut undone_any = false;
+        while let Some(undone_move) = self.game_match.undo() {
+            undone_any = true;
+            if self.game_mode.is_human(undone_move.player) || !self.game_mode.has_human_seat() {
+                break;
+            }
+        }
+        undone_any.then(|| self.restart_turn(ctx, quad_ctx))
+    }
+
+    /// Replays the last reverted move together with the CPU moves which followed it.
+    fn redo(
+        &mut self,
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut event::GraphicsContext,
+    ) -> Option<Transition> {
+        self.game_match.redo()?;
+        while self.game_mode.has_human_seat()
+            && self
+                .game_match
+                .get_next_redo()
+                .is_some_and(|next_move| !self.game_mode.is_human(next_move.player))
+        {
+            self.game_match.redo();
+        }
+        Some(self.restart_turn(ctx, quad_ctx))
+    }
+
+    fn restart_turn(
+        &self,
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut event::GraphicsContext,
+    ) -> Transition {
+        let game = PlayingScene::new(
+            ctx,
+            quad_ctx,
+            self.game_match.clone(),
+            self.game_mode.clone(),
+            self.settings.clone(),
+        )
+        .expect("board was initialized");
+
+        Transition::ToPlaying(Box::new(game))
+    }
+
+    fn change_cpu_speed(&mut self, faster: bool) {
+        let cpu_speed = if faster {
+            self.settings.cpu_speed + 1
+        } else {
+            self.settings.cpu_speed.saturating_sub(1)
         };
+        self.settings.set_cpu_speed(cpu_speed);
+        self.faster_button_renderer
+            .set_caption(&get_faster_button_caption(&self.settings));
+    }
+
+    /// Adds the result of a finished game against the adaptive CPU to the history of the human
+    /// and takes the strength for the next game from it.
+    fn record_adaptive_result(&mut self) -> Option<LevelChange> {
+        let player = self.settings.adaptive_player()?;
+        let mut history = AdaptiveHistory::load();
+        let outcome = GameOutcome::of(&self.game_match.get_statistics(), player);
+        let level_change = history.record(player, outcome);
+        history.save();
+        self.settings.set_strength(level_change.current);
+        Some(level_change)
+    }
+
+    /// Starts the search of the move of the current player when it is a CPU's turn.
+    fn start_thinking(&self) -> Option<Box<dyn MoveSearch>> {
+        if self.deferred_transition.is_some() || self.game_match.is_over() {
+            return None;
+        }
+        let player = self.game_match.current_player();
+        self.game_mode
+            .get_move_generator(player)
+            .map(|move_generator| start_thinking(move_generator, self.game_match.board(), player))
+    }
+
+    /// Asks the CPU player of the hint difficulty for the move of the human on turn.
+    fn ask_for_hint(&mut self) {
+        let player = self.game_match.current_player();
+        let hint_generator = get_cpu_player(
+            &self.settings.hint_difficulty,
+            MAX_CPU_STRENGTH,
+            self.settings.seed,
+        );
+        self.hint = Some(start_thinking(
+            hint_generator.as_ref(),
+            self.game_match.board(),
+            player,
+        ));
+        self.game_match.record_hint();
     }
-    
-    fn download_board(&self, _board: &Board) {
+
+    fn can_ask_for_hint(&self) -> bool {
+        !self.is_cpu_turn()
+            && !self.game_match.is_over()
+            && self.deferred_transition.is_none()
+            && self.hint.is_none()
+    }
+
+    fn is_thinking(&self) -> bool {
+        [&self.thinking, &self.hint]
+            .into_iter()
+            .flatten()
+            .any(|search| !search.is_finished())
+    }
+
+    fn is_cpu_turn(&self) -> bool {
+        !self.game_mode.is_human(self.game_match.current_player())
+    }
+
+    fn update_points(&mut self, ctx: &mut Context, quad_ctx: &mut miniquad::Context) {
+        let statistics = &self.game_match.board().statistics;
+        for (renderer, player) in self
+            .player_renderers
+            .iter_mut()
+            .zip(self.game_mode.turn_order())
+        {
+            renderer
+                .set_points(ctx, quad_ctx, statistics.get_points(player))
+                .expect("player points can be set in the renderer");
+        }
+    }
+
+    fn download_board(&self) {
         use crate::file;
-        let filename = format!("{}_board.txt", ggez::timer::time());
+        let filename = format!("{}{}", ggez::timer::time(), SAVED_GAME_FILE_SUFFIX);
         #[cfg(target_arch = "wasm32")]
         {
             println!("Downloading scene");
@@ -180,31 +307,41 @@ impl Scene for PlayingScene {
         ctx: &mut ggez::Context,
         quad_ctx: &mut event::GraphicsContext,
     ) -> Result<Option<Transition>, ggez::GameError> {
-        if self.already_drawn && timer::ticks(ctx) % PLAYING_TICK_COUNT == 0 {
+        // the CPU thinks in every frame, the move is played at the pace of the CPU speed
+        match self.thinking.as_mut() {
+            Some(search) => search.step(CPU_THINKING_STEP),
+            None => self.thinking = self.start_thinking(),
+        }
+        if let Some(search) = self.hint.as_mut() {
+            search.step(CPU_THINKING_STEP);
+            if search.is_finished() {
+                self.board_renderer.set_hint(search.best_so_far());
+                self.hint = None;
+            }
+        }
+
+        if self.already_drawn && timer::ticks(ctx).is_multiple_of(self.settings.get_cpu_move_ticks()) {
             if let Some(transition) = self.deferred_transition.take() {
                 return Ok(Some(transition));
             }
 
-            if self.board.all_is_clicked() {
-                let game_statistics = self.board.get_statistics();
+            if self.game_match.is_over() {
+                let level_change = self.record_adaptive_result();
                 let game = GameOverScene::new(
                     ctx,
                     quad_ctx,
-                    game_statistics,
+                    &self.game_match,
                     &self.game_mode,
-                    self.difficulty,
-                    self.board.width,
-                    self.board.height,
+                    self.settings.clone(),
+                    level_change,
                 )
                 .expect("scene has been created");
                 return Ok(Some(Transition::ToGameOver(Box::new(game))));
             }
 
-            if self.player == Player::CPU {
-                if let Some((row, col)) = match &self.game_mode {
-                    GameMode::OnePlayer(move_generator) => move_generator.next_move(&self.board),
-                    GameMode::TwoPlayer => None,
-                } {
+            if self.thinking.as_ref().is_some_and(|search| search.is_finished()) {
+                let search = self.thinking.take().expect("search is finished");
+                if let Some((row, col)) = search.best_so_far() {
                     self.click_wall(ctx, quad_ctx, row, col);
                 }
             }
@@ -214,16 +351,43 @@ impl Scene for PlayingScene {
 
     fn draw(&mut self, ctx: &mut Context, quad_ctx: &mut miniquad::GraphicsContext) -> GameResult {
         self.board_renderer.draw(ctx, quad_ctx)?;
-        self.first_player_renderer.set_orientation(quad_ctx);
-        self.first_player_renderer.draw(ctx, quad_ctx)?;
-        self.second_player_renderer.set_orientation(quad_ctx);
-        self.second_player_renderer.draw(ctx, quad_ctx)?;
+        for player_renderer in &mut self.player_renderers {
+            player_renderer.set_orientation(quad_ctx);
+            player_renderer.draw(ctx, quad_ctx)?;
+        }
         self.cancel_button_renderer.draw(ctx, quad_ctx)?;
         self.download_button_renderer.draw(ctx, quad_ctx)?;
+        self.undo_button_renderer.draw(ctx, quad_ctx)?;
+        self.redo_button_renderer.draw(ctx, quad_ctx)?;
+        if self.settings.has_cpu_seat() {
+            self.slower_button_renderer.draw(ctx, quad_ctx)?;
+            self.faster_button_renderer.draw(ctx, quad_ctx)?;
+            self.slower_bounding_box = self.slower_button_renderer.get_bouding_rect();
+            self.faster_bounding_box = self.faster_button_renderer.get_bouding_rect();
+        }
+        if self.can_ask_for_hint() {
+            self.hint_button_renderer.draw(ctx, quad_ctx)?;
+            self.hint_bounding_box = self.hint_button_renderer.get_bouding_rect();
+        } else {
+            self.hint_bounding_box = Rect::default();
+        }
+        if self.is_thinking() {
+            let scene_scale = get_scene_scale(quad_ctx);
+            let (w, _) = quad_ctx.screen_size();
+            crate::scene::prelude::draw_text(
+                ctx,
+                quad_ctx,
+                w - PLAYING_THINKING_X * scene_scale.0,
+                PLAYING_THINKING_Y * scene_scale.1,
+                "Thinking...",
+            )?;
+        }
 
         self.wall_bounding_boxes = self.board_renderer.get_wall_bounding_boxes();
         self.cancel_bounding_box = self.cancel_button_renderer.get_bouding_rect();
         self.download_bounding_box = self.download_button_renderer.get_bouding_rect();
+        self.undo_bounding_box = self.undo_button_renderer.get_bouding_rect();
+        self.redo_bounding_box = self.redo_button_renderer.get_bouding_rect();
         self.already_drawn = true;
 
         Ok(())
@@ -240,36 +404,49 @@ impl Scene for PlayingScene {
         let point = Point2::new(x, y);
 
         if self.download_bounding_box.contains(point) {
-            self.download_board(&self.board);
+            self.download_board();
+            return None;
+        }
+
+        if self.settings.has_cpu_seat()
+            && (self.slower_bounding_box.contains(point) || self.faster_bounding_box.contains(point))
+        {
+            self.change_cpu_speed(self.faster_bounding_box.contains(point));
             return None;
         }
 
+        if self.hint_bounding_box.contains(point) {
+            if self.can_ask_for_hint() {
+                self.ask_for_hint();
+            }
+            return None;
+        }
+
+        if self.undo_bounding_box.contains(point) {
+            return self.undo(ctx, quad_ctx);
+        }
+
+        if self.redo_bounding_box.contains(point) {
+            return self.redo(ctx, quad_ctx);
+        }
+
         if self.cancel_bounding_box.contains(point) {
-            let game = MainMenuScene::from(
-                ctx,
-                quad_ctx,
-                self.board.width,
-                self.board.height,
-                match &self.game_mode {
-                    GameMode::OnePlayer(_) => true,
-                    GameMode::TwoPlayer => false,
-                },
-                self.difficulty,
-            );
+            let game = MainMenuScene::from(ctx, quad_ctx, self.settings.clone());
             return Some(Transition::ToMainMenu(Box::new(
                 game.expect("scene has been created"),
             )));
         }
 
-        if self.player == Player::CPU || self.deferred_transition.is_some() {
+        if self.is_cpu_turn() || self.deferred_transition.is_some() {
             return None;
         }
 
-        for row in 0..2 * self.board.height + 1 {
+        let board = self.game_match.board();
+        for row in 0..2 * board.height + 1 {
             let max_col = if row % 2 > 0 {
-                self.board.width + 1
+                board.width + 1
             } else {
-                self.board.width
+                board.width
             };
             for col in 0..max_col {
                 let rect = self.wall_bounding_boxes[row][col];
@@ -285,17 +462,15 @@ impl Scene for PlayingScene {
 
 impl std::fmt::Display for PlayingScene {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        writeln!(f, "Player:{:?}", self.player)?;
-        writeln!(f, "Width:{}", self.board.width)?;
-        writeln!(f, "Height:{}", self.board.height)?;
-        writeln!(f, "Board:")?;
-        writeln!(f, "{}", self.board)?;
-        match self.game_mode {
-            GameMode::OnePlayer(_) => writeln!(f, "OnePlayerMode:true")?,
-            GameMode::TwoPlayer => writeln!(f, "OnePlayerMode:false")?,
-        }        
-        writeln!(f, "Difficulty:{:?}", self.difficulty)?;
-
-        Ok(())
+        let record = GameRecord::from_match(
+            &self.game_match,
+            &self.settings,
+            &format_date(ggez::timer::time()),
+        );
+        write!(f, "{record}")
     }
-}   
\ No newline at end of file
+}
+
+fn get_faster_button_caption(settings: &GameSettings) -> String {
+    format!("Faster ({}/{})", settings.cpu_speed + 1, CPU_MOVE_TICKS.len())
+}
@@ -2,15 +2,17 @@ use std::time::Duration;
 
 use ggez::graphics::{DrawParam, Point2};
 
-use crate::game::{Difficulty, Player};
+use crate::game::game_match::Match;
+use crate::game::settings::GameSettings;
+use crate::game::Player;
+use crate::rendering::board_renderer::get_player_bg_color;
 use crate::scene::prelude::*;
 
 #[derive(Debug)]
 pub struct PreparePlayerScene {
-    player: Player,
-    board: Board,
+    game_match: Match,
     game_mode: GameMode,
-    difficulty: Difficulty,
+    settings: GameSettings,
     start_time: Option<Duration>,
     image_player1: graphics::Image,
     image_player2: graphics::Image,
@@ -21,10 +23,9 @@ impl PreparePlayerScene {
     pub fn new(
         ctx: &mut ggez::Context,
         quad_ctx: &mut ggez::event::GraphicsContext,
-        player: Player,
-        board: &Board,
+        game_match: &Match,
         game_mode: &GameMode,
-        difficulty : Difficulty,
+        settings: &GameSettings,
     ) -> Self {
         let image_player1 = graphics::Image::new(ctx, quad_ctx, "ui/player_1_turn.png")
             .expect("image is available");
@@ -34,10 +35,9 @@ impl PreparePlayerScene {
             graphics::Image::new(ctx, quad_ctx, "ui/cpu_turn.png").expect("image is available");
 
         Self {
-            player,
-            board: board.clone(),
+            game_match: game_match.clone(),
             game_mode: game_mode.clone(),
-            difficulty,
+            settings: settings.clone(),
             start_time: None,
             image_player1,
             image_player2,
@@ -56,6 +56,27 @@ impl PreparePlayerScene {
         }
         (tile_size_x, tile_size_x)
     }
+
+    /// Tells the strength the adaptive CPU plays at, when it plays.
+    fn draw_adaptive_level(
+        &self,
+        ctx: &mut Context,
+        quad_ctx: &mut miniquad::GraphicsContext,
+        tile_size: (f32, f32),
+        translation: (f32, f32),
+    ) -> GameResult {
+        if self.settings.adaptive_player().is_none() {
+            return Ok(());
+        }
+        draw_text(
+            ctx,
+            quad_ctx,
+            SCENE_WIDTH / 2.0 * tile_size.0 + translation.0,
+            PREPARE_PLAYER_LEVEL_Y * tile_size.1 + translation.1,
+            &format!("CPU level: {}", self.settings.strength),
+        )?;
+        Ok(())
+    }
 }
 
 impl Scene for PreparePlayerScene {
@@ -74,10 +95,9 @@ impl Scene for PreparePlayerScene {
                 let game = PlayingScene::new(
                     ctx,
                     quad_ctx,
-                    self.player,
-                    self.board.clone(),
+                    self.game_match.clone(),
                     self.game_mode.clone(),
-                    self.difficulty,
+                    self.settings.clone(),
                 )
                 .expect("board was initialized");
 
@@ -95,32 +115,34 @@ impl Scene for PreparePlayerScene {
 
         let dst = Point2::new(translation.0, translation.1);
 
-        match self.player {
-            Player::Player1 => graphics::draw(
-                ctx,
-                quad_ctx,
-                &self.image_player1,
-                DrawParam::new()
-                    .dest(dst)
-                    .scale(Vector2::new(tile_size.0, tile_size.1)),
-            )?,
-            Player::Player2 => graphics::draw(
-                ctx,
-                quad_ctx,
-                &self.image_player2,
-                DrawParam::new()
-                    .dest(dst)
-                    .scale(Vector2::new(tile_size.0, tile_size.1)),
-            )?,
-            Player::CPU => graphics::draw(
-                ctx,
-                quad_ctx,
-                &self.image_cpu,
-                DrawParam::new()
-                    .dest(dst)
-                    .scale(Vector2::new(tile_size.0, tile_size.1)),
-            )?,
-        }
+        let player = self.game_match.current_player();
+        let image = match player {
+            Player::Player1 => &self.image_player1,
+            Player::Player2 => &self.image_player2,
+            Player::CPU => &self.image_cpu,
+            Player::Player3 | Player::Player4 => {
+                graphics::clear(ctx, quad_ctx, graphics::Color::from_rgb_u32(get_player_bg_color(player)));
+                draw_text(
+                    ctx,
+                    quad_ctx,
+                    SCENE_WIDTH / 2.0 * tile_size.0 + translation.0,
+                    SCENE_HEIGHT / 2.0 * tile_size.1 + translation.1,
+                    &format!("{} turn", player.caption()),
+                )?;
+                self.draw_adaptive_level(ctx, quad_ctx, tile_size, translation)?;
+                graphics::present(ctx, quad_ctx)?;
+                return Ok(());
+            }
+        };
+        graphics::draw(
+            ctx,
+            quad_ctx,
+            image,
+            DrawParam::new()
+                .dest(dst)
+                .scale(Vector2::new(tile_size.0, tile_size.1)),
+        )?;
+        self.draw_adaptive_level(ctx, quad_ctx, tile_size, translation)?;
 
         graphics::present(ctx, quad_ctx)?;
         Ok(())
@@ -6,16 +6,23 @@ use crate::scene::prelude::*;
 pub struct TitleScreenScene {
     image: graphics::Image,
     start_button_bounding_box: Rect,
+    seed: u64,
 }
 
 impl TitleScreenScene {
-    pub fn new(ctx: &mut ggez::Context, quad_ctx: &mut ggez::event::GraphicsContext) -> Self {
+    /// Title screen of a session whose first game is played with the given seed.
+    pub fn new(
+        ctx: &mut ggez::Context,
+        quad_ctx: &mut ggez::event::GraphicsContext,
+        seed: u64,
+    ) -> Self {
         let image =
             graphics::Image::new(ctx, quad_ctx, "ui/title_screen.png").expect("image is available");
 
         Self {
             image,
             start_button_bounding_box: Rect::default(),
+            seed,
         }
     }    
 }
@@ -74,7 +81,7 @@ impl Scene for TitleScreenScene {
         let point = Point2::new(x, y);
 
         self.start_button_bounding_box.contains(point).then(|| {
-            let game = MainMenuScene::new(ctx, quad_ctx).expect("scene was created");
+            let game = MainMenuScene::new(ctx, quad_ctx, self.seed).expect("scene was created");
 
             Transition::ToMainMenu(Box::new(game))
         })
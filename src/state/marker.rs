@@ -15,6 +15,8 @@ pub struct GameOverState;
 pub struct SettingsState;
 #[derive(Debug, Clone)]
 pub struct TitleScreenState;
+#[derive(Debug, Clone)]
+pub struct AnalysisState;
 
 impl GameStateMarker for MainMenuState {}
 impl GameStateMarker for PreparePlayerState {}
@@ -23,3 +25,4 @@ impl GameStateMarker for PausedState {}
 impl GameStateMarker for GameOverState {}
 impl GameStateMarker for SettingsState {}
 impl GameStateMarker for TitleScreenState {}
+impl GameStateMarker for AnalysisState {}
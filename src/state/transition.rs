@@ -12,4 +12,5 @@ pub enum Transition {
     ToGameOver(Box<dyn Scene<State = GameOverState>>),
     //ToSettings(Box<dyn Scene<State = SettingsState>>),
     ToTitleScreen(Box<dyn Scene<State = TitleScreenState>>),
+    ToAnalysis(Box<dyn Scene<State = AnalysisState>>),
 }